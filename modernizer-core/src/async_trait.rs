@@ -0,0 +1,78 @@
+/// ----------------------------------------------------
+/// `#[async_trait]` → 네이티브 async fn in trait
+/// ----------------------------------------------------
+/// Rust 1.75부터 트레이트에 `async fn`을 직접 쓸 수 있으므로 `#[async_trait]` 속성을 지울 수 있습니다.
+/// 다만 네이티브 async 메서드가 있는 트레이트는 `dyn Trait`로 쓸 수 없고, 반환하는 퓨처에 `Send` 경계가 붙지 않습니다.
+/// 파일 안에서 트레이트 객체로 쓰는 트레이트를 찾고, 속성 인자(`?Send`)로 `Send` 경계가 있었는지 판정합니다.
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use std::collections::BTreeSet;
+use syn::{
+    visit::{self, Visit},
+    Attribute, Item, ItemMod, Meta, Type,
+};
+
+/// 파일에서 트레이트 객체(`dyn Trait`, `Box<Trait>`)로 쓰는 트레이트 이름 (`traits`: 파일에서 선언한 트레이트)
+pub(crate) fn trait_objects(file: &syn::File, traits: &BTreeSet<String>) -> BTreeSet<String> {
+    let mut finder = TraitObjects { traits, found: BTreeSet::new() };
+    finder.visit_file(file);
+    finder.found
+}
+
+struct TraitObjects<'a> {
+    traits: &'a BTreeSet<String>,
+    found: BTreeSet<String>,
+}
+
+impl<'ast> Visit<'ast> for TraitObjects<'_> {
+    fn visit_type(&mut self, i: &'ast Type) {
+        // 타입 위치에 쓴 트레이트 이름은 `dyn` 없이 쓴 트레이트 객체
+        let paths: Vec<&syn::Path> = match i {
+            Type::TraitObject(object) => object
+                .bounds
+                .iter()
+                .filter_map(|bound| match bound {
+                    syn::TypeParamBound::Trait(bound) => Some(&bound.path),
+                    _ => None,
+                })
+                .collect(),
+            Type::Path(path) if path.qself.is_none() => vec![&path.path],
+            _ => Vec::new(),
+        };
+        for path in paths {
+            if let Some(name) = path.segments.last().map(|last| last.ident.to_string()).filter(|name| self.traits.contains(name)) {
+                self.found.insert(name);
+            }
+        }
+        visit::visit_type(self, i);
+    }
+}
+
+/// 속성이 만드는 퓨처에 `Send` 경계가 붙는지 (`#[async_trait]`은 true, `#[async_trait(?Send)]`는 false)
+/// 그 밖의 인자는 네이티브 async fn으로 옮길 수 없으므로 이유를 반환합니다.
+pub(crate) fn requires_send(attr: &Attribute) -> Result<bool, String> {
+    match &attr.meta {
+        Meta::Path(_) => Ok(true),
+        Meta::List(list) if list.tokens.to_string().replace(' ', "") == "?Send" => Ok(false),
+        _ => Err(format!("unsupported attribute arguments `{}`", attr.meta.to_token_stream())),
+    }
+}
+
+/// `use` 선언 밖의 아이템(안쪽 모듈 포함)이 `name`이라는 이름을 쓰는지
+pub(crate) fn mentions(items: &[Item], name: &str) -> bool {
+    items.iter().any(|item| match item {
+        Item::Use(_) => false,
+        Item::Mod(ItemMod { content: Some((_, content)), attrs, .. }) => {
+            attrs.iter().any(|attr| has_ident(attr.to_token_stream(), name)) || mentions(content, name)
+        }
+        item => has_ident(item.to_token_stream(), name),
+    })
+}
+
+fn has_ident(tokens: TokenStream, name: &str) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == name,
+        TokenTree::Group(group) => has_ident(group.stream(), name),
+        _ => false,
+    })
+}
@@ -0,0 +1,59 @@
+/// ----------------------------------------------------
+/// `dyn` 없는 `Fn(...)` 트레이트 객체의 위치 찾기
+/// ----------------------------------------------------
+/// syn은 타입 위치의 `Fn(u32) -> u32` 표기를 `dyn`이나 `impl` 없이는 파싱하지 못하므로(`&Fn(u32)`, `Box<FnMut()>`),
+/// 파싱 전에 토큰만 보고 `dyn `을 넣을 위치를 찾습니다. 트레이트 경계(`F: Fn()`, `+ Fn()`)와 구분하기 위해
+/// 바로 앞이 `&`, `&mut`, `&'a`, `<`, `*const`, `*mut`인 경우만 트레이트 객체로 봅니다.
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+
+const FN_TRAITS: &[&str] = &["Fn", "FnMut", "FnOnce"];
+
+/// `dyn `을 넣을 바이트 위치 (소스 순서). 토큰으로 나눌 수 없는 소스면 빈 목록
+pub(crate) fn insertion_points(source: &str) -> Vec<usize> {
+    let mut points = Vec::new();
+    if let Ok(tokens) = source.parse::<TokenStream>() {
+        scan(tokens, &mut points);
+    }
+    points.sort_unstable();
+    points
+}
+
+fn scan(tokens: TokenStream, points: &mut Vec<usize>) {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Group(group) => scan(group.stream(), points),
+            TokenTree::Ident(ident) if FN_TRAITS.iter().any(|name| ident == name) => {
+                let called = matches!(tokens.get(index + 1), Some(TokenTree::Group(args)) if args.delimiter() == Delimiter::Parenthesis);
+                if called && in_type_position(&tokens[..index]) {
+                    points.push(ident.span().byte_range().start);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 앞선 토큰이 참조/포인터 타입이나 제네릭 인자의 시작인지 여부
+fn in_type_position(before: &[TokenTree]) -> bool {
+    let punct = |token: Option<&TokenTree>, c: char| matches!(token, Some(TokenTree::Punct(punct)) if punct.as_char() == c);
+    let ident = |token: Option<&TokenTree>, name: &str| matches!(token, Some(TokenTree::Ident(ident)) if ident == name);
+    let mut rest = before.iter().rev();
+    let mut previous = rest.next();
+    if ident(previous, "const") || ident(previous, "mut") {
+        let qualifier = previous;
+        previous = rest.next();
+        if punct(previous, '*') {
+            return true;
+        }
+        if ident(qualifier, "const") {
+            return false;
+        }
+    }
+    // 수명 `'a`는 `'`와 식별자 두 토큰
+    if matches!(previous, Some(TokenTree::Ident(_))) && punct(rest.clone().next(), '\'') {
+        rest.next();
+        previous = rest.next();
+    }
+    punct(previous, '&') || (punct(previous, '<') && !ident(rest.next(), "impl"))
+}
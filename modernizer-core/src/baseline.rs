@@ -0,0 +1,99 @@
+/// ----------------------------------------------------
+/// 기준선 파일 (--baseline / --use-baseline)
+/// ----------------------------------------------------
+/// 현재 남아 있는 변환(레거시 패턴)을 기록해 두고, 이후 실행에서는 기준선에 없는 새 변환만 보고/적용합니다.
+/// 줄 번호는 코드를 고칠 때마다 바뀌므로 (파일, 규칙, 감싸는 함수, 원본 코드)로 변환을 식별하고 건수를 셉니다.
+use crate::FileReport;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+/// 기준선 파일 형식 버전
+const BASELINE_VERSION: u32 = 1;
+
+/// 기준선에 기록한 변환 하나 (같은 위치 정보를 가진 변환이 여러 건이면 count로 묶음)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BaselineEntry {
+    pub file: PathBuf,
+    pub rule_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+    /// 원본 노드의 소스 코드 (보고서의 `original`과 같은 한 줄 형식)
+    pub original: String,
+    pub count: u32,
+}
+
+/// 변환을 식별하는 키 (규칙 ID, 감싸는 함수, 원본 코드)
+pub(crate) type BaselineKey = (String, Option<String>, String);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    version: u32,
+    findings: Vec<BaselineEntry>,
+}
+
+impl Baseline {
+    /// 보고서의 변환을 모두 기준선으로 기록합니다.
+    pub fn from_reports(reports: &[FileReport]) -> Self {
+        let mut counts: BTreeMap<(PathBuf, BaselineKey), u32> = BTreeMap::new();
+        for report in reports {
+            for change in &report.changes {
+                let key = (change.rule_id.clone(), change.function.clone(), change.original.clone());
+                *counts.entry((normalize(&report.path), key)).or_insert(0) += 1;
+            }
+        }
+        let findings = counts
+            .into_iter()
+            .map(|((file, (rule_id, function, original)), count)| BaselineEntry { file, rule_id, function, original, count })
+            .collect();
+        Baseline { version: BASELINE_VERSION, findings }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path).with_context(|| format!("Failed to read baseline file: {}", path.display()))?;
+        let baseline: Baseline = serde_json::from_str(&json)
+            .with_context(|| format!("Failed to parse baseline file: {}. Check JSON format.", path.display()))?;
+        if baseline.version != BASELINE_VERSION {
+            bail!("Unsupported baseline version {} in {} (expected {})", baseline.version, path.display(), BASELINE_VERSION);
+        }
+        Ok(baseline)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).with_context(|| "Failed to serialize baseline.")?;
+        fs::write(path, json + "\n").with_context(|| format!("Failed to write baseline file: {}", path.display()))
+    }
+
+    pub fn findings(&self) -> &[BaselineEntry] {
+        &self.findings
+    }
+
+    /// 기록된 변환 건수
+    pub fn len(&self) -> u32 {
+        self.findings.iter().map(|entry| entry.count).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// 파일 하나에 해당하는 기준선 항목 (변환 키 → 남은 건수)
+    pub(crate) fn for_file(&self, file: &Path) -> BTreeMap<BaselineKey, u32> {
+        let file = normalize(file);
+        let mut remaining = BTreeMap::new();
+        for entry in self.findings.iter().filter(|entry| normalize(&entry.file) == file) {
+            *remaining.entry((entry.rule_id.clone(), entry.function.clone(), entry.original.clone())).or_insert(0) += entry.count;
+        }
+        remaining
+    }
+}
+
+/// `./src/a.rs`와 `src/a.rs`를 같은 파일로 보도록 `.` 경로 요소를 지웁니다. (`\` 구분자도 `/`로 통일)
+fn normalize(path: &Path) -> PathBuf {
+    let path: PathBuf = path.components().filter(|component| *component != Component::CurDir).collect();
+    PathBuf::from(path.to_string_lossy().replace('\\', "/"))
+}
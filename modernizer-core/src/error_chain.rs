@@ -0,0 +1,224 @@
+/// ----------------------------------------------------
+/// `error_chain! { ... }` → thiserror 오류 열거형
+/// ----------------------------------------------------
+/// `error_chain!` 본문(`types`, `links`, `foreign_links`, `errors`)을 읽어 thiserror derive를 붙인 오류 열거형과
+/// `Msg` 변형의 `From<&str>`/`From<String>` 구현, `Result` 별칭을 만듭니다.
+/// links와 foreign_links는 `#[error(transparent)]`와 `#[from]`을 붙인 변형이 되고, errors의 인자는 이름 있는 필드가 됩니다.
+/// `ErrorKind`, `ResultExt::chain_err`, 백트레이스처럼 대응하는 것이 없는 부분은 검토 안내로 남깁니다.
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    braced, parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Attribute, Expr, ExprLit, Ident, Item, Lit, LitStr, Path, Token, Type, Variant,
+};
+
+/// `error_chain!` 본문
+pub(crate) struct ErrorChain {
+    /// `types { Error, ErrorKind, ResultExt, Result; }`의 오류 타입과 Result 별칭 이름
+    error: Ident,
+    result: Ident,
+    links: Vec<Link>,
+    foreign_links: Vec<Link>,
+    errors: Vec<ChainError>,
+    /// `skip_msg_variant`가 없으면 `Msg(String)` 변형을 만듦
+    msg_variant: bool,
+}
+
+/// `links`/`foreign_links`의 항목 (`Io(::std::io::Error) #[cfg(unix)];`)
+struct Link {
+    attrs: Vec<Attribute>,
+    name: Ident,
+    ty: Type,
+}
+
+/// `errors`의 항목 (`Name(field: T) { description("...") display("...", field) }`)
+struct ChainError {
+    attrs: Vec<Attribute>,
+    name: Ident,
+    fields: Vec<(Ident, Type)>,
+    description: Option<Expr>,
+    display: Option<Punctuated<Expr, Token![,]>>,
+}
+
+impl Parse for ErrorChain {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut chain = ErrorChain {
+            error: Ident::new("Error", input.span()),
+            result: Ident::new("Result", input.span()),
+            links: Vec::new(),
+            foreign_links: Vec::new(),
+            errors: Vec::new(),
+            msg_variant: true,
+        };
+        while !input.is_empty() {
+            let section: Ident = input.parse()?;
+            if section == "skip_msg_variant" {
+                chain.msg_variant = false;
+                continue;
+            }
+            let content;
+            braced!(content in input);
+            match section.to_string().as_str() {
+                "types" => {
+                    let names = Punctuated::<Ident, Token![,]>::parse_separated_nonempty(&content)?;
+                    content.parse::<Option<Token![;]>>()?;
+                    if names.len() != 4 {
+                        return Err(content.error("`types` must name the Error, ErrorKind, ResultExt and Result types"));
+                    }
+                    chain.error = names[0].clone();
+                    chain.result = names[3].clone();
+                }
+                "links" => chain.links = parse_links(&content, true)?,
+                "foreign_links" => chain.foreign_links = parse_links(&content, false)?,
+                "errors" => {
+                    while !content.is_empty() {
+                        chain.errors.push(content.parse()?);
+                    }
+                }
+                _ => return Err(syn::Error::new(section.span(), format!("unknown section `{}`", section))),
+            }
+        }
+        Ok(chain)
+    }
+}
+
+/// `Name(Type) attrs;` 목록. links는 `Name(other::Error, other::ErrorKind)`처럼 ErrorKind까지 적음
+fn parse_links(input: ParseStream, with_kind: bool) -> syn::Result<Vec<Link>> {
+    let mut links = Vec::new();
+    while !input.is_empty() {
+        let leading = input.call(Attribute::parse_outer)?;
+        let name = input.parse()?;
+        let content;
+        parenthesized!(content in input);
+        let ty = content.parse()?;
+        if with_kind {
+            content.parse::<Token![,]>()?;
+            content.parse::<Path>()?;
+        }
+        let mut attrs = input.call(Attribute::parse_outer)?;
+        attrs.splice(0..0, leading);
+        input.parse::<Token![;]>()?;
+        links.push(Link { attrs, name, ty });
+    }
+    Ok(links)
+}
+
+impl Parse for ChainError {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let name = input.parse()?;
+        let mut fields = Vec::new();
+        if input.peek(syn::token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            let parsed = Punctuated::<(Ident, Type), Token![,]>::parse_terminated_with(&content, |field| {
+                let name = field.parse()?;
+                field.parse::<Token![:]>()?;
+                Ok((name, field.parse()?))
+            })?;
+            fields = parsed.into_iter().collect();
+        }
+        let body;
+        braced!(body in input);
+        let (mut description, mut display) = (None, None);
+        while !body.is_empty() {
+            let key: Ident = body.parse()?;
+            let content;
+            parenthesized!(content in body);
+            match key.to_string().as_str() {
+                "description" => description = Some(content.parse()?),
+                // `display(me) -> ("...")`처럼 자기 자신을 받는 형식은 지원하지 않음
+                "display" => display = Some(Punctuated::parse_terminated(&content)?),
+                _ => return Err(syn::Error::new(key.span(), format!("unsupported `{}` in error `{}`", key, name))),
+            }
+            if body.peek(Token![->]) {
+                return Err(body.error(format!("`display` with a self binding in error `{}` is not supported", name)));
+            }
+        }
+        Ok(ChainError { attrs, name, fields, description, display })
+    }
+}
+
+impl ErrorChain {
+    /// 오류 열거형, `Msg`의 `From` 구현, `Result` 별칭을 만듭니다. (`derive`: 오류 derive 경로, `review`: 열거형에 붙일 검토 안내)
+    /// thiserror 속성으로 옮길 수 없는 display가 있으면 이유를 반환합니다.
+    pub(crate) fn items(&self, derive: &Path, review: Option<Attribute>) -> Result<Vec<Item>, String> {
+        let mut variants: Vec<Variant> = Vec::new();
+        for error in &self.errors {
+            let (attrs, name) = (&error.attrs, &error.name);
+            let message = error.message()?;
+            let fields = error.fields.iter().map(|(field, ty)| quote!(#field: #ty));
+            variants.push(match error.fields.is_empty() {
+                true => syn::parse_quote!(#(#attrs)* #[error(#message)] #name),
+                false => syn::parse_quote!(#(#attrs)* #[error(#message)] #name { #(#fields),* }),
+            });
+        }
+        for Link { attrs, name, ty } in self.links.iter().chain(&self.foreign_links) {
+            variants.push(syn::parse_quote!(#(#attrs)* #[error(transparent)] #name(#[from] #ty)));
+        }
+        if self.msg_variant {
+            variants.push(syn::parse_quote!(#[error("{0}")] Msg(String)));
+        }
+
+        let (error, result) = (&self.error, &self.result);
+        let mut items: Vec<Item> = vec![syn::parse_quote! {
+            #review
+            #[derive(Debug, #derive)]
+            pub enum #error {
+                #(#variants,)*
+            }
+        }];
+        if self.msg_variant {
+            items.push(syn::parse_quote! {
+                impl From<&str> for #error {
+                    fn from(message: &str) -> Self {
+                        #error::Msg(message.to_string())
+                    }
+                }
+            });
+            items.push(syn::parse_quote! {
+                impl From<String> for #error {
+                    fn from(message: String) -> Self {
+                        #error::Msg(message)
+                    }
+                }
+            });
+        }
+        items.push(syn::parse_quote!(pub type #result<T> = ::std::result::Result<T, #error>;));
+        Ok(items)
+    }
+}
+
+impl ChainError {
+    /// `#[error(...)]`의 인자. display가 없으면 description 문자열, 둘 다 없으면 변형 이름을 씁니다.
+    fn message(&self) -> Result<TokenStream, String> {
+        let Some(display) = &self.display else {
+            return match &self.description {
+                Some(Expr::Lit(ExprLit { lit: Lit::Str(description), .. })) => Ok(quote!(#description)),
+                Some(_) => Err(format!("error `{}` has a non-literal description and no display", self.name)),
+                None => {
+                    let name = LitStr::new(&self.name.to_string(), self.name.span());
+                    Ok(quote!(#name))
+                }
+            };
+        };
+        let mut args = display.iter();
+        let Some(format @ Expr::Lit(ExprLit { lit: Lit::Str(_), .. })) = args.next() else {
+            return Err(format!("display of error `{}` does not start with a format string", self.name));
+        };
+        // 서식 인자는 필드 이름만 thiserror의 필드 참조(`.name`)로 옮길 수 있음
+        let fields = args
+            .map(|arg| match arg {
+                Expr::Path(path) => match path.path.get_ident() {
+                    Some(ident) if self.fields.iter().any(|(field, _)| field == ident) => Ok(quote!(.#ident)),
+                    _ => Err(()),
+                },
+                _ => Err(()),
+            })
+            .collect::<Result<Vec<TokenStream>, ()>>()
+            .map_err(|_| format!("display of error `{}` uses arguments other than its fields", self.name))?;
+        Ok(quote!(#format #(, #fields)*))
+    }
+}
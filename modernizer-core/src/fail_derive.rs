@@ -0,0 +1,95 @@
+/// ----------------------------------------------------
+/// `#[derive(Fail)]`의 도우미 속성 → thiserror 속성
+/// ----------------------------------------------------
+/// failure_derive의 `#[fail(display = "...", args)]`는 `#[error("...", args)]`로, `#[fail(cause)]`와 필드의 `#[cause]`는 `#[source]`로 바꿉니다.
+/// 서식 인자는 필드 이름(`_0`, `name`)만 지원하며 thiserror의 필드 참조(`.0`, `.name`)로 옮깁니다.
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{parse_quote, punctuated::Punctuated, Attribute, Expr, ExprLit, Field, Item, Lit, LitStr, Token, Variant};
+
+/// 구조체/열거형과 그 변형, 필드에 붙은 `#[fail(...)]` 속성과 필드에 붙은 `#[cause]` 속성
+pub(crate) fn fail_attrs(item: &mut Item) -> Vec<&mut Attribute> {
+    fn is_fail(attr: &&mut Attribute) -> bool {
+        attr.path().is_ident("fail")
+    }
+    fn field_attrs(field: &mut Field) -> impl Iterator<Item = &mut Attribute> {
+        field.attrs.iter_mut().filter(|attr| attr.path().is_ident("fail") || attr.path().is_ident("cause"))
+    }
+    match item {
+        Item::Struct(item) => item.attrs.iter_mut().filter(is_fail).chain(item.fields.iter_mut().flat_map(field_attrs)).collect(),
+        Item::Enum(item) => item
+            .attrs
+            .iter_mut()
+            .filter(is_fail)
+            .chain(item.variants.iter_mut().flat_map(|Variant { attrs, fields, .. }| {
+                attrs.iter_mut().filter(is_fail).chain(fields.iter_mut().flat_map(field_attrs))
+            }))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// `#[fail(...)]`(또는 `#[cause]`) 속성 하나를 thiserror 속성으로 바꿉니다. 바꿀 수 없으면 이유를 반환합니다.
+pub(crate) fn convert(attr: &Attribute) -> Result<Attribute, String> {
+    let unsupported = || format!("`{}` has no thiserror equivalent", attr.to_token_stream().to_string().replace("# [", "#[").replace(" (", "("));
+    if attr.path().is_ident("cause") {
+        return match attr.meta {
+            syn::Meta::Path(_) => Ok(parse_quote!(#[source])),
+            _ => Err(unsupported()),
+        };
+    }
+    let args = attr.parse_args_with(Punctuated::<Expr, Token![,]>::parse_terminated).map_err(|_| unsupported())?;
+    let mut args = args.into_iter();
+    match args.next() {
+        Some(Expr::Path(cause)) if cause.path.is_ident("cause") && args.len() == 0 => Ok(parse_quote!(#[source])),
+        Some(Expr::Assign(assign)) if matches!(&*assign.left, Expr::Path(left) if left.path.is_ident("display")) => {
+            let Expr::Lit(ExprLit { lit: Lit::Str(format), .. }) = &*assign.right else {
+                return Err("`display` is not a string literal".to_string());
+            };
+            let format = LitStr::new(&field_placeholders(&format.value()), format.span());
+            let fields = args.map(|arg| field_arg(&arg)).collect::<Result<Vec<TokenStream>, String>>()?;
+            Ok(parse_quote!(#[error(#format #(, #fields)*)]))
+        }
+        _ => Err(unsupported()),
+    }
+}
+
+/// 서식 문자열의 `{_0}`처럼 튜플 필드 바인딩을 쓴 자리표시자를 thiserror의 `{0}`으로 바꿉니다.
+fn field_placeholders(format: &str) -> String {
+    let mut output = String::with_capacity(format.len());
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        output.push(c);
+        if c != '{' {
+            continue;
+        }
+        if chars.peek() == Some(&'{') {
+            output.push('{');
+            chars.next();
+            continue;
+        }
+        let rest: String = chars.clone().skip(1).take_while(char::is_ascii_digit).collect();
+        if chars.peek() == Some(&'_') && !rest.is_empty() && matches!(chars.clone().nth(rest.len() + 1), Some('}' | ':')) {
+            chars.next();
+        }
+    }
+    output
+}
+
+/// `display`의 서식 인자(필드 이름)를 thiserror의 필드 참조로 바꿉니다. (`_0` → `.0`, `name` → `.name`)
+fn field_arg(arg: &Expr) -> Result<TokenStream, String> {
+    let ident = match arg {
+        Expr::Path(path) => path.path.get_ident(),
+        _ => None,
+    };
+    let Some(ident) = ident else {
+        return Err(format!("display argument `{}` is not a field name", arg.to_token_stream()));
+    };
+    match ident.to_string().strip_prefix('_').and_then(|index| index.parse::<usize>().ok()) {
+        Some(index) => {
+            let index = syn::Index::from(index);
+            Ok(quote!(.#index))
+        }
+        None => Ok(quote!(.#ident)),
+    }
+}
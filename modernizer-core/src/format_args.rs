@@ -0,0 +1,182 @@
+/// ----------------------------------------------------
+/// 서식 문자열의 위치 인자 → 캡처한 식별자
+/// ----------------------------------------------------
+/// `format!("{}", name)`처럼 식별자 하나뿐인 위치 인자를 서식 문자열 안으로 옮깁니다. (`format!("{name}")`, Rust 1.58+)
+/// 원본 리터럴의 토큰 텍스트를 고치므로 이스케이프와 raw 문자열 표기는 그대로 남고, 남은 위치 인자를 가리키는
+/// 자리표시자는 인자가 빠진 만큼 번호를 다시 매깁니다. 너비/정밀도를 인자에서 가져오는 서식(`{:1$}`, `{:.*}`)은 바꾸지 않습니다.
+use syn::Ident;
+
+/// 자리표시자가 가리키는 인자
+enum Arg {
+    /// `{}`: 다음 위치 인자
+    Next,
+    /// `{0}`
+    Index(usize),
+    /// `{name}`: 명명 인자 또는 캡처한 식별자
+    Name(String),
+}
+
+enum Piece {
+    Text(String),
+    Placeholder { arg: Arg, spec: Option<String> },
+}
+
+/// 인자를 옮긴 결과
+pub(crate) struct Inlined {
+    /// 새 서식 문자열 리터럴 (토큰 텍스트)
+    pub(crate) literal: String,
+    /// 지울 위치 인자의 번호 (오름차순)
+    pub(crate) removed: Vec<usize>,
+}
+
+/// 서식 문자열 리터럴(`literal`: 토큰 텍스트, `value`: 값)에서 식별자 위치 인자를 캡처로 바꿉니다.
+/// `positional`은 위치 인자마다 식별자 하나뿐이면 그 이름, `named`는 명명 인자의 이름입니다. 바꿀 수 없으면 이유를 반환합니다.
+pub(crate) fn inline(literal: &str, value: &str, positional: &[Option<String>], named: &[String]) -> Result<Inlined, String> {
+    let (prefix, body, suffix) = split_literal(literal).ok_or_else(|| "format string is not a string literal".to_string())?;
+    let raw = prefix.starts_with('r');
+    let pieces = parse(body, raw)?;
+    // `\x7B`처럼 이스케이프로 쓴 중괄호는 토큰 텍스트로 찾을 수 없음
+    let placeholders = |pieces: &[Piece]| pieces.iter().filter(|piece| matches!(piece, Piece::Placeholder { .. })).count();
+    if parse(value, true).map(|value_pieces| placeholders(&value_pieces)) != Ok(placeholders(&pieces)) {
+        return Err("format string writes braces with escapes".to_string());
+    }
+
+    // 자리표시자마다 가리키는 위치 인자 번호 (명명 인자/캡처는 None)
+    let mut next = 0;
+    let mut targets = Vec::new();
+    for piece in &pieces {
+        let Piece::Placeholder { arg, spec } = piece else { continue };
+        if spec.as_deref().is_some_and(|spec| spec.contains(['$', '*'])) {
+            return Err("width or precision is taken from an argument".to_string());
+        }
+        let target = match arg {
+            Arg::Next => {
+                next += 1;
+                Some(next - 1)
+            }
+            Arg::Index(index) => Some(*index),
+            Arg::Name(_) => None,
+        };
+        if target.is_some_and(|index| index >= positional.len()) {
+            return Err("a placeholder refers to a missing argument".to_string());
+        }
+        targets.push(target);
+    }
+
+    let removed: Vec<usize> = (0..positional.len())
+        .filter(|&index| positional[index].as_ref().is_some_and(|name| !named.contains(name)))
+        .collect();
+    if removed.is_empty() {
+        return Err("no positional argument is a plain identifier".to_string());
+    }
+
+    // 남은 위치 인자의 새 번호. `{}`는 다음 번호와 같을 때만 그대로 두고, 아니면 번호를 적음
+    let mut output = String::from(prefix);
+    let mut next = 0;
+    let mut targets = targets.into_iter();
+    for piece in &pieces {
+        let (arg, spec) = match piece {
+            Piece::Text(text) => {
+                output.push_str(text);
+                continue;
+            }
+            Piece::Placeholder { arg, spec } => (arg, spec),
+        };
+        let name = match (targets.next().flatten(), arg) {
+            (Some(index), _) if removed.contains(&index) => positional[index].clone().unwrap_or_default(),
+            (Some(index), arg) => {
+                let index = index - removed.iter().filter(|&&removed| removed < index).count();
+                if matches!(arg, Arg::Next) && index == next {
+                    next += 1;
+                    String::new()
+                } else {
+                    index.to_string()
+                }
+            }
+            (None, Arg::Name(name)) => name.clone(),
+            (None, _) => String::new(),
+        };
+        output.push('{');
+        output.push_str(&name);
+        if let Some(spec) = spec {
+            output.push(':');
+            output.push_str(spec);
+        }
+        output.push('}');
+    }
+    output.push_str(suffix);
+    Ok(Inlined { literal: output, removed })
+}
+
+/// 문자열 리터럴 토큰을 여는 부분(`"`, `r#"`), 내용, 닫는 부분으로 나눕니다. (바이트/C 문자열은 None)
+fn split_literal(literal: &str) -> Option<(&str, &str, &str)> {
+    let open = match literal.strip_prefix('r') {
+        Some(rest) => 1 + rest.find('"').filter(|&hashes| rest[..hashes].chars().all(|c| c == '#'))? + 1,
+        None if literal.starts_with('"') => 1,
+        None => return None,
+    };
+    let close = open - literal.starts_with('r') as usize;
+    if literal.len() < open + close {
+        return None;
+    }
+    Some((&literal[..open], &literal[open..literal.len() - close], &literal[literal.len() - close..]))
+}
+
+/// 서식 문자열을 글자와 자리표시자로 나눕니다. (`raw`가 아니면 `\u{...}` 같은 이스케이프를 글자로 건너뜀)
+fn parse(body: &str, raw: bool) -> Result<Vec<Piece>, String> {
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if !raw => {
+                text.push(c);
+                if let Some(escaped) = chars.next() {
+                    text.push(escaped);
+                    if escaped == 'u' {
+                        for c in chars.by_ref() {
+                            text.push(c);
+                            if c == '}' {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+            '{' | '}' if chars.peek() == Some(&c) => {
+                text.push(c);
+                text.push(c);
+                chars.next();
+            }
+            '{' => {
+                let mut inner = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => inner.push(c),
+                        None => return Err("format string has an unclosed `{`".to_string()),
+                    }
+                }
+                pieces.push(Piece::Text(std::mem::take(&mut text)));
+                let (arg, spec) = match inner.split_once(':') {
+                    Some((arg, spec)) => (arg, Some(spec.to_string())),
+                    None => (inner.as_str(), None),
+                };
+                let arg = if arg.is_empty() {
+                    Arg::Next
+                } else if let Ok(index) = arg.parse() {
+                    Arg::Index(index)
+                } else if syn::parse_str::<Ident>(arg).is_ok() {
+                    Arg::Name(arg.to_string())
+                } else {
+                    return Err(format!("unsupported placeholder `{{{}}}`", inner));
+                };
+                pieces.push(Piece::Placeholder { arg, spec });
+            }
+            '}' => return Err("format string has an unmatched `}`".to_string()),
+            c => text.push(c),
+        }
+    }
+    pieces.push(Piece::Text(text));
+    Ok(pieces)
+}
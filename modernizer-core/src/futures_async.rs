@@ -0,0 +1,317 @@
+/// ----------------------------------------------------
+/// futures 0.1 조합자 체인 → `async fn` 본문
+/// ----------------------------------------------------
+/// `Box<Future<Item = T, Error = E>>`(또는 `impl Future<...>`)를 반환하는 함수의 꼬리 식을 `.await` 문장으로 풉니다.
+/// `f.and_then(|x| g(x))`는 `let x = f.await?;` 다음에 `g(x)`를, `map`은 `Ok(...)`을, `map_err`/`or_else`/`then`은
+/// 오류를 가로채는 식을 만듭니다. `future::ok(x)`/`err(e)`는 `Ok(x)`/`Err(e)`, `Box::new(f)`와 `Either::A(f)`는 `f`로 보고,
+/// 그 밖의 식은 std 퓨처라고 가정하고 `.await`합니다. (호출하는 쪽 퓨처의 이식은 검토 대상)
+use proc_macro2::Span;
+use syn::{
+    parse_quote,
+    visit::{self, Visit},
+    visit_mut::{self, VisitMut},
+    Block, Expr, ExprClosure, GenericArgument, Ident, Pat, PathArguments, ReturnType, Stmt, Type, TypeParamBound,
+};
+
+/// `Item`/`Error` 연관 타입을 적은 퓨처 트레이트 경계 (`trait_path`: 트레이트 경로 끝부분, 예: "Future")
+/// `Box<...>` 안의 트레이트 객체와 `impl Future<...>`를 보며, 찾으면 (Item 타입, Error 타입)을 반환합니다.
+pub(crate) fn future_output(output: &ReturnType, trait_path: &str) -> Option<(Type, Type)> {
+    let ReturnType::Type(_, ty) = output else { return None };
+    let ty = match &**ty {
+        Type::Path(path) if path.qself.is_none() && path.path.segments.last().is_some_and(|last| last.ident == "Box") => {
+            let PathArguments::AngleBracketed(args) = &path.path.segments.last()?.arguments else { return None };
+            match args.args.first()? {
+                GenericArgument::Type(inner) if args.args.len() == 1 => inner,
+                _ => return None,
+            }
+        }
+        ty @ Type::ImplTrait(_) => ty,
+        _ => return None,
+    };
+    let bounds: Vec<&syn::Path> = match ty {
+        Type::TraitObject(object) => object.bounds.iter().filter_map(trait_bound_path).collect(),
+        Type::ImplTrait(object) => object.bounds.iter().filter_map(trait_bound_path).collect(),
+        // 2015 에디션의 `Box<Future<Item = T>>`
+        Type::Path(path) if path.qself.is_none() => vec![&path.path],
+        _ => return None,
+    };
+    bounds.into_iter().filter(|path| crate::path_matches(path, trait_path)).find_map(|path| {
+        let PathArguments::AngleBracketed(args) = &path.segments.last()?.arguments else { return None };
+        let assoc = |name: &str| {
+            args.args.iter().find_map(|arg| match arg {
+                GenericArgument::AssocType(assoc) if assoc.ident == name => Some(assoc.ty.clone()),
+                _ => None,
+            })
+        };
+        Some((assoc("Item")?, assoc("Error")?))
+    })
+}
+
+fn trait_bound_path(bound: &TypeParamBound) -> Option<&syn::Path> {
+    match bound {
+        TypeParamBound::Trait(bound) => Some(&bound.path),
+        _ => None,
+    }
+}
+
+/// 변환한 함수 본문
+pub(crate) struct AsyncBody {
+    pub(crate) block: Block,
+    /// std 퓨처라고 가정하고 `.await`한 식
+    pub(crate) awaited: Vec<Expr>,
+    /// `async move { ... }.await`로 감싼 식이 있는지 (블록 안 `?`의 오류 타입을 추론하지 못할 수 있음)
+    pub(crate) scoped: bool,
+}
+
+/// 함수 본문의 꼬리 식과 `return` 식을 `Result`를 내는 식으로 바꿉니다. 바꿀 수 없으면 이유를 반환합니다.
+pub(crate) fn convert_body(block: &Block) -> Result<AsyncBody, String> {
+    let mut block = block.clone();
+    let mut returns = ReturnValues { converter: Converter::default(), error: None };
+    returns.visit_block_mut(&mut block);
+    if let Some(error) = returns.error {
+        return Err(error);
+    }
+    let mut converter = returns.converter;
+    let Some(Stmt::Expr(tail, None)) = block.stmts.pop() else {
+        return Err("function body does not end with a future expression".to_string());
+    };
+    let tail = converter.future(&tail)?;
+    block.stmts.extend(tail.stmts);
+    block.stmts.push(Stmt::Expr(tail.tail, None));
+    Ok(AsyncBody { block, awaited: converter.awaited, scoped: converter.scoped })
+}
+
+/// 퓨처 하나를 `.await`한 결과: 앞에 둘 문장과 `Result`를 내는 마지막 식
+struct Seq {
+    stmts: Vec<Stmt>,
+    tail: Expr,
+}
+
+impl Seq {
+    fn value(tail: Expr) -> Self {
+        Seq { stmts: Vec::new(), tail }
+    }
+
+    /// 문장이 있으면 블록으로 감싼 식 (갈래와 클로저 본문 자리)
+    fn into_expr(self) -> Expr {
+        let Seq { stmts, tail } = self;
+        match stmts.is_empty() {
+            true => tail,
+            false => parse_quote!({ #(#stmts)* #tail }),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Converter {
+    awaited: Vec<Expr>,
+    scoped: bool,
+}
+
+impl Converter {
+    fn future(&mut self, expr: &Expr) -> Result<Seq, String> {
+        match expr {
+            Expr::MethodCall(call) => {
+                let method = call.method.to_string();
+                let arg = call.args.first();
+                match (method.as_str(), arg) {
+                    ("and_then", Some(arg)) if call.args.len() == 1 => {
+                        let mut seq = self.future(&call.receiver)?;
+                        let (pat, body) = closure(arg, "value", &method)?;
+                        let value = seq.tail;
+                        seq.stmts.push(parse_quote!(let #pat = #value?;));
+                        let next = self.future(&body)?;
+                        seq.stmts.extend(next.stmts);
+                        seq.tail = next.tail;
+                        Ok(seq)
+                    }
+                    ("map", Some(arg)) if call.args.len() == 1 => {
+                        let mut seq = self.future(&call.receiver)?;
+                        let (pat, body) = closure(arg, "value", &method)?;
+                        let value = seq.tail;
+                        seq.stmts.push(parse_quote!(let #pat = #value?;));
+                        seq.tail = parse_quote!(Ok(#body));
+                        Ok(seq)
+                    }
+                    ("map_err", Some(arg)) if call.args.len() == 1 => {
+                        let receiver = self.future(&call.receiver)?;
+                        let result = postfix(self.result(receiver));
+                        Ok(Seq::value(parse_quote!(#result.map_err(#arg))))
+                    }
+                    ("from_err", None) => {
+                        let receiver = self.future(&call.receiver)?;
+                        let result = postfix(self.result(receiver));
+                        Ok(Seq::value(parse_quote!(#result.map_err(From::from))))
+                    }
+                    ("or_else", Some(arg)) if call.args.len() == 1 => {
+                        let receiver = self.future(&call.receiver)?;
+                        let result = self.result(receiver);
+                        let (pat, body) = closure(arg, "error", &method)?;
+                        let recovered = self.future(&body)?.into_expr();
+                        Ok(Seq::value(parse_quote!(match #result { Ok(value) => Ok(value), Err(#pat) => #recovered })))
+                    }
+                    ("then", Some(arg)) if call.args.len() == 1 => {
+                        let receiver = self.future(&call.receiver)?;
+                        let result = self.result(receiver);
+                        let (pat, body) = closure(arg, "result", &method)?;
+                        let mut seq = self.future(&body)?;
+                        seq.stmts.insert(0, parse_quote!(let #pat = #result;));
+                        Ok(seq)
+                    }
+                    _ => Ok(self.awaited(expr)),
+                }
+            }
+            Expr::Call(call) => {
+                let Expr::Path(func) = &*call.func else { return Ok(self.awaited(expr)) };
+                let names: Vec<String> = func.path.segments.iter().map(|segment| segment.ident.to_string()).collect();
+                let parent = names.len().checked_sub(2).map(|index| names[index].as_str());
+                let args = &call.args;
+                match (parent, names.last().map(String::as_str), args.first()) {
+                    // `Result`도 0.1의 IntoFuture
+                    (None, Some("Ok" | "Err"), _) => Ok(Seq::value(expr.clone())),
+                    (None | Some("future"), Some("ok" | "finished"), _) => Ok(Seq::value(parse_quote!(Ok(#args)))),
+                    (None | Some("future"), Some("err" | "failed"), _) => Ok(Seq::value(parse_quote!(Err(#args)))),
+                    (Some("future"), Some("result"), Some(result)) if args.len() == 1 => Ok(Seq::value(result.clone())),
+                    (Some("Box"), Some("new"), Some(inner)) | (Some("Either"), Some("A" | "B" | "Left" | "Right"), Some(inner))
+                        if args.len() == 1 =>
+                    {
+                        self.future(inner)
+                    }
+                    _ => Ok(self.awaited(expr)),
+                }
+            }
+            Expr::Paren(paren) => self.future(&paren.expr),
+            // 반환 식은 ReturnValues가 이미 바꿈
+            Expr::Return(_) => Ok(Seq::value(expr.clone())),
+            Expr::Block(block) if block.label.is_none() && block.attrs.is_empty() => {
+                let mut block = block.clone();
+                self.block(&mut block.block)?;
+                Ok(Seq::value(Expr::Block(block)))
+            }
+            Expr::If(expr_if) => {
+                let mut expr_if = expr_if.clone();
+                self.block(&mut expr_if.then_branch)?;
+                let Some((_, else_branch)) = &mut expr_if.else_branch else {
+                    return Err("an `if` without `else` does not produce a future".to_string());
+                };
+                **else_branch = self.future(else_branch)?.into_expr();
+                Ok(Seq::value(Expr::If(expr_if)))
+            }
+            Expr::Match(expr_match) => {
+                let mut expr_match = expr_match.clone();
+                for arm in &mut expr_match.arms {
+                    *arm.body = self.future(&arm.body)?.into_expr();
+                }
+                Ok(Seq::value(Expr::Match(expr_match)))
+            }
+            _ => Ok(self.awaited(expr)),
+        }
+    }
+
+    /// 블록의 꼬리 식을 바꿉니다. (블록 안의 문장은 그대로 두어 변수 범위를 유지)
+    fn block(&mut self, block: &mut Block) -> Result<(), String> {
+        let Some(Stmt::Expr(tail, None)) = block.stmts.pop() else {
+            return Err("a block does not end with a future expression".to_string());
+        };
+        let seq = self.future(&tail)?;
+        block.stmts.extend(seq.stmts);
+        block.stmts.push(Stmt::Expr(seq.tail, None));
+        Ok(())
+    }
+
+    /// 오류를 가로채는 조합자(`map_err`, `or_else`, `then`) 앞의 식. 문장 안의 `?`가 함수 전체에서 반환하지 않도록
+    /// 문장이 있으면 async 블록으로 감쌈
+    fn result(&mut self, seq: Seq) -> Expr {
+        let Seq { stmts, tail } = seq;
+        if stmts.is_empty() {
+            return tail;
+        }
+        self.scoped = true;
+        parse_quote!(async move { #(#stmts)* #tail }.await)
+    }
+
+    /// std 퓨처라고 가정하고 `.await`
+    fn awaited(&mut self, expr: &Expr) -> Seq {
+        self.awaited.push(expr.clone());
+        let expr = postfix(expr.clone());
+        Seq::value(parse_quote!(#expr.await))
+    }
+}
+
+/// 뒤에 `.method()`나 `.await`를 붙일 수 있는 식 (아니면 괄호로 감쌈)
+fn postfix(expr: Expr) -> Expr {
+    match expr {
+        Expr::MethodCall(_) | Expr::Call(_) | Expr::Path(_) | Expr::Field(_) | Expr::Macro(_) | Expr::Index(_) | Expr::Paren(_) | Expr::Await(_) => expr,
+        _ => parse_quote!((#expr)),
+    }
+}
+
+/// 조합자 인자를 (매개변수 패턴, 본문)으로 나눕니다. 클로저가 아닌 함수는 `name`으로 받아 호출합니다. (`and_then(parse)` → `parse(value)`)
+fn closure(arg: &Expr, name: &str, method: &str) -> Result<(Pat, Expr), String> {
+    let Expr::Closure(ExprClosure { inputs, body, asyncness: None, .. }) = arg else {
+        return match arg {
+            Expr::Path(_) => {
+                let name = Ident::new(name, Span::call_site());
+                Ok((parse_quote!(#name), parse_quote!(#arg(#name))))
+            }
+            _ => Err(format!("the argument of `{}` is not a closure or function path", method)),
+        };
+    };
+    if inputs.len() != 1 {
+        return Err(format!("the closure passed to `{}` does not take exactly one argument", method));
+    }
+    let mut finder = Returns::default();
+    finder.visit_expr(body);
+    if !finder.values.is_empty() {
+        return Err(format!("the closure passed to `{}` uses `return`", method));
+    }
+    Ok((inputs[0].clone(), (**body).clone()))
+}
+
+/// 클로저, async 블록, 안쪽 아이템 밖의 `return` 식
+#[derive(Default)]
+struct Returns<'ast> {
+    values: Vec<Option<&'ast Expr>>,
+}
+
+impl<'ast> Visit<'ast> for Returns<'ast> {
+    fn visit_expr_return(&mut self, i: &'ast syn::ExprReturn) {
+        self.values.push(i.expr.as_deref());
+        visit::visit_expr_return(self, i);
+    }
+
+    fn visit_expr_closure(&mut self, _: &'ast ExprClosure) {}
+
+    fn visit_expr_async(&mut self, _: &'ast syn::ExprAsync) {}
+
+    fn visit_item(&mut self, _: &'ast syn::Item) {}
+}
+
+/// 클로저, async 블록, 안쪽 아이템 밖의 `return` 식의 값을 바꿉니다.
+struct ReturnValues {
+    converter: Converter,
+    error: Option<String>,
+}
+
+impl VisitMut for ReturnValues {
+    fn visit_expr_mut(&mut self, i: &mut Expr) {
+        let Expr::Return(expr_return) = i else {
+            visit_mut::visit_expr_mut(self, i);
+            return;
+        };
+        let Some(value) = &mut expr_return.expr else {
+            self.error = Some("function body has a `return` without a value".to_string());
+            return;
+        };
+        match self.converter.future(value) {
+            Ok(seq) => **value = seq.into_expr(),
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    fn visit_expr_closure_mut(&mut self, _: &mut ExprClosure) {}
+
+    fn visit_expr_async_mut(&mut self, _: &mut syn::ExprAsync) {}
+
+    fn visit_item_mut(&mut self, _: &mut syn::Item) {}
+}
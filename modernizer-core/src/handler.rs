@@ -0,0 +1,134 @@
+/// ----------------------------------------------------
+/// 사용자 정의 규칙 처리기 (RuleHandler)
+/// ----------------------------------------------------
+/// 규칙 파일의 `handler`에 이름을 지정하면 replacement_template 대신 등록된 처리기가 대체 식을 만듭니다.
+/// 처리기는 Rust 코드로 직접 등록(`HandlerRegistry::register`)하거나, 플러그인 디렉터리의 실행 파일로 등록합니다.
+/// 실행 파일 플러그인은 규칙 ID를 인자로, 매칭된 식의 코드를 표준 입력으로 받아 대체 식 코드를 표준 출력에 씁니다.
+/// (출력이 비어 있으면 적용하지 않음, 0이 아닌 종료 코드는 오류)
+/// `.wasm`/`.wat` 파일은 WebAssembly 모듈로 실행합니다. (`wasm` 기능, 호스트 API는 wasm.rs 참고)
+use crate::ModernizerRule;
+use anyhow::{bail, Context, Result};
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::Arc,
+};
+use syn::Expr;
+
+/// 매칭된 노드에서 대체 식을 만드는 처리기
+pub trait RuleHandler: Send + Sync {
+    /// 매칭된 식(하위 노드 변환이 반영된 상태)의 대체 식을 반환합니다. None이면 적용하지 않음
+    fn replace(&self, rule: &ModernizerRule, node: &Expr) -> Result<Option<Expr>>;
+}
+
+/// `Fn(&ModernizerRule, &Expr) -> Result<Option<Expr>>` 클로저도 처리기로 등록할 수 있습니다.
+impl<F> RuleHandler for F
+where
+    F: Fn(&ModernizerRule, &Expr) -> Result<Option<Expr>> + Send + Sync,
+{
+    fn replace(&self, rule: &ModernizerRule, node: &Expr) -> Result<Option<Expr>> {
+        self(rule, node)
+    }
+}
+
+/// 이름 → 처리기 목록. 여러 파일을 병렬로 변환할 수 있도록 처리기는 공유됩니다.
+#[derive(Clone, Default)]
+pub struct HandlerRegistry {
+    handlers: BTreeMap<String, Arc<dyn RuleHandler>>,
+}
+
+impl fmt::Debug for HandlerRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.handlers.keys()).finish()
+    }
+}
+
+impl HandlerRegistry {
+    /// 처리기를 등록합니다. 같은 이름이 이미 있으면 교체합니다.
+    pub fn register(&mut self, name: impl Into<String>, handler: impl RuleHandler + 'static) {
+        self.handlers.insert(name.into(), Arc::new(handler));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn RuleHandler> {
+        self.handlers.get(name).map(|handler| &**handler)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.handlers.contains_key(name)
+    }
+
+    /// 디렉터리의 실행 파일과 WebAssembly 모듈을 (확장자를 뺀 파일 이름으로) 모두 등록합니다.
+    pub fn load_dir(&mut self, dir: &Path) -> Result<()> {
+        let entries = fs::read_dir(dir).with_context(|| format!("Failed to read plugins directory: {}", dir.display()))?;
+        let mut programs: Vec<PathBuf> = entries
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<_, _>>()
+            .with_context(|| format!("Failed to read plugins directory: {}", dir.display()))?;
+        programs.retain(|path| path.is_file() && !path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.')));
+        programs.sort();
+        for program in programs {
+            let Some(name) = program.file_stem().map(|stem| stem.to_string_lossy().to_string()) else { continue };
+            if self.contains(&name) {
+                bail!("Duplicate handler `{}` in plugins directory {}", name, dir.display());
+            }
+            match program.extension().and_then(|extension| extension.to_str()) {
+                Some("wasm" | "wat") => self.register_wasm(name, &program)?,
+                _ => self.register(name, ExternalHandler { program }),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl HandlerRegistry {
+    #[cfg(feature = "wasm")]
+    fn register_wasm(&mut self, name: String, path: &Path) -> Result<()> {
+        self.register(name, crate::wasm::WasmHandler::load(path)?);
+        Ok(())
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    fn register_wasm(&mut self, _name: String, path: &Path) -> Result<()> {
+        bail!("{}: WebAssembly plugins require the `wasm` feature of modernizer-core", path.display())
+    }
+}
+
+/// 플러그인 디렉터리의 실행 파일 처리기
+struct ExternalHandler {
+    program: PathBuf,
+}
+
+impl RuleHandler for ExternalHandler {
+    fn replace(&self, rule: &ModernizerRule, node: &Expr) -> Result<Option<Expr>> {
+        let code = crate::expr_code(node);
+        let mut child = Command::new(&self.program)
+            .arg(&rule.id)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to run handler plugin {}", self.program.display()))?;
+
+        // 출력이 파이프 버퍼보다 크면 쓰기와 읽기가 서로를 기다리므로 표준 입력은 별도 스레드에서 씀
+        let mut stdin = child.stdin.take().with_context(|| "Failed to open handler plugin stdin")?;
+        let output = std::thread::scope(|scope| {
+            scope.spawn(move || std::io::Write::write_all(&mut stdin, code.as_bytes()));
+            child.wait_with_output()
+        })
+        .with_context(|| format!("Failed to read output of handler plugin {}", self.program.display()))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("handler plugin {} failed: {}", self.program.display(), stderr.trim());
+        }
+
+        let replacement = String::from_utf8(output.stdout).with_context(|| "handler plugin output is not valid UTF-8")?;
+        if replacement.trim().is_empty() {
+            return Ok(None);
+        }
+        syn::parse_str(&replacement)
+            .map(Some)
+            .with_context(|| format!("handler plugin {} printed an invalid expression: {}", self.program.display(), replacement.trim()))
+    }
+}
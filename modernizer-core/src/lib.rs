@@ -0,0 +1,5238 @@
+//! Rust 레거시 코드 현대화 엔진
+//!
+//! JSON 규칙(`modernizer_rules.json`)에 따라 syn AST를 변환합니다.
+//! `rust_modernizer` CLI는 이 크레이트 위에 파일 탐색, 보고서, 캐시, 감시 모드를 얹은 얇은 래퍼입니다.
+//!
+//! ```no_run
+//! use modernizer_core::{default_rules, Modernizer, TransformOptions};
+//!
+//! let rules = default_rules()?;
+//! let options = TransformOptions { annotations: None, ..TransformOptions::default() };
+//! let result = Modernizer::new(rules).with_options(options).modernize_str("fn main() { let x = foo().unwrap(); }")?;
+//! if let Some(code) = result.code {
+//!     println!("{}", code);
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+mod async_trait;
+mod bare_fn;
+mod baseline;
+mod error_chain;
+mod fail_derive;
+mod format_args;
+mod futures_async;
+mod handler;
+mod macro_use;
+mod pattern;
+mod std_renames;
+mod suppress;
+mod template;
+mod validate;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+pub use baseline::{Baseline, BaselineEntry};
+pub use handler::{HandlerRegistry, RuleHandler};
+pub use validate::{validate_rules, RuleIssue};
+
+use anyhow::{bail, Context, Result};
+use proc_macro2::Span;
+use quote::{quote, ToTokens};
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::{self, Write as _},
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+    process,
+    sync::RwLock,
+};
+use syn::{
+    ext::IdentExt,
+    parse::ParseStream,
+    parse_quote,
+    spanned::Spanned,
+    visit_mut::{self, VisitMut},
+    punctuated::Punctuated,
+    Attribute, Block, Expr, ExprBinary, ExprCall, ExprMatch, ExprClosure, ExprLit, ExprMacro, ExprMethodCall, ExprPath, Field, GenericArgument, Ident,
+    ImplItem, ImplItemFn, Item, ItemExternCrate, ItemFn, ItemImpl, ItemMacro, ItemMod, ItemStatic, ItemTrait, ItemUse, Lit, LitStr, Pat,
+    PatRange, PathArguments, PathSegment, RangeLimits, ReturnType, Signature, StaticMutability, Stmt, Token, TraitBound, TraitBoundModifier, TraitItem, TraitItemFn, Type,
+    TypeParamBound, TypePath, TypeTraitObject, UseTree, Variant, Visibility,
+};
+use baseline::BaselineKey;
+use pattern::Pattern;
+use suppress::Suppressions;
+use template::Captures;
+use serde::{Deserialize, Serialize};
+
+/// 진행 로그를 받는 함수. 설정하지 않으면 라이브러리는 로그를 출력하지 않습니다.
+static LOG_SINK: RwLock<Option<fn(&str)>> = RwLock::new(None);
+
+/// 진행 로그(`⚙️ Modernizing...`, `[EXPLAIN]` 등)를 받을 함수를 설정합니다. (None: 출력하지 않음)
+pub fn set_log_sink(sink: Option<fn(&str)>) {
+    if let Ok(mut current) = LOG_SINK.write() {
+        *current = sink;
+    }
+}
+
+#[doc(hidden)]
+pub fn emit_log(args: fmt::Arguments) {
+    if let Some(sink) = LOG_SINK.read().ok().and_then(|sink| *sink) {
+        sink(&args.to_string());
+    }
+}
+
+/// 진행 로그를 `set_log_sink`로 설정한 함수에 보냅니다.
+#[macro_export]
+macro_rules! log {
+    () => {
+        $crate::emit_log(format_args!(""))
+    };
+    ($($arg:tt)*) => {
+        $crate::emit_log(format_args!($($arg)*))
+    };
+}
+
+/// ----------------------------------------------------
+/// 0. 상수 및 규칙 모델 정의
+/// ----------------------------------------------------
+/// 바이너리에 내장된 기본 규칙 (`generate-rules`로 내보내는 내용, 패키지에 포함되도록 크레이트 디렉터리에 둠)
+const DEFAULT_RULES_JSON: &str = include_str!("../modernizer_rules.json");
+/// 변환 결과 또는 규칙 파일 검증 실패 (EXIT_INVALID)
+#[derive(Debug)]
+pub struct ValidationError(pub String);
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// 규칙 처리기가 대체 식을 만들지 않음 (오류가 아니라 건너뜀)
+#[derive(Debug)]
+struct NoReplacement;
+
+impl std::fmt::Display for NoReplacement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("handler produced no replacement")
+    }
+}
+
+impl std::error::Error for NoReplacement {}
+
+/// 규칙의 심각도 (위험도가 높을수록 큼: info < warning < error)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// 안전한 변환 (✅)
+    Info,
+    /// 수동 검토가 권장되는 변환 (⚠️)
+    Warning,
+    /// 위험하여 반드시 검토해야 하는 변환 (❌)
+    Error,
+}
+
+impl Severity {
+    /// 기존 규칙 파일의 level_icon에서 심각도를 유추합니다.
+    fn from_icon(icon: &str) -> Self {
+        match icon {
+            "❌" => Severity::Error,
+            "⚠️" | "⚠" => Severity::Warning,
+            _ => Severity::Info,
+        }
+    }
+
+    /// 보고서 출력용 아이콘
+    pub fn icon(self) -> &'static str {
+        match self {
+            Severity::Info => "✅",
+            Severity::Warning => "⚠️",
+            Severity::Error => "❌",
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// 규칙이 매칭할 인자 개수 조건 (JSON에서는 `1` 또는 `">=1"`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(try_from = "ArgsCountRepr", into = "ArgsCountRepr")]
+pub enum ArgsCount {
+    /// 정확히 N개
+    Exact(u8),
+    /// N개 이상 (가변 인자)
+    AtLeast(u8),
+}
+
+/// ArgsCount의 JSON 표현 (숫자 또는 문자열)
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum ArgsCountRepr {
+    Number(u8),
+    Text(#[schemars(regex(pattern = r"^\s*>=\s*\d+\s*$"))] String),
+}
+
+impl TryFrom<ArgsCountRepr> for ArgsCount {
+    type Error = String;
+
+    fn try_from(repr: ArgsCountRepr) -> Result<Self, Self::Error> {
+        let text = match repr {
+            ArgsCountRepr::Number(count) => return Ok(ArgsCount::Exact(count)),
+            ArgsCountRepr::Text(text) => text,
+        };
+        let parse = |count: &str| {
+            count.trim().parse::<u8>().map_err(|_| format!("invalid args_count `{}` (expected N or \">=N\")", text))
+        };
+        match text.trim().strip_prefix(">=") {
+            Some(count) => parse(count).map(ArgsCount::AtLeast),
+            None => parse(&text).map(ArgsCount::Exact),
+        }
+    }
+}
+
+impl From<ArgsCount> for ArgsCountRepr {
+    fn from(args_count: ArgsCount) -> Self {
+        match args_count {
+            ArgsCount::Exact(count) => ArgsCountRepr::Number(count),
+            ArgsCount::AtLeast(count) => ArgsCountRepr::Text(format!(">={}", count)),
+        }
+    }
+}
+
+impl ArgsCount {
+    fn matches(self, found: usize) -> bool {
+        match self {
+            ArgsCount::Exact(count) => found == count as usize,
+            ArgsCount::AtLeast(count) => found >= count as usize,
+        }
+    }
+}
+
+impl std::fmt::Display for ArgsCount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArgsCount::Exact(count) => write!(f, "{}", count),
+            ArgsCount::AtLeast(count) => write!(f, ">={}", count),
+        }
+    }
+}
+
+/// 규칙이 요구하는 수신자 타입 (`Option`에 `?`를 붙이면 Result 반환 함수에서 타입 오류가 나는 경우 방지)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiverKind {
+    /// 수신자와 무관하게 적용 (기본값)
+    #[default]
+    Any,
+    /// 수신자가 Result로 추정될 때만 적용
+    Result,
+    /// 수신자가 Option으로 추정되면 건너뜀 (추정 불가는 적용)
+    NotOption,
+    /// 수신자가 `self`이면 건너뜀 (`Display::fmt` 안의 `self.description()` → `self.to_string()`처럼 자기 자신을 부르는 변환 방지)
+    NotSelf,
+}
+
+/// 위치별 인자 조건 (JSON: `"any"`, `"any_literal"`, `{"literal": 0}`, `{"call": ["Vec::new"]}`)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ArgMatcher {
+    /// 어떤 식이든 허용
+    Any,
+    /// 리터럴이면 허용 (`-1`처럼 부호가 붙은 숫자 포함)
+    AnyLiteral,
+    /// 값이 일치하는 리터럴만 허용 (숫자: 정수/실수 값, 문자열: 문자열/문자 리터럴 값, 불리언). 접미사(`0usize`)는 무시
+    Literal(serde_json::Value),
+    /// 인자 없는 함수 호출 중 경로가 목록의 하나와 일치할 때만 허용 (경로 끝부분 비교, 제네릭 무시. 예: `Vec::<u8>::new()`)
+    Call(Vec<String>),
+}
+
+impl ArgMatcher {
+    fn matches(&self, arg: &Expr) -> bool {
+        match self {
+            ArgMatcher::Any => true,
+            ArgMatcher::AnyLiteral => literal_of(arg).is_some(),
+            ArgMatcher::Literal(expected) => literal_of(arg).is_some_and(|(negative, lit)| {
+                literal_equals(negative, lit, expected)
+            }),
+            ArgMatcher::Call(paths) => match arg {
+                Expr::Call(call) if call.args.is_empty() => {
+                    matches!(&*call.func, Expr::Path(func) if paths.iter().any(|path| path_matches(&func.path, path)))
+                }
+                _ => false,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for ArgMatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArgMatcher::Any => write!(f, "any expression"),
+            ArgMatcher::AnyLiteral => write!(f, "any literal"),
+            ArgMatcher::Literal(expected) => write!(f, "literal {}", expected),
+            ArgMatcher::Call(paths) => write!(f, "a no-argument call to one of `{}`", paths.join("`, `")),
+        }
+    }
+}
+
+/// 식이 (부호가 붙을 수 있는) 리터럴이면 (음수 여부, 리터럴)을 반환합니다.
+fn literal_of(expr: &Expr) -> Option<(bool, &Lit)> {
+    match expr {
+        Expr::Lit(expr_lit) => Some((false, &expr_lit.lit)),
+        Expr::Unary(unary) if matches!(unary.op, syn::UnOp::Neg(_)) => match &*unary.expr {
+            Expr::Lit(ExprLit { lit: lit @ (Lit::Int(_) | Lit::Float(_)), .. }) => Some((true, lit)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn literal_equals(negative: bool, lit: &Lit, expected: &serde_json::Value) -> bool {
+    let sign = if negative { -1.0 } else { 1.0 };
+    match (lit, expected) {
+        (Lit::Int(int), serde_json::Value::Number(number)) => match (int.base10_parse::<i128>(), number.as_i64()) {
+            (Ok(value), Some(expected)) => (if negative { -value } else { value }) == i128::from(expected),
+            _ => int.base10_parse::<f64>().is_ok_and(|value| Some(value * sign) == number.as_f64()),
+        },
+        (Lit::Float(float), serde_json::Value::Number(number)) => {
+            float.base10_parse::<f64>().is_ok_and(|value| Some(value * sign) == number.as_f64())
+        }
+        (Lit::Str(lit_str), serde_json::Value::String(expected)) => !negative && lit_str.value() == *expected,
+        (Lit::Char(lit_char), serde_json::Value::String(expected)) => !negative && lit_char.value().to_string() == *expected,
+        (Lit::Bool(lit_bool), serde_json::Value::Bool(expected)) => !negative && lit_bool.value == *expected,
+        _ => false,
+    }
+}
+
+/// `#[test]` 함수 안에서 규칙을 어떻게 적용할지 결정 (테스트는 대개 Result를 반환하지 않음)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TestBehavior {
+    /// 일반 코드와 동일하게 적용 (기본값)
+    #[default]
+    Apply,
+    /// 테스트 함수 안에서는 적용하지 않음
+    Skip,
+    /// 감싸는 함수가 require_fn_returns를 만족하지 않으면 수신자 코드가 담긴 `.expect("...")`로 대체
+    Expect,
+}
+
+/// 변환의 위험도 (검토자가 주의를 집중할 곳을 고르는 기준: low < medium < high)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum Risk {
+    /// 의미가 바뀌지 않는 기계적 변환 (예: cloned → copied)
+    Low,
+    /// 문맥에 따라 동작이 달라질 수 있는 변환 (예: unwrap → ?)
+    Medium,
+    /// 반드시 수동 검토가 필요한 변환 (예: mem::uninitialized → MaybeUninit)
+    High,
+}
+
+impl Risk {
+    /// risk가 명시되지 않은 규칙은 심각도에서 유추합니다.
+    fn from_severity(severity: Severity) -> Self {
+        match severity {
+            Severity::Info => Risk::Low,
+            Severity::Warning => Risk::Medium,
+            Severity::Error => Risk::High,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Risk::Low => "low",
+            Risk::Medium => "medium",
+            Risk::High => "high",
+        }
+    }
+}
+
+/// Rust 버전 (규칙의 min_rust_version, 프로젝트의 rust-version 비교용. 예: "1.80", "1.80.1")
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct RustVersion {
+    pub major: u64,
+    pub minor: u64,
+    /// 생략하면 0
+    pub patch: u64,
+}
+
+impl std::str::FromStr for RustVersion {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid Rust version `{}` (expected e.g. 1.80 or 1.80.1)", text);
+        let numbers = text
+            .trim()
+            .split('.')
+            .map(|part| part.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid())?;
+        match numbers[..] {
+            [major, minor] => Ok(RustVersion { major, minor, patch: 0 }),
+            [major, minor, patch] => Ok(RustVersion { major, minor, patch }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl TryFrom<String> for RustVersion {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, String> {
+        text.parse()
+    }
+}
+
+impl From<RustVersion> for String {
+    fn from(version: RustVersion) -> Self {
+        version.to_string()
+    }
+}
+
+impl std::fmt::Display for RustVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.patch {
+            0 => write!(f, "{}.{}", self.major, self.minor),
+            patch => write!(f, "{}.{}.{}", self.major, self.minor, patch),
+        }
+    }
+}
+
+/// Rust 에디션 (규칙의 min_edition, 대상 크레이트의 edition 비교용. 예: "2018")
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+pub enum Edition {
+    #[serde(rename = "2015")]
+    E2015,
+    #[serde(rename = "2018")]
+    E2018,
+    #[serde(rename = "2021")]
+    E2021,
+    #[serde(rename = "2024")]
+    E2024,
+}
+
+impl Edition {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Edition::E2015 => "2015",
+            Edition::E2018 => "2018",
+            Edition::E2021 => "2021",
+            Edition::E2024 => "2024",
+        }
+    }
+}
+
+impl std::str::FromStr for Edition {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, String> {
+        match text.trim() {
+            "2015" => Ok(Edition::E2015),
+            "2018" => Ok(Edition::E2018),
+            "2021" => Ok(Edition::E2021),
+            "2024" => Ok(Edition::E2024),
+            other => Err(format!("invalid edition `{}` (expected 2015, 2018, 2021 or 2024)", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for Edition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 매칭된 노드를 어떻게 바꿀지 결정하는 규칙 동작
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    /// replacement_template으로 노드 전체를 교체 (기본값)
+    #[default]
+    Replace,
+    /// 수신자와 인자는 유지하고 메서드 이름만 replacement_template으로 변경
+    RenameMethod,
+    /// `#[derive(...)]`의 트레이트 목록을 규칙의 order에 따라 재정렬
+    ReorderDerive,
+    /// 매크로 인자(토큰)는 유지하고 매크로 경로만 replacement_template으로 변경 (예: `println` → `tracing::info`)
+    RenameMacro,
+    /// `#[macro_use] extern crate`를 지우고, 매크로를 호출하는 모듈마다 매크로 하나당 replacement_template의 `use`를 추가
+    /// (ItemExternCrate 전용, 예: `use #crate::#macro;`)
+    ImportMacros,
+    /// derive 트레이트를 replacement_template으로 바꾸고 failure_derive의 `#[fail(...)]` 도우미 속성을
+    /// thiserror의 `#[error(...)]`/`#[source]`로 변환 (ItemDerive 전용, 예: `Fail` → `thiserror::Error`)
+    ThiserrorDerive,
+    /// `error_chain! { ... }`을 replacement_template의 derive를 붙인 오류 열거형, `Msg`의 `From` 구현, `Result` 별칭으로 변환
+    /// (ItemMacro 전용, 예: `thiserror::Error`)
+    ErrorChainToThiserror,
+    /// 서식 매크로의 식별자 위치 인자를 서식 문자열의 캡처로 옮김 (ExprMacro 전용, replacement_template은 사용하지 않음,
+    /// 예: `println!("{:?}", value)` → `println!("{value:?}")`)
+    InlineFormatArgs,
+    /// futures 0.1 퓨처를 반환하는 함수를 `async fn`으로 바꾸고 조합자 체인을 `.await` 문장으로 풂
+    /// (ItemFn 전용, replacement_template은 사용하지 않음, 예: `.and_then(|x| f(x))` → `let x = ...await?; f(x).await`)
+    FuturesToAsync,
+    /// 트레이트 선언과 impl의 `#[async_trait]`을 지워 네이티브 async fn in trait으로 둠 (ItemTrait 전용, replacement_template은 사용하지 않음)
+    RemoveAsyncTrait,
+}
+
+/// AST 변환을 위한 단일 규칙을 정의하는 구조체 (JSON에서 로드됨)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ModernizerRule {
+    /// 규칙 ID (보고서 및 로그용)
+    pub id: String,
+    /// 매칭할 AST 타입 (ExprMethodCall, ExprCall, ExprMacro, ExprTry, ExprBinary, ExprReturn, ExprMatch, Attribute, Pattern,
+    /// ItemExternCrate, ItemMacro, ItemStatic, ItemDerive, ImplItemFn, Path, TraitObject, PatRange, ExprPath, ItemFn,
+    /// ItemTrait)
+    #[schemars(schema_with = "ast_type_schema")]
+    pub ast_type: String, 
+    /// 매칭할 메서드 이름 (.unwrap, uninitialized 등). ExprCall/ExprMacro는 `mem::uninitialized`처럼 경로 끝부분 지정 가능 (매크로는 `!` 제외)
+    /// ExprTry는 `?` 식을 감싼 호출 이름 (예: "Ok" → `Ok(x?)`), 빈 문자열이면 모든 `x?`
+    /// ExprBinary는 왼쪽 피연산자의 메서드 호출 이름 (예: "len" → `x.len() == 0`)
+    /// ExprReturn은 함수 본문 마지막 `return` 식을 감싼 호출 이름 (예: "Ok" → `return Ok(x);`), 빈 문자열이면 모든 값
+    /// ExprMatch는 값을 그대로 꺼내는 갈래의 변형 이름 ("Ok" → `Ok(v) => v, Err(e) => return Err(e)`, "Some" → `None => return None`)
+    /// Pattern, PatRange는 사용하지 않음 (빈 문자열)
+    /// ItemExternCrate는 `extern crate` 선언의 크레이트 이름, 빈 문자열이면 모든 크레이트
+    /// ItemMacro는 아이템 위치 매크로의 경로 끝부분 (예: "lazy_static")
+    /// ItemStatic은 `static` 타입의 경로 끝부분이며, 파일의 `use`로 들여온 이름은 전체 경로로 풀어서 비교 (예: "once_cell::sync::Lazy")
+    /// ItemDerive는 구조체/열거형의 `#[derive(...)]`에 있는 트레이트 경로 끝부분 (예: "Fail")
+    /// ImplItemFn은 `트레이트::메서드` (예: "Error::description" → `impl Error for X`의 `fn description`)
+    /// Path는 타입, 식, 매크로 경로와 `use` 선언의 앞부분에 일치할 전체 경로 (예: "failure::Error")
+    /// TraitObject는 `dyn` 없이 쓴 트레이트 객체의 (첫) 트레이트 경로 끝부분이며 `use`로 들여온 이름은 전체 경로로 풀어서 비교
+    /// (예: "std::error::Error"), 빈 문자열이면 트레이트로 확실한 것(`+` 경계, `Fn(...)`, 표준 트레이트, 파일에서 선언한 트레이트)
+    /// ItemTrait는 트레이트 선언과 트레이트 impl에 붙은 속성 매크로의 경로 끝부분 (예: "async_trait")
+    /// ItemFn은 함수(트레이트 impl 밖의 메서드 포함) 반환 타입의 퓨처 트레이트 경로 끝부분 (예: "Future" → `Box<Future<Item = T, Error = E>>`)
+    /// ExprPath는 식 위치에 쓴 상수의 전체 경로이며 `use`로 들여온 이름은 전체 경로로 풀어서 비교 (예: "std::sync::atomic::ATOMIC_BOOL_INIT")
+    pub method_name: String, 
+    /// Pattern 전용: 매칭할 식 패턴 (예: `$x.ok().unwrap()`, `std::mem::replace($a, Default::default())`)
+    /// `$name`(또는 `#name`)은 임의의 식과 일치하고 같은 이름의 자리표시자로 템플릿에서 쓸 수 있습니다.
+    /// 같은 이름이 두 번 나오면 같은 코드여야 하며, `$_`는 바인딩하지 않고, 인자 목록 끝의 `$rest...`는 남은 인자 전체,
+    /// 메서드 이름 자리의 `$m`은 메서드 이름과 일치합니다.
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// 매칭할 인자 개수 (숫자: 정확히 일치, ">=N": N개 이상, 생략/null: 개수 무관)
+    /// ItemExternCrate는 `as` 별칭을 인자 하나로 셈 (0: `extern crate foo;`, 1: `extern crate foo as bar;`)
+    /// ItemMacro는 매크로 본문의 `static ref` 선언 개수, ItemStatic은 초기값 호출의 인자 개수 (호출이 아니면 일치하지 않음)
+    #[serde(default)]
+    pub args_count: Option<ArgsCount>,
+    /// 위치별 인자 조건 (예: `[{"literal": 0}]` → 첫 인자가 `0`일 때만). 조건이 없는 뒤쪽 인자는 검사하지 않음
+    /// ExprMacro는 서식 문자열(첫 인자가 문자열 리터럴일 때)을 제외한 인자 기준, ExprBinary는 오른쪽 피연산자 하나 기준
+    #[serde(default)]
+    pub match_args: Vec<ArgMatcher>,
+    /// ExprBinary 전용: 매칭할 연산자 목록 (예: `["!=", ">"]`, 비어 있으면 모든 이항 연산자)
+    #[serde(default)]
+    pub binary_ops: Vec<String>,
+    /// 변환 동작 (replace: 템플릿으로 교체, rename_method: 메서드 이름만 변경)
+    #[serde(default)]
+    pub action: RuleAction,
+    /// 변환 결과 템플릿 (예: "#receiver?", rename_method의 경우 새 메서드 이름, rename_macro의 경우 `!`를 뺀 새 매크로 경로)
+    /// ExprCall 자리표시자: #func, #args, #arg0, #arg1, ...
+    /// ExprMacro 자리표시자: 서식 문자열을 제외한 인자에 대해 #args, #arg0, #arg1, ... (`try!(x)`처럼 서식 문자열이 없으면 모든 인자)
+    /// ExprTry 자리표시자: #inner (`?` 앞의 식), #try (`x?` 전체), 감싼 호출이 있으면 #func
+    /// ExprBinary 자리표시자: #left, #right, 왼쪽 메서드 호출의 #receiver, #method, #args, #arg0, ...
+    /// ExprReturn 자리표시자: #value (반환 식), 감싼 호출이 있으면 #func, #args, #arg0, ... (결과는 본문의 꼬리 식이 됨)
+    /// ExprMatch 자리표시자: #scrutinee (match 대상 식)
+    /// Pattern 자리표시자: pattern의 메타변수 (`$x` → #x)
+    /// ItemExternCrate 자리표시자: #crate (크레이트 이름), 별칭이 있으면 #rename. 결과는 아이템이며 빈 문자열이면 선언을 지움
+    /// (import_macros는 들여올 매크로마다 #crate, #macro (매크로 이름)로 전개)
+    /// ItemMacro 자리표시자: `lazy_static!` 본문의 `static ref` 선언마다 #name, #ty, #init (선언 하나당 아이템 하나로 전개,
+    /// error_chain_to_thiserror는 자리표시자 없이 오류 열거형에 붙일 derive 경로)
+    /// ItemStatic 자리표시자: #name, #ty (선언 타입), #inner_ty (타입의 유일한 제네릭 인자), #init (초기값),
+    /// 초기값이 호출이면 #func, #args, #arg0, ... (ItemMacro/ItemStatic 모두 원래 속성과 가시성을 그대로 붙임)
+    /// ImplItemFn 자리표시자: #self_ty (impl 대상 타입), #body (메서드 본문). 결과 아이템은 impl 뒤에 추가되고 메서드는 impl에서
+    /// 지워지며, 빈 문자열이면 메서드만 지움
+    /// ItemDerive는 새 derive 트레이트 경로, Path는 일치한 앞부분을 대신할 경로 (뒤쪽 세그먼트와 제네릭 인자는 유지).
+    /// Path 규칙의 템플릿이 빈 문자열이면 바꾸지 않고 `use`로 들여온 이름까지 찾아 수동 검토 대상으로 보고
+    /// TraitObject 자리표시자: #bounds (쓰인 그대로의 트레이트 경계, 예: `dyn #bounds`). 결과는 타입
+    /// PatRange는 `...`로 쓴 범위 패턴의 연산자를 대신할 범위 연산자 (예: `..=`)
+    /// ExprPath는 상수를 대신할 식이며 상수와 같은 모듈의 이름으로 씀. 상수를 모듈 경로와 함께 썼으면 결과의 맨 앞 경로에
+    /// 같은 모듈 경로가 붙고 (`atomic::ATOMIC_BOOL_INIT` → `atomic::AtomicBool::new(false)`), 상수를 들여온 `use`는 그 이름을 들여옴
+    /// ExprMethodCall 자리표시자: #receiver, #inner_receiver, #method (메서드 이름), #args, #arg0, #arg1, ...,
+    /// 수신자 체인의 호출 인자 #recv_args, #recv_arg0, ... (두 단계 안쪽은 #recv_recv_arg0)
+    /// 모든 식 규칙: #original (매칭된 식), 문자열 리터럴 안의 `{original}` (원본 소스 텍스트)
+    /// 자리표시자는 `$receiver`처럼 `$`로도 쓸 수 있고, 목록 자리표시자는 `$args...`처럼 `...`를 붙여도 됩니다.
+    pub replacement_template: String,
+    /// 대체 식을 replacement_template 대신 만들 처리기 이름 (`HandlerRegistry`에 등록된 Rust 처리기 또는
+    /// --plugins-dir의 실행 파일 이름). 지정하면 replacement_template은 무시되며 빈 문자열이어도 됩니다.
+    /// (fallback_template은 그대로 템플릿으로 전개, action이 replace인 식 규칙에만 적용)
+    #[serde(default)]
+    pub handler: Option<String>,
+    /// (구버전 규칙 파일 호환) severity가 없을 때 심각도를 유추할 아이콘 ("✅", "⚠️", "❌").
+    /// 로그와 보고서의 아이콘은 severity로 정해집니다.
+    #[serde(default)]
+    pub level_icon: String,
+    /// 심각도: info (스타일 개선), warning (검토 권장), error (건전하지 않은 레거시 패턴). 생략 시 level_icon에서 유추
+    #[serde(default)]
+    pub severity: Option<Severity>,
+    /// 변환 위험도 (생략 시 severity에서 유추)
+    #[serde(default)]
+    pub risk: Option<Risk>,
+    /// 공식 문서 URL (parse_quote 내부에서 직접 참조 불가)
+    /// 로드 시 `$VAR`, `${VAR}`가 전개됩니다. (예: "${MODERNIZER_DOC_BASE}/unwrap_to_try", `$$`는 `$`)
+    pub doc_url: String,
+    /// 특수 패턴 매칭을 위한 플래그 (예: ok().unwrap() 매칭 시 "ok")
+    pub nested_method: Option<String>, 
+    /// 감싸는 함수의 반환 타입 조건 (예: "Result" → Result를 반환하는 함수 안에서만 적용)
+    #[serde(default)]
+    pub require_fn_returns: Option<String>,
+    /// require_fn_returns 조건이 맞지 않을 때 건너뛰는 대신 적용할 대체 템플릿 (자리표시자는 replacement_template과 동일,
+    /// action과 무관하게 노드 전체를 교체. 예: `#receiver.expect(\"TODO: handle error\")`)
+    #[serde(default)]
+    pub fallback_template: Option<String>,
+    /// ExprMacro 전용: 첫 인자(서식 문자열 리터럴)가 이 값과 정확히 일치할 때만 적용 (예: "{}")
+    #[serde(default)]
+    pub format_string: Option<String>,
+    /// 적용될 때 로그와 보고서에 남길 수동 검토 메모 (`{original}`은 원본 소스 텍스트로 전개)
+    #[serde(default)]
+    pub review_note: Option<String>,
+    /// 변환 위치에 검토 안내 주석을 남길지 여부 (생략 시 true, --no-annotations가 우선)
+    /// 주석은 문장/아이템 단위 변환만 남기며, 식 단위 규칙에는 영향이 없습니다. (검토 메모는 review_note 사용)
+    #[serde(default = "default_emit_comment")]
+    pub emit_comment: bool,
+    /// ExprMethodCall 전용: 수신자 타입 조건 (any: 제한 없음, result: Result로 추정될 때만,
+    /// not_option: Option으로 추정되면 건너뜀, not_self: 수신자가 `self`이면 건너뜀). 추정은 `parse()`, `File::open()` 등 이름 기반 휴리스틱입니다.
+    #[serde(default)]
+    pub receiver_kind: ReceiverKind,
+    /// `#[test]` 함수 안에서의 동작 (apply: 그대로 적용, skip: 건너뜀, expect: `.expect("...")`로 대체)
+    #[serde(default)]
+    pub test_behavior: TestBehavior,
+    /// Attribute(derive) 전용: 트레이트 정렬 우선순위 (목록에 없는 트레이트는 원래 순서대로 뒤에 배치)
+    #[serde(default)]
+    pub order: Option<Vec<String>>,
+    /// 변환 결과가 컴파일되는 최소 Rust 버전 (예: "1.80"). 프로젝트의 rust-version이 이보다 낮으면 건너뜀
+    #[serde(default)]
+    #[schemars(with = "Option<String>")]
+    pub min_rust_version: Option<RustVersion>,
+    /// 변환 결과가 유효한 최소 에디션 (예: "2018"). 대상 크레이트의 edition이 이보다 낮으면 건너뜀
+    #[serde(default)]
+    pub min_edition: Option<Edition>,
+    /// false면 기본적으로 꺼져 있고 `--enable-rule <ID>`로만 활성화 (의미가 미묘한 정리 규칙용, 생략 시 true)
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// --audit-file 실행에서 필터로 제외된 규칙의 제외 이유 (실행 중에만 사용, 매칭은 하되 적용하지 않고 감사 기록만 남김)
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub disabled_by: Option<String>,
+}
+
+/// 규칙이 매칭할 수 있는 AST 타입 (JSON Schema의 ast_type 열거값)
+pub(crate) const AST_TYPES: &[&str] = &[
+    "ExprMethodCall", "ExprCall", "ExprMacro", "ExprTry", "ExprBinary", "ExprReturn", "ExprMatch", "Attribute", "Pattern", "ItemExternCrate",
+    "ItemMacro", "ItemStatic", "ItemDerive", "Path", "ImplItemFn", "TraitObject", "PatRange", "ExprPath",
+    "ItemFn", "ItemTrait",
+];
+
+fn ast_type_schema(_generator: &mut SchemaGenerator) -> Schema {
+    schemars::json_schema!({ "type": "string", "enum": AST_TYPES })
+}
+
+/// 규칙 파일 형식 (JSON Schema 생성용). 규칙 배열, 또는 편집기용 `$schema`를 지정할 수 있는 객체
+#[derive(JsonSchema)]
+#[schemars(untagged)]
+#[allow(dead_code)]
+pub enum RulesFile {
+    List(Vec<ModernizerRule>),
+    Document(RulesDocument),
+}
+
+/// `{ "$schema": "...", "rules": [...] }` 형식의 규칙 파일
+#[derive(Deserialize, JsonSchema)]
+pub struct RulesDocument {
+    /// 편집기 자동 완성/검증에 사용할 JSON Schema 경로 (`schema` 서브커맨드로 생성, 변환기는 무시)
+    #[serde(rename = "$schema", default)]
+    pub schema: Option<String>,
+    pub rules: Vec<ModernizerRule>,
+}
+
+/// 규칙 파일 형식 (생략하면 확장자로 판별: `.json`, `.toml`, `.yaml`/`.yml`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum RulesFormat {
+    /// 규칙 배열 또는 `{ "$schema": ..., "rules": [...] }` 객체
+    Json,
+    /// `[[rules]]` 테이블 배열 (템플릿은 `"""` / `'''` 여러 줄 문자열로 작성 가능)
+    Toml,
+    /// 규칙 시퀀스 또는 `rules:` 키를 가진 매핑
+    Yaml,
+}
+
+impl RulesFormat {
+    /// 확장자로 형식을 판별합니다. (알 수 없는 확장자는 None)
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(RulesFormat::Json),
+            "toml" => Some(RulesFormat::Toml),
+            "yaml" | "yml" => Some(RulesFormat::Yaml),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RulesFormat::Json => "JSON",
+            RulesFormat::Toml => "TOML",
+            RulesFormat::Yaml => "YAML",
+        }
+    }
+
+    /// 규칙 파일 내용을 파싱합니다. (문법/스키마 오류는 JSON과 같은 종료 코드가 되도록 ValidationError로 반환)
+    fn parse(&self, contents: &str) -> Result<Vec<ModernizerRule>> {
+        let invalid = |err: &dyn std::fmt::Display| ValidationError(err.to_string());
+        let rules = match self {
+            // 규칙 배열, 또는 `$schema`를 지정한 `{ "rules": [...] }` 객체
+            RulesFormat::Json if contents.trim_start().starts_with('{') => {
+                serde_json::from_str::<RulesDocument>(contents)?.rules
+            }
+            RulesFormat::Json => serde_json::from_str(contents)?,
+            // TOML 문서의 최상위는 항상 테이블
+            RulesFormat::Toml => toml::from_str::<RulesDocument>(contents).map_err(|err| invalid(&err))?.rules,
+            RulesFormat::Yaml => match serde_norway::from_str::<serde_norway::Value>(contents).map_err(|err| invalid(&err))? {
+                value @ serde_norway::Value::Mapping(_) => serde_norway::from_value::<RulesDocument>(value).map_err(|err| invalid(&err))?.rules,
+                value => serde_norway::from_value(value).map_err(|err| invalid(&err))?,
+            },
+        };
+        Ok(rules)
+    }
+}
+
+fn default_emit_comment() -> bool {
+    true
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl ModernizerRule {
+    /// 명시된 severity가 없으면 level_icon에서 유추한 값을 사용합니다.
+    pub fn severity(&self) -> Severity {
+        self.severity.unwrap_or_else(|| Severity::from_icon(&self.level_icon))
+    }
+
+    /// 명시된 risk가 없으면 심각도에서 유추한 값을 사용합니다.
+    pub fn risk(&self) -> Risk {
+        self.risk.unwrap_or_else(|| Risk::from_severity(self.severity()))
+    }
+}
+
+/// 문장/아이템 단위 변환이 변환 위치에 남기는 검토 안내 주석 형식 (식 단위 규칙은 주석을 남기지 않음)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum AnnotationStyle {
+    /// 규칙 ID, 검토 사항, 문서 링크를 포함한 상세 안내
+    Verbose,
+    /// 규칙 ID와 검토 사항만 담은 한 줄 안내
+    Compact,
+}
+
+/// 저장할 파일의 줄바꿈 형식
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum LineEndings {
+    /// 원본 파일의 줄바꿈(LF/CRLF)을 유지
+    Preserve,
+    /// 항상 LF (`\n`)
+    Lf,
+    /// 항상 CRLF (`\r\n`)
+    Crlf,
+}
+
+/// 원본 파일의 BOM, 줄바꿈, 마지막 줄바꿈 여부 (prettyplease 출력에 다시 적용)
+#[derive(Debug, Clone, Copy)]
+struct SourceLayout {
+    bom: bool,
+    crlf: bool,
+    trailing_newline: bool,
+}
+
+impl SourceLayout {
+    /// CRLF와 LF가 섞여 있으면 더 많이 쓰인 쪽을 따릅니다.
+    fn detect(source: &str) -> Self {
+        let crlf_count = source.matches("\r\n").count();
+        let lf_count = source.matches('\n').count() - crlf_count;
+        SourceLayout {
+            bom: source.starts_with('\u{feff}'),
+            crlf: crlf_count > lf_count,
+            trailing_newline: source.ends_with('\n'),
+        }
+    }
+
+    /// LF로 정규화된 변환 결과에 원본 형식을 적용합니다.
+    fn apply(&self, code: &str, line_endings: LineEndings) -> String {
+        let mut code = code.to_string();
+        if !self.trailing_newline {
+            code.truncate(code.trim_end_matches('\n').len());
+        }
+        let crlf = match line_endings {
+            LineEndings::Preserve => self.crlf,
+            LineEndings::Lf => false,
+            LineEndings::Crlf => true,
+        };
+        if crlf {
+            code = code.replace('\n', "\r\n");
+        }
+        if self.bom {
+            code.insert(0, '\u{feff}');
+        }
+        code
+    }
+
+    /// 원본을 부분 치환한 결과에 --line-endings를 적용합니다. (preserve이면 줄바꿈을 건드리지 않고 BOM만 복원)
+    fn apply_spliced(&self, code: &str, line_endings: LineEndings) -> String {
+        match line_endings {
+            LineEndings::Preserve if self.bom => format!("\u{feff}{}", code),
+            LineEndings::Preserve => code.to_string(),
+            _ => self.apply(&code.replace("\r\n", "\n"), line_endings),
+        }
+    }
+}
+
+/// 원본(변환 전) 소스 기준 위치. 줄/열은 1부터 시작하고 바이트 범위는 [byte_start, byte_end) 입니다.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub byte_start: usize,
+    pub byte_end: usize,
+}
+
+impl Location {
+    fn from_span(span: Span) -> Self {
+        let (start, end) = (span.start(), span.end());
+        let bytes = span.byte_range();
+        Location {
+            line: start.line,
+            column: start.column + 1,
+            end_line: end.line,
+            end_column: end.column + 1,
+            byte_start: bytes.start,
+            byte_end: bytes.end,
+        }
+    }
+}
+
+/// 원본 소스의 바이트 범위를 대체 코드로 바꾸는 편집 (--reformat이 아닐 때 파일 전체 재출력 대신 사용)
+#[derive(Debug, Clone)]
+struct SourceEdit {
+    range: Range<usize>,
+    /// prettyplease 서식 그대로의 대체 코드 (여러 줄이면 둘째 줄부터 원본 줄의 들여쓰기를 붙여 넣음)
+    code: String,
+}
+
+impl SourceEdit {
+    fn new(location: &Location, code: String) -> Self {
+        SourceEdit { range: location.byte_start..location.byte_end, code }
+    }
+}
+
+/// 적용된(또는 dry-run에서 적용될) 단일 변환 기록
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedChange {
+    pub rule_id: String,
+    pub severity: Severity,
+    pub risk: Risk,
+    pub location: Location,
+    /// 변환 위치를 감싸는 함수 (`Type::method`, 중첩 함수는 `outer::inner`, 함수 밖이면 None)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review_note: Option<String>,
+    /// 원본 노드의 소스 코드 (한 줄로 정리)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub original: String,
+    /// 원본 노드를 대체한 코드 (한 줄로 정리, 문장 단위 변환은 None)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<String>,
+    /// 원본 서식 보존 출력에 사용할 소스 편집 (문장 단위 변환 등 원본 범위만 바꿀 수 없는 변환은 None)
+    #[serde(skip)]
+    edit: Option<SourceEdit>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub doc_url: String,
+}
+
+impl AppliedChange {
+    /// `original`: 매칭된 노드의 원본 소스 텍스트 (review_note의 `{original}` 전개 및 보고서의 변경 전 코드)
+    fn new(rule: &ModernizerRule, span: Span, original: &str) -> Self {
+        AppliedChange {
+            rule_id: rule.id.clone(),
+            severity: rule.severity(),
+            risk: rule.risk(),
+            location: Location::from_span(span),
+            function: None,
+            review_note: rule.review_note.as_deref().map(|note| template::fill_original(note, original)),
+            original: original.to_string(),
+            replacement: None,
+            edit: None,
+            doc_url: rule.doc_url.clone(),
+        }
+    }
+
+    fn in_function(mut self, function: Option<&str>) -> Self {
+        self.function = function.map(str::to_string);
+        self
+    }
+}
+
+/// 규칙 모양은 일치했지만 조건(반환 타입, 수신자 종류, #[test] 등)이 맞지 않아 건너뛴 노드
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedMatch {
+    pub rule_id: String,
+    pub reason: String,
+    pub location: Location,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+}
+
+/// --audit-file 판정 결과
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditDecision {
+    Applied,
+    Skipped,
+}
+
+/// --audit-file에 JSONL 한 줄로 기록되는 규칙 판정 (규칙 모양과 부분 일치한 모든 노드)
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub file: PathBuf,
+    pub rule_id: String,
+    pub decision: AuditDecision,
+    /// 건너뛴 이유 (반환 타입 조건, 규칙 비활성화, 앞선 규칙이 먼저 변환 등)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub location: Location,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+}
+
+/// 함수 밖(derive 속성, 상수 등)에서 적용된 변환의 함수별 집계 키
+const TOP_LEVEL_FN: &str = "(top-level)";
+
+/// 파일 하나의 변환 결과 요약
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub counts: BTreeMap<String, u32>,
+    /// 위험도별 변환 건수
+    pub risk_counts: BTreeMap<Risk, u32>,
+    /// --by-function: 함수 → 규칙 ID → 건수
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub functions: BTreeMap<String, BTreeMap<String, u32>>,
+    pub changes: Vec<AppliedChange>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<SkippedMatch>,
+    /// --use-baseline: 기준선에 있어 적용/보고하지 않은 변환 건수
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub baselined: u32,
+    /// fail_on_unparseable_output: 변환 결과를 다시 파싱하지 못한 이유와 관련 규칙 적용 내역
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reparse_error: Option<String>,
+}
+
+fn is_zero(count: &u32) -> bool {
+    *count == 0
+}
+
+impl FileReport {
+    /// 파일 하나의 적용/건너뜀 내역과 집계를 사람이 읽는 형식으로 출력합니다.
+    pub fn render_text(&self, output: &mut String) {
+        let location = |location: &Location| format!("{}:{}:{}", self.path.display(), location.line, location.column);
+        let _ = writeln!(output, "\n📄 {}", self.path.display());
+        for skip in &self.skipped {
+            let _ = writeln!(output, "[SKIP] {} skipped: {} ({})", skip.rule_id, skip.reason, location(&skip.location));
+        }
+        for change in &self.changes {
+            let _ = writeln!(output, "[MOD] {} {} applied ({})", change.severity.icon(), change.rule_id, location(&change.location));
+            if let Some(note) = &change.review_note {
+                let _ = writeln!(output, "[REVIEW] {} ({}): {}", change.rule_id, location(&change.location), note);
+            }
+        }
+        if let Some(err) = &self.reparse_error {
+            let _ = writeln!(output, "❌ 변환 결과를 다시 파싱할 수 없습니다: {}", err);
+        }
+        if self.baselined > 0 {
+            let _ = writeln!(output, "📌 기준선에 있는 변환 {}건은 적용하지 않았습니다.", self.baselined);
+        }
+        if self.changes.is_empty() {
+            let _ = writeln!(output, "ℹ️ 코드 변경 사항이 감지되지 않았습니다.");
+            return;
+        }
+
+        let _ = writeln!(output, "📊 변환 보고서:");
+        for (id, count) in &self.counts {
+            let _ = writeln!(output, "  - {} 건 ({})", count, id);
+        }
+        let risk_summary: Vec<String> =
+            self.risk_counts.iter().map(|(risk, count)| format!("{} {}", risk.as_str(), count)).collect();
+        let _ = writeln!(output, "  위험도: {}", risk_summary.join(", "));
+        if !self.functions.is_empty() {
+            let _ = writeln!(output, "  함수별:");
+            for (function, counts) in &self.functions {
+                let summary: Vec<String> = counts.iter().map(|(id, count)| format!("{} {}", id, count)).collect();
+                let _ = writeln!(output, "    {}: {}", function, summary.join(", "));
+            }
+        }
+        // 고위험 변환은 검토자가 놓치지 않도록 위치를 모두 나열
+        for change in self.changes.iter().filter(|change| change.risk == Risk::High) {
+            let _ = writeln!(output, "  🚨 HIGH RISK: {} ({}) — 수동 검토 필요", change.rule_id, location(&change.location));
+        }
+    }
+}
+
+/// ----------------------------------------------------
+/// 2. AST 변환기 정의 (syn::VisitMut)
+/// ----------------------------------------------------
+pub struct Modernizer {
+    changed: bool, 
+    counters: BTreeMap<String, u32>, // 규칙 ID별 카운터
+    changes: Vec<AppliedChange>, // 적용된 변환과 원본 위치
+    rules: Vec<ModernizerRule>, 
+    fn_return_stack: Vec<String>, // 감싸는 함수/클로저들의 반환 타입 이름 (예: "Result", "()", 추론 불가: "_")
+    test_fn_stack: Vec<bool>, // 감싸는 함수들이 `#[test]` 함수인지 여부 (클로저는 바깥 함수를 따름)
+    explain: Option<String>, // --explain 대상 규칙 ID
+    scoped_maybeuninit: bool, // --scoped-maybeuninit: 문장 단위 MaybeUninit 변환 사용
+    annotations: Option<AnnotationStyle>, // 검토 안내 주석 형식 (--no-annotations이면 None)
+    explain_candidates: Cell<usize>, // --explain 대상 규칙과 부분적으로 일치한 노드 수
+    source_path: PathBuf, // 변환 중인 파일 경로 (오류 메시지용)
+    source: String, // 변환 중인 파일의 원본 소스 (BOM 제외, span 바이트 위치 기준)
+    doc_tests: bool, // --doc-tests: 문서 주석의 ```rust 코드 블록도 변환
+    fn_name_stack: Vec<String>, // 감싸는 함수 이름 (`Type::method`, 중첩 함수는 `outer::inner`)
+    audit: Option<RefCell<Vec<AuditEntry>>>, // --audit-file: 규칙별 적용/건너뜀 판정 기록
+    impl_name_stack: Vec<String>, // 감싸는 impl 블록의 타입 / 트레이트 이름
+    skipped: RefCell<Vec<SkippedMatch>>, // 조건이 맞지 않아 건너뛴 노드와 이유
+    review: Option<Review>, // --interactive: 변환마다 적용 여부를 사용자에게 확인
+    restack_guard: bool, // 규칙이 자기 대체 코드에 다시 일치하면 적용하지 않음 (확인용 probe 자신은 false)
+    options: TransformOptions, // 파일 단위 변환 옵션 (반복 횟수, 재출력/서식 방식 등)
+    handlers: HandlerRegistry, // 규칙의 `handler`가 가리키는 사용자 정의 처리기
+    patterns: Vec<Option<Pattern>>, // Pattern 규칙의 컴파일된 패턴 (rules와 같은 순서)
+    baseline: Option<BaselineState>, // --use-baseline: 기준선에 있는 변환은 적용하지 않음
+    suppressions: Suppressions, // `// modernizer:ignore` 주석으로 막은 범위
+    imports: HashMap<String, Option<Vec<Ident>>>, // 파일의 `use`로 들여온 이름 → 전체 경로 (이름이 겹치면 None)
+    traits: BTreeSet<String>, // 파일(인라인 모듈 포함)에서 선언한 트레이트 이름
+    inserted_dyn: BTreeSet<usize>, // 파싱하려고 `Fn(...)` 앞에 미리 넣은 `dyn`의 바이트 위치 (bare_fn)
+    in_trait_impl: bool, // 트레이트 impl 블록 안을 방문 중인지 여부 (메서드 시그니처를 바꿀 수 없음)
+    trait_objects: BTreeSet<String>, // 파일에서 트레이트 객체(`dyn Trait`)로 쓰는 선언된 트레이트 이름
+}
+
+/// --use-baseline 상태 (변환 중인 파일 하나 기준)
+#[derive(Default)]
+struct BaselineState {
+    /// 변환 키 → 아직 가리지 않은 기준선 건수
+    remaining: BTreeMap<BaselineKey, u32>,
+    /// 기준선으로 가린 변환 (규칙 ID, 원본 시작 바이트). 반복 패스(--max-iterations)에서 다시 세지 않음
+    suppressed: BTreeSet<(String, usize)>,
+}
+
+/// --interactive 검토 상태
+#[derive(Default)]
+struct Review {
+    /// `a`(모두 적용) / `q`(나머지 거절) 이후 남은 변환에 일괄 적용할 결정
+    remaining: Option<bool>,
+    /// 거절한 변환 (규칙 ID, 원본 시작 바이트). 반복 패스(--max-iterations)에서 다시 묻지 않음
+    declined: BTreeSet<(String, usize)>,
+}
+
+/// --interactive에서 변환 하나에 대한 사용자 결정
+enum ReviewDecision {
+    Accept,
+    Reject,
+    /// 사용자가 직접 입력한 대체 식
+    Edit(Box<Expr>),
+}
+
+/// 로그용 위치 표기 (줄:열)
+fn format_span(span: Span) -> String {
+    let start = span.start();
+    format!("{}:{}", start.line, start.column + 1)
+}
+
+/// 함수 시그니처의 반환 타입 이름을 구합니다. (`io::Result<T>` → "Result", 반환 타입 없음 → "()")
+fn return_type_name(output: &ReturnType) -> String {
+    match output {
+        ReturnType::Default => "()".to_string(),
+        ReturnType::Type(_, ty) => match &**ty {
+            Type::Path(type_path) => type_path
+                .path
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string())
+                .unwrap_or_default(),
+            Type::Tuple(tuple) if tuple.elems.is_empty() => "()".to_string(),
+            _ => String::new(),
+        },
+    }
+}
+
+/// 클로저의 반환 타입 이름을 구합니다.
+/// 선언되지 않은 경우 본문의 마지막 식이 `Ok(..)`/`Err(..)`이면 "Result", `Some(..)`이면 "Option",
+/// 그 외에는 추론할 수 없으므로 "_"를 반환합니다.
+fn closure_return_type_name(closure: &ExprClosure) -> String {
+    if let ReturnType::Type(..) = closure.output {
+        return return_type_name(&closure.output);
+    }
+
+    let tail = match &*closure.body {
+        Expr::Block(block) => match block.block.stmts.last() {
+            Some(Stmt::Expr(expr, None)) => expr,
+            _ => return "_".to_string(),
+        },
+        body => body,
+    };
+
+    if let Expr::Call(call) = tail {
+        if let Expr::Path(func) = &*call.func {
+            // `Ok::<T, E>(..)`처럼 터보피시가 붙은 경우도 마지막 세그먼트 이름으로 판정
+            match func.path.segments.last().map(|segment| segment.ident.to_string()).as_deref() {
+                Some("Ok" | "Err") => return "Result".to_string(),
+                Some("Some") => return "Option".to_string(),
+                _ => {}
+            }
+        }
+    }
+    "_".to_string()
+}
+
+impl Modernizer {
+    /// 규칙 목록으로 변환기를 만듭니다. 옵션은 `with_options`로 바꿉니다.
+    pub fn new(rules: Vec<ModernizerRule>) -> Self {
+        // 패턴 오류는 규칙 파일 로드 시 보고하므로 여기서는 컴파일되는 패턴만 사용
+        let patterns = rules
+            .iter()
+            .map(|rule| rule.pattern.as_deref().filter(|_| rule.ast_type == "Pattern").and_then(|pattern| Pattern::compile(pattern).ok()))
+            .collect();
+        Modernizer {
+            changed: false,
+            counters: BTreeMap::new(),
+            changes: Vec::new(),
+            rules,
+            fn_return_stack: Vec::new(),
+            test_fn_stack: Vec::new(),
+            explain: None,
+            explain_candidates: Cell::new(0),
+            scoped_maybeuninit: false,
+            annotations: Some(AnnotationStyle::Verbose),
+            source_path: PathBuf::new(),
+            source: String::new(),
+            doc_tests: false,
+            fn_name_stack: Vec::new(),
+            audit: None,
+            impl_name_stack: Vec::new(),
+            skipped: RefCell::default(),
+            review: None,
+            restack_guard: true,
+            options: TransformOptions::default(),
+            handlers: HandlerRegistry::default(),
+            patterns,
+            baseline: None,
+            suppressions: Suppressions::default(),
+            imports: HashMap::new(),
+            traits: BTreeSet::new(),
+            inserted_dyn: BTreeSet::new(),
+            in_trait_impl: false,
+            trait_objects: BTreeSet::new(),
+        }
+    }
+
+    /// 방금 기록한 변환이 `// modernizer:ignore` 주석으로 막힌 위치이면 되돌리고 true를 반환합니다.
+    fn suppress_commented(&mut self, recorded: usize, was_changed: bool) -> bool {
+        let Some(change) = self.changes.get(recorded..).and_then(|changes| changes.last()) else { return false };
+        let location = &change.location;
+        let Some(comment_line) = self.suppressions.find(&change.rule_id, location.line, location.end_line) else { return false };
+        let reason = format!("suppressed by `// modernizer:ignore` at line {}", comment_line);
+        self.skipped.borrow_mut().push(SkippedMatch {
+            rule_id: change.rule_id.clone(),
+            reason: reason.clone(),
+            location: location.clone(),
+            function: change.function.clone(),
+        });
+        self.revert_changes(recorded, was_changed, &reason);
+        true
+    }
+
+    /// --use-baseline: 방금 기록한 변환이 기준선에 있으면 되돌리고 true를 반환합니다.
+    fn suppress_baselined(&mut self, recorded: usize, was_changed: bool) -> bool {
+        let Some(baseline) = &mut self.baseline else { return false };
+        let Some(change) = self.changes.get(recorded..).and_then(|changes| changes.last()) else { return false };
+        let location = (change.rule_id.clone(), change.location.byte_start);
+        if !baseline.suppressed.contains(&location) {
+            let key = (change.rule_id.clone(), change.function.clone(), change.original.clone());
+            match baseline.remaining.get_mut(&key) {
+                Some(count) if *count > 0 => *count -= 1,
+                _ => return false,
+            }
+            baseline.suppressed.insert(location);
+        }
+        self.revert_changes(recorded, was_changed, "recorded in baseline");
+        true
+    }
+
+    /// --interactive: `recorded` 이후 기록된 변환(방금 제안된 변환)을 보여 주고 적용 여부를 묻습니다.
+    /// 거절하면 변환 기록을 되돌립니다. (`was_changed`: 제안 전의 changed 값, `editable`: 식으로 직접 고칠 수 있는지)
+    /// `// modernizer:ignore`로 막거나 --use-baseline으로 가린 변환은 묻지 않고 거절합니다.
+    fn review_change(&mut self, recorded: usize, was_changed: bool, editable: bool) -> ReviewDecision {
+        if self.suppress_commented(recorded, was_changed) || self.suppress_baselined(recorded, was_changed) {
+            return ReviewDecision::Reject;
+        }
+        let Some(review) = &mut self.review else { return ReviewDecision::Accept };
+        let Some(change) = self.changes.get(recorded..).and_then(|changes| changes.last()) else {
+            return ReviewDecision::Accept;
+        };
+        let key = (change.rule_id.clone(), change.location.byte_start);
+
+        let decision = if review.declined.contains(&key) {
+            ReviewDecision::Reject
+        } else if let Some(apply_all) = review.remaining {
+            if apply_all { ReviewDecision::Accept } else { ReviewDecision::Reject }
+        } else {
+            prompt_review(&self.source_path, change, editable, review)
+        };
+
+        if let ReviewDecision::Reject = decision {
+            review.declined.insert(key);
+            self.revert_changes(recorded, was_changed, "declined in interactive review");
+        }
+        if let ReviewDecision::Edit(expr) = &decision {
+            if let Some(change) = self.changes.last_mut() {
+                change.replacement = Some(expr_source(expr));
+                change.edit = Some(SourceEdit::new(&change.location, expr_code(expr)));
+            }
+        }
+        decision
+    }
+
+    /// `recorded` 이후에 기록된 변환을 취소하고 건너뛴 것으로 기록합니다. (`was_changed`: 변환 전의 changed 값)
+    fn revert_changes(&mut self, recorded: usize, was_changed: bool, reason: &str) {
+        for change in self.changes.drain(recorded..) {
+            if let Some(count) = self.counters.get_mut(&change.rule_id) {
+                *count -= 1;
+                if *count == 0 {
+                    self.counters.remove(&change.rule_id);
+                }
+            }
+            if let Some(audit) = &self.audit {
+                audit.borrow_mut().push(AuditEntry {
+                    file: self.source_path.clone(),
+                    rule_id: change.rule_id,
+                    decision: AuditDecision::Skipped,
+                    reason: Some(reason.to_string()),
+                    location: change.location,
+                    function: change.function,
+                });
+            }
+        }
+        self.changed = was_changed;
+    }
+
+    /// 방금 적용한 규칙(`recorded` 이후의 마지막 변환)이 자기 대체 코드에 다시 일치하는지 확인합니다.
+    /// 일치하면 실행할 때마다 같은 변환이 겹겹이 쌓이므로 (예: `x.expect("a")` → `x.expect("a").expect("b")`)
+    /// 변환을 취소하고 건너뛴 것으로 보고합니다. 다른 규칙과의 연쇄 변환은 --max-iterations가 처리합니다.
+    fn guard_restacking(&mut self, recorded: usize, was_changed: bool, new_expr: &Expr) -> bool {
+        if !self.restack_guard {
+            return false;
+        }
+        let Some(change) = self.changes.get(recorded..).and_then(|changes| changes.last()) else { return false };
+        let Some(rule) = self.rules.iter().find(|rule| rule.id == change.rule_id) else { return false };
+
+        let mut probe = Modernizer::new(vec![rule.clone()]);
+        probe.restack_guard = false;
+        probe.fn_return_stack = self.fn_return_stack.clone();
+        probe.test_fn_stack = self.test_fn_stack.clone();
+        probe.fn_name_stack = self.fn_name_stack.clone();
+        probe.impl_name_stack = self.impl_name_stack.clone();
+        probe.handlers = self.handlers.clone();
+        probe.visit_expr_mut(&mut new_expr.clone());
+        if probe.changes.is_empty() {
+            return false;
+        }
+
+        let reason = "replacement matches the rule again (would stack on every run)";
+        self.skipped.borrow_mut().push(SkippedMatch {
+            rule_id: change.rule_id.clone(),
+            reason: reason.to_string(),
+            location: change.location.clone(),
+            function: change.function.clone(),
+        });
+        self.revert_changes(recorded, was_changed, reason);
+        true
+    }
+
+    /// 적용된 변환을 보고서에 기록합니다.
+    fn record_change(&mut self, change: AppliedChange) {
+        self.changed = true;
+        *self.counters.entry(change.rule_id.clone()).or_insert(0) += 1;
+        self.changes.push(change);
+    }
+
+    /// `recorded` 이후에 기록된 변환(방금 적용된 변환)에 대체 코드를 남깁니다. (보고서의 `replacement`)
+    fn record_replacement(&mut self, recorded: usize, expr: &Expr) {
+        if let Some(change) = self.changes.get_mut(recorded..).and_then(|changes| changes.last_mut()) {
+            change.replacement.get_or_insert_with(|| expr_source(expr));
+            if change.edit.is_none() {
+                change.edit = Some(SourceEdit::new(&change.location, expr_code(expr)));
+            }
+        }
+    }
+
+    /// 규칙 모양은 일치했지만 조건이 맞지 않아 건너뛴 노드를 보고서에 기록합니다.
+    fn record_skip(&self, rule: &ModernizerRule, span: Span, reason: &str) {
+        self.skipped.borrow_mut().push(SkippedMatch {
+            rule_id: rule.id.clone(),
+            reason: reason.to_string(),
+            location: Location::from_span(span),
+            function: self.fn_name_stack.last().cloned(),
+        });
+    }
+
+    /// `--explain` 대상 규칙이면 부분 일치한 노드와 판정 결과를 출력합니다.
+    /// --audit-file이면 모든 규칙의 적용/건너뜀 판정을 감사 기록에 남깁니다.
+    fn explain(&self, rule: &ModernizerRule, span: Span, verdict: &str) {
+        if let Some(audit) = &self.audit {
+            let decision = if verdict == "matched" {
+                Some((AuditDecision::Applied, None))
+            } else if let Some(reason) = verdict.strip_prefix("rejected: ") {
+                Some((AuditDecision::Skipped, Some(reason.to_string())))
+            } else if verdict.starts_with("not evaluated: ") {
+                Some((AuditDecision::Skipped, Some(verdict.to_string())))
+            } else {
+                None
+            };
+            if let Some((decision, reason)) = decision {
+                audit.borrow_mut().push(AuditEntry {
+                    file: self.source_path.clone(),
+                    rule_id: rule.id.clone(),
+                    decision,
+                    reason,
+                    location: Location::from_span(span),
+                    function: self.fn_name_stack.last().cloned(),
+                });
+            }
+        }
+        if self.explain.as_deref() == Some(rule.id.as_str()) {
+            self.explain_candidates.set(self.explain_candidates.get() + 1);
+            log!("[EXPLAIN] {} ({}): {}", rule.id, format_span(span), verdict);
+        }
+    }
+
+    /// 앞선 규칙이 노드를 먼저 변환해 뒤쪽 규칙(`--explain` 대상, 감사 기록 시 전부)이 평가되지 못한 경우를 알립니다.
+    fn explain_shadowed(&self, later_rules: &[ModernizerRule], applied: &ModernizerRule, span: Span) {
+        let last_name = |name: &str| name.rsplit("::").next().unwrap_or_default().to_string();
+        let shadowed = later_rules.iter().filter(|rule| {
+            (self.audit.is_some() || self.explain.as_deref() == Some(rule.id.as_str()))
+                && last_name(&rule.method_name) == last_name(&applied.method_name)
+        });
+        for rule in shadowed {
+            self.explain(rule, span, &format!("not evaluated: node already rewritten by earlier rule `{}`", applied.id));
+        }
+    }
+
+    /// --audit-file 실행에서 필터로 제외된 규칙이면 감사 기록을 남기고 true를 반환합니다.
+    fn is_disabled(&self, rule: &ModernizerRule, span: Span) -> bool {
+        match &rule.disabled_by {
+            Some(reason) => {
+                self.explain(rule, span, &format!("rejected: rule disabled ({})", reason));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 템플릿 전개 실패를 규칙 ID, 파일, 원본 노드 위치와 함께 출력합니다. (해당 노드는 변환하지 않음)
+    fn template_error(&self, rule: &ModernizerRule, span: Span, err: anyhow::Error) {
+        if err.is::<NoReplacement>() {
+            self.explain(rule, span, "rejected: handler produced no replacement");
+            return;
+        }
+        // 처리기 오류는 이미 "handler `name` failed"로 감싸져 있음
+        let source = if rule.handler.is_some() { "" } else { " replacement_template" };
+        let err = err.context(format!(
+            "rule `{}`{} failed at {}:{}", rule.id, source, self.source_path.display(), format_span(span)
+        ));
+        log!("[ERR] ❌ {:#}", err);
+        self.explain(rule, span, "rejected: replacement template error");
+    }
+
+    /// `#[test]` 함수 안에서의 규칙 동작을 판정합니다. (테스트 밖이면 Apply)
+    fn test_behavior(&self, rule: &ModernizerRule) -> TestBehavior {
+        match self.test_fn_stack.last() {
+            Some(true) => rule.test_behavior,
+            _ => TestBehavior::Apply,
+        }
+    }
+
+    /// 원본 노드의 소스 텍스트를 구합니다. (템플릿으로 새로 만든 노드는 원본 위치가 없어 None)
+    fn source_text(&self, span: Span) -> Option<&str> {
+        let range = span.byte_range();
+        if range.is_empty() {
+            return None;
+        }
+        self.source.get(range)
+    }
+
+    /// `{original}`에 넣을 원본 노드의 소스 텍스트를 구합니다. (연속된 공백은 한 칸으로 정리)
+    /// 템플릿으로 새로 만든 노드처럼 원본 위치가 없으면 토큰을 문자열로 바꿔 사용합니다.
+    fn original_text(&self, span: Span, node: &impl ToTokens) -> String {
+        match self.source_text(span) {
+            Some(text) => text.split_whitespace().collect::<Vec<_>>().join(" "),
+            None => node.to_token_stream().to_string(),
+        }
+    }
+
+    /// 규칙의 템플릿(replacement_template 또는 fallback_template)을 전개합니다.
+    /// `#original`은 (하위 노드 변환이 반영된) 매칭된 식, 문자열 리터럴 안의 `{original}`은 원본 소스 텍스트입니다.
+    /// 규칙에 `handler`가 있으면 (fallback_template이 아닌) 기본 대체 식을 등록된 처리기로 만듭니다.
+    fn expand_template(
+        &self,
+        rule: &ModernizerRule,
+        template: &str,
+        mut captures: Captures,
+        original: &Expr,
+        span: Span,
+    ) -> Result<Expr> {
+        // select_template은 규칙의 문자열을 그대로 빌려주므로 주소로 기본 템플릿인지 구분
+        let is_primary = std::ptr::eq(template, rule.replacement_template.as_str());
+        if let Some(name) = rule.handler.as_deref().filter(|_| is_primary) {
+            let handler = self.handlers.get(name).with_context(|| format!("handler `{}` is not registered", name))?;
+            let replacement = handler.replace(rule, original).with_context(|| format!("handler `{}` failed", name))?;
+            return replacement.ok_or_else(|| NoReplacement.into());
+        }
+        captures.insert("original".to_string(), template::Capture::Expr(Box::new(original.clone())));
+        let template = template::fill_original_in_strings(template, &self.original_text(span, original));
+        template::expand(&template, &captures)
+    }
+
+    /// test_behavior: expect — 메서드 호출을 수신자 코드가 담긴 `.expect("...")`로 대체합니다.
+    fn expect_in_test(&self, method_call: &ExprMethodCall) -> Expr {
+        let receiver = &method_call.receiver;
+        let message = match self.source_text(receiver.span()) {
+            Some(text) => format!("`{}` failed", text.split_whitespace().collect::<Vec<_>>().join(" ")),
+            None => format!("`.{}()` failed", method_call.method),
+        };
+        parse_quote!(#receiver.expect(#message))
+    }
+
+    /// `require_fn_returns` 조건을 현재 감싸는 함수의 반환 타입과 비교합니다.
+    /// 조건이 충족되지 않으면 건너뛴 이유를 반환합니다.
+    fn check_fn_context(&self, rule: &ModernizerRule) -> Result<(), String> {
+        let Some(required) = rule.require_fn_returns.as_deref() else {
+            return Ok(());
+        };
+
+        match self.fn_return_stack.last() {
+            Some(actual) if actual == required => Ok(()),
+            Some(actual) if actual == "_" => Err(format!("enclosing closure return type unknown, requires `{}`", required)),
+            Some(actual) => Err(format!("enclosing fn/closure returns `{}`, requires `{}`", actual, required)),
+            None => Err(format!("not inside a fn, requires `{}`", required)),
+        }
+    }
+
+    /// `require_fn_returns` 조건에 따라 전개할 템플릿을 고릅니다.
+    /// 조건이 맞지 않으면 fallback_template을, 그것도 없으면 건너뛴 이유를 남기고 None을 반환합니다.
+    fn select_template<'r>(&self, rule: &'r ModernizerRule, span: Span) -> Option<&'r str> {
+        match self.check_fn_context(rule) {
+            Ok(()) => Some(&rule.replacement_template),
+            Err(reason) => match rule.fallback_template.as_deref() {
+                Some(fallback) => {
+                    log!("[FALLBACK] {} {}: {}; using fallback_template ({})", rule.severity().icon(), rule.id, reason, format_span(span));
+                    self.explain(rule, span, &format!("{}; using fallback_template", reason));
+                    Some(fallback)
+                }
+                None => {
+                    self.record_skip(rule, span, &reason);
+                    self.explain(rule, span, &format!("rejected: {}", reason));
+                    None
+                }
+            },
+        }
+    }
+    
+    /// `match_args` 조건을 실제 인자와 비교합니다. 조건이 충족되지 않으면 거부 이유를 반환합니다.
+    fn check_match_args<'a>(rule: &ModernizerRule, args: impl IntoIterator<Item = &'a Expr>) -> Result<(), String> {
+        let mut args = args.into_iter();
+        for (index, matcher) in rule.match_args.iter().enumerate() {
+            match args.next() {
+                Some(arg) if matcher.matches(arg) => {}
+                Some(arg) => {
+                    let found = arg.to_token_stream().to_string();
+                    return Err(format!("match_args[{}] is {}, found `{}`", index, matcher, found));
+                }
+                None => return Err(format!("match_args[{}] is {}, found no argument", index, matcher)),
+            }
+        }
+        Ok(())
+    }
+
+    /// 규칙 템플릿을 기반으로 AST 노드를 생성합니다. (rename_method는 메서드 이름만 교체)
+    fn apply_rule_template(&self, method_call: &ExprMethodCall, rule: &ModernizerRule, span: Span) -> Option<Expr> {
+        if rule.action == RuleAction::RenameMethod {
+            // 메서드 이름만 교체하고 수신자/인자/원래 위치(span)는 유지
+            let mut method = syn::parse_str::<Ident>(&rule.replacement_template).ok()?;
+            method.set_span(method_call.method.span());
+            let mut renamed = method_call.clone();
+            renamed.method = method;
+            return Some(Expr::MethodCall(renamed));
+        }
+
+        // 규칙 ID로 분기하지 않고 모든 규칙을 replacement_template(또는 handler)로 전개
+        self.expand_method_call_template(method_call, rule, &rule.replacement_template, span)
+    }
+
+    /// 메서드 호출 규칙의 템플릿을 런타임에 전개합니다. (실패하면 오류를 출력하고 None)
+    fn expand_method_call_template(
+        &self,
+        method_call: &ExprMethodCall,
+        rule: &ModernizerRule,
+        template: &str,
+        span: Span,
+    ) -> Option<Expr> {
+        let mut captures = Captures::new();
+        captures.insert("receiver".to_string(), template::Capture::Expr(method_call.receiver.clone()));
+        captures.insert("method".to_string(), template::Capture::Ident(method_call.method.clone()));
+        if let Expr::MethodCall(inner_call) = &*method_call.receiver {
+            captures.insert("inner_receiver".to_string(), template::Capture::Expr(inner_call.receiver.clone()));
+        }
+        template::bind_args(&mut captures, &method_call.args);
+        bind_receiver_chain_args(&mut captures, &method_call.receiver);
+        let original = Expr::MethodCall(method_call.clone());
+        match self.expand_template(rule, template, captures, &original, span) {
+            Ok(new_expr) => Some(new_expr),
+            Err(err) => {
+                self.template_error(rule, span, err);
+                None
+            }
+        }
+    }
+    
+    /// 로드된 규칙을 순회하며 메서드 호출을 변환합니다. (`span`: 변환 전 원본 노드 위치)
+    fn transform_method_call(&mut self, method_call: &ExprMethodCall, span: Span) -> Option<Expr> {
+        let method_name = method_call.method.to_string();
+        
+        for (index, rule) in self.rules.iter().enumerate() {
+            if rule.method_name != method_name { continue; }
+            // `x.len() == 0` 형태는 transform_expr_binary에서 평가
+            if rule.ast_type == "ExprBinary" { continue; }
+
+            if rule.ast_type != "ExprMethodCall" {
+                self.explain(rule, span, &format!("rejected: ast_type is `{}`, found ExprMethodCall", rule.ast_type));
+                continue;
+            }
+            if self.is_disabled(rule, span) { continue; }
+
+            if let Some(args_count) = rule.args_count.filter(|args_count| !args_count.matches(method_call.args.len())) {
+                self.explain(rule, span, &format!(
+                    "rejected: args_count is {}, found {} argument(s)", args_count, method_call.args.len()
+                ));
+                continue;
+            }
+            if let Err(reason) = Self::check_match_args(rule, &method_call.args) {
+                self.explain(rule, span, &format!("rejected: {}", reason));
+                continue;
+            }
+
+            if let Some(nested) = rule.nested_method.as_deref() {
+                let found = match &*method_call.receiver {
+                    Expr::MethodCall(inner_call) => format!("receiver is `.{}()`", inner_call.method),
+                    _ => "receiver is not a method call".to_string(),
+                };
+                let is_nested_match = matches!(&*method_call.receiver, Expr::MethodCall(inner_call) if inner_call.method == nested);
+                if !is_nested_match {
+                    self.explain(rule, span, &format!("rejected: nested_method is `{}`, {}", nested, found));
+                    continue;
+                }
+            }
+
+            let receiver_type = guess_receiver_type(&method_call.receiver);
+            let receiver_rejected = match rule.receiver_kind {
+                ReceiverKind::Any => false,
+                ReceiverKind::Result => receiver_type != Some("Result"),
+                ReceiverKind::NotOption => receiver_type == Some("Option"),
+                ReceiverKind::NotSelf => matches!(&*method_call.receiver, Expr::Path(receiver) if receiver.path.is_ident("self")),
+            };
+            if receiver_rejected {
+                let found = if rule.receiver_kind == ReceiverKind::NotSelf { "self" } else { receiver_type.unwrap_or("unknown") };
+                self.record_skip(rule, span, &format!("receiver looks like `{}`", found));
+                self.explain(rule, span, &format!(
+                    "rejected: receiver_kind is `{:?}`, receiver looks like `{}`", rule.receiver_kind, found
+                ));
+                continue;
+            }
+
+            let test_behavior = self.test_behavior(rule);
+            if test_behavior == TestBehavior::Skip {
+                self.record_skip(rule, span, "inside #[test] fn");
+                self.explain(rule, span, "rejected: inside #[test] fn (test_behavior: skip)");
+                continue;
+            }
+            // 테스트 함수가 Result를 반환하면 그대로 적용하고, 아니면 expect로 대체
+            let new_expr = match self.check_fn_context(rule) {
+                Ok(()) => self.apply_rule_template(method_call, rule, span),
+                Err(reason) if test_behavior == TestBehavior::Expect => {
+                    self.explain(rule, span, &format!("{}; using `.expect(...)` (test_behavior: expect)", reason));
+                    Some(self.expect_in_test(method_call))
+                }
+                Err(_) => match self.select_template(rule, span) {
+                    Some(fallback) => self.expand_method_call_template(method_call, rule, fallback, span),
+                    None => continue,
+                },
+            };
+            if let Some(new_expr) = new_expr {
+                self.explain(rule, span, "matched");
+                self.explain_shadowed(&self.rules[index + 1..], rule, span);
+                let original = self.original_text(span, method_call);
+                self.record_change(AppliedChange::new(rule, span, &original).in_function(self.fn_name_stack.last().map(String::as_str)));
+                return Some(new_expr);
+            }
+            self.explain(rule, span, "rejected: template produced no replacement");
+        }
+        None
+    }
+    
+    /// 로드된 규칙을 순회하며 함수 호출을 변환합니다. (경로 + 인자 개수로 매칭, 템플릿으로 교체)
+    fn transform_expr_call(&mut self, expr_call: &ExprCall, span: Span) -> Option<Expr> {
+        let Expr::Path(func) = &*expr_call.func else { return None };
+        let last_segment = func.path.segments.last()?;
+
+        for (index, rule) in self.rules.iter().enumerate() {
+            // 경로의 마지막 이름이 같으면 부분 일치 (--explain 대상)
+            if rule.method_name.rsplit("::").next() != Some(last_segment.ident.to_string().as_str()) { continue; }
+            // `Ok(x?)` 형태는 transform_expr_try에서, 함수 끝의 `return Ok(x);`는 transform_tail_return에서,
+            // match 갈래 패턴의 변형 이름은 transform_expr_match에서 평가
+            if matches!(rule.ast_type.as_str(), "ExprTry" | "ExprReturn" | "ExprMatch") { continue; }
+
+            if rule.ast_type != "ExprCall" {
+                self.explain(rule, span, &format!("rejected: ast_type is `{}`, found ExprCall", rule.ast_type));
+                continue;
+            }
+
+            if !path_matches(&func.path, &rule.method_name) {
+                let found: Vec<String> = func.path.segments.iter().map(|segment| segment.ident.to_string()).collect();
+                self.explain(rule, span, &format!(
+                    "rejected: path is `{}`, found `{}`", rule.method_name, found.join("::")
+                ));
+                continue;
+            }
+            if self.is_disabled(rule, span) { continue; }
+
+            if let Some(args_count) = rule.args_count.filter(|args_count| !args_count.matches(expr_call.args.len())) {
+                self.explain(rule, span, &format!(
+                    "rejected: args_count is {}, found {} argument(s)", args_count, expr_call.args.len()
+                ));
+                continue;
+            }
+            if let Err(reason) = Self::check_match_args(rule, &expr_call.args) {
+                self.explain(rule, span, &format!("rejected: {}", reason));
+                continue;
+            }
+            if self.test_behavior(rule) != TestBehavior::Apply {
+                // expect는 메서드 호출에만 의미가 있으므로 함수/매크로 호출에서는 skip과 동일하게 처리
+                self.record_skip(rule, span, "inside #[test] fn");
+                self.explain(rule, span, "rejected: inside #[test] fn (test_behavior is not apply)");
+                continue;
+            }
+            let Some(template) = self.select_template(rule, span) else { continue };
+
+            let mut captures = Captures::new();
+            captures.insert("func".to_string(), template::Capture::Expr(expr_call.func.clone()));
+            template::bind_args(&mut captures, &expr_call.args);
+
+            let original = Expr::Call(expr_call.clone());
+            match self.expand_template(rule, template, captures, &original, span) {
+                Ok(new_expr) => {
+                    self.explain(rule, span, "matched");
+                    self.explain_shadowed(&self.rules[index + 1..], rule, span);
+                    let original = self.original_text(span, &original);
+                    self.record_change(AppliedChange::new(rule, span, &original).in_function(self.fn_name_stack.last().map(String::as_str)));
+                    return Some(new_expr);
+                }
+                Err(err) => self.template_error(rule, span, err),
+            }
+        }
+        None
+    }
+
+    /// 로드된 규칙을 순회하며 매크로 호출을 변환합니다. (매크로 경로 + 서식 문자열 + 인자 개수로 매칭)
+    /// 방문자는 매크로 토큰 안으로 들어가지 않으므로, 규칙이 일치하면 인자를 먼저 변환한 뒤 템플릿에 넣습니다.
+    /// (`try!(f(try!(g())))` → `f(g()?)?`)
+    fn transform_expr_macro(&mut self, expr_macro: &ExprMacro, span: Span) -> Option<Expr> {
+        // `try`는 2018 에디션부터 예약어라 `r#try!`로 쓰므로 raw 식별자도 같은 이름으로 봄
+        let name = expr_macro.mac.path.segments.last()?.ident.unraw().to_string();
+        let (recorded, was_changed) = (self.changes.len(), self.changed);
+        let mut visited_args: Option<Vec<Expr>> = None;
+
+        for index in 0..self.rules.len() {
+            if self.rules[index].method_name.rsplit("::").next() != Some(name.as_str()) { continue; }
+            let rule = &self.rules[index].clone();
+
+            if rule.ast_type != "ExprMacro" {
+                self.explain(rule, span, &format!("rejected: ast_type is `{}`, found ExprMacro", rule.ast_type));
+                continue;
+            }
+
+            if !path_matches(&expr_macro.mac.path, &rule.method_name) {
+                self.explain(rule, span, &format!("rejected: macro path does not match `{}`", rule.method_name));
+                continue;
+            }
+            if self.is_disabled(rule, span) { continue; }
+            if rule.action == RuleAction::InlineFormatArgs {
+                match self.inline_format_args(rule, expr_macro, span) {
+                    Some(new_expr) => return Some(new_expr),
+                    None => continue,
+                }
+            }
+
+            // 매크로 본문을 쉼표로 구분된 식 목록으로 파싱 (`name = value` 형태의 명명 인자는 거부)
+            let Ok(body) = expr_macro.mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) else {
+                self.explain(rule, span, "rejected: macro body is not a comma-separated expression list");
+                continue;
+            };
+            let mut body = body.into_iter().peekable();
+
+            // 첫 인자가 문자열 리터럴이면 서식 문자열로 보고 인자에서 제외 (`try!(x)`, `vec![a, b]`는 모든 인자를 사용)
+            let format_string = match body.peek() {
+                Some(Expr::Lit(ExprLit { lit: Lit::Str(lit_str), .. })) => {
+                    let value = lit_str.value();
+                    body.next();
+                    Some(value)
+                }
+                _ => None,
+            };
+            if let Some(expected) = rule.format_string.as_deref() {
+                if format_string.as_deref() != Some(expected) {
+                    let found = format_string.map_or("no string literal".to_string(), |found| format!("{:?}", found));
+                    self.explain(rule, span, &format!("rejected: format_string is {:?}, found {}", expected, found));
+                    continue;
+                }
+            }
+
+            let args: Vec<Expr> = body.collect();
+            // 명명 인자는 템플릿 자리표시자로 옮길 수 없음 (rename_macro는 토큰을 그대로 두므로 허용)
+            if rule.action != RuleAction::RenameMacro && args.iter().any(|arg| matches!(arg, Expr::Assign(_))) {
+                self.explain(rule, span, "rejected: named format arguments are not supported");
+                continue;
+            }
+            if let Some(args_count) = rule.args_count.filter(|args_count| !args_count.matches(args.len())) {
+                self.explain(rule, span, &format!(
+                    "rejected: args_count is {}, found {} argument(s)", args_count, args.len()
+                ));
+                continue;
+            }
+            if let Err(reason) = Self::check_match_args(rule, &args) {
+                self.explain(rule, span, &format!("rejected: {}", reason));
+                continue;
+            }
+            if self.test_behavior(rule) != TestBehavior::Apply {
+                // expect는 메서드 호출에만 의미가 있으므로 함수/매크로 호출에서는 skip과 동일하게 처리
+                self.record_skip(rule, span, "inside #[test] fn");
+                self.explain(rule, span, "rejected: inside #[test] fn (test_behavior is not apply)");
+                continue;
+            }
+            let Some(template) = self.select_template(rule, span) else { continue };
+
+            let original = Expr::Macro(expr_macro.clone());
+            let new_expr = if rule.action == RuleAction::RenameMacro {
+                rename_macro(expr_macro, template)
+            } else {
+                let args = visited_args.get_or_insert_with(|| {
+                    let mut args = args;
+                    args.iter_mut().for_each(|arg| self.visit_expr_mut(arg));
+                    args
+                });
+                let mut captures = Captures::new();
+                template::bind_args(&mut captures, args.iter());
+                self.expand_template(rule, template, captures, &original, span)
+            };
+            match new_expr {
+                Ok(new_expr) => {
+                    self.explain(rule, span, "matched");
+                    self.explain_shadowed(&self.rules[index + 1..], rule, span);
+                    let change = AppliedChange::new(rule, span, &self.original_text(span, &original))
+                        .in_function(self.fn_name_stack.last().map(String::as_str));
+                    self.record_change(change);
+                    return Some(new_expr);
+                }
+                Err(err) => self.template_error(rule, span, err),
+            }
+        }
+        // 어떤 규칙도 적용되지 않았다면 인자의 변환은 매크로 토큰에 반영되지 않으므로 취소
+        if visited_args.is_some() && self.changes.len() > recorded {
+            self.revert_changes(recorded, was_changed, "enclosing macro was not rewritten");
+        }
+        None
+    }
+
+    /// inline_format_args: 서식 문자열(첫 문자열 리터럴 인자) 뒤의 위치 인자 중 식별자 하나뿐인 것을 서식 문자열의 캡처로 옮깁니다.
+    /// (`write!(f, "{}", x)` → `write!(f, "{x}")`) 서식 문자열 앞의 인자와 남은 인자는 쓰인 그대로 둡니다.
+    fn inline_format_args(&mut self, rule: &ModernizerRule, expr_macro: &ExprMacro, span: Span) -> Option<Expr> {
+        let Ok(body) = expr_macro.mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) else {
+            self.explain(rule, span, "rejected: macro body is not a comma-separated expression list");
+            return None;
+        };
+        let args: Vec<Expr> = body.into_iter().collect();
+        let format = args.iter().enumerate().find_map(|(position, arg)| match arg {
+            Expr::Lit(ExprLit { lit: Lit::Str(format), .. }) => Some((position, format.clone())),
+            _ => None,
+        });
+        let Some((position, format)) = format else {
+            self.explain(rule, span, "rejected: no format string literal");
+            return None;
+        };
+        let (before, rest) = (&args[..position], &args[position + 1..]);
+        // 위치 인자가 명명 인자(`name = value`)보다 앞에 옴
+        let positional: Vec<Option<String>> = rest.iter().take_while(|arg| !matches!(arg, Expr::Assign(_))).map(captured_ident).collect();
+        let named: Vec<String> = rest
+            .iter()
+            .filter_map(|arg| match arg {
+                Expr::Assign(assign) => match &*assign.left {
+                    Expr::Path(path) => path.path.get_ident().map(Ident::to_string),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+        let inlined = match format_args::inline(&format.token().to_string(), &format.value(), &positional, &named) {
+            Ok(inlined) => inlined,
+            Err(reason) => {
+                self.explain(rule, span, &format!("rejected: {}", reason));
+                return None;
+            }
+        };
+        if self.test_behavior(rule) != TestBehavior::Apply {
+            self.record_skip(rule, span, "inside #[test] fn");
+            self.explain(rule, span, "rejected: inside #[test] fn (test_behavior is not apply)");
+            return None;
+        }
+        let Ok(literal) = syn::parse_str::<LitStr>(&inlined.literal) else {
+            self.explain(rule, span, &format!("rejected: `{}` is not a string literal", inlined.literal));
+            return None;
+        };
+        let kept: Vec<&Expr> = rest.iter().enumerate().filter(|(index, _)| !inlined.removed.contains(index)).map(|(_, arg)| arg).collect();
+        let mut new_macro = expr_macro.clone();
+        new_macro.mac.tokens = quote!(#(#before,)* #literal #(, #kept)*);
+
+        // 원본 위치가 있으면 리터럴과 지운 인자만 바꿔 나머지 인자의 서식을 유지
+        let code = self.source_text(span).and_then(|_| {
+            let (range, literal_range) = (span.byte_range(), format.span().byte_range());
+            let mut code = self.source.get(range.start..literal_range.start)?.to_string();
+            code.push_str(&inlined.literal);
+            let mut end = literal_range.end;
+            for (index, arg) in rest.iter().enumerate() {
+                let arg_end = arg.span().byte_range().end;
+                if !inlined.removed.contains(&index) {
+                    code.push_str(self.source.get(end..arg_end)?);
+                }
+                end = arg_end;
+            }
+            code.push_str(self.source.get(end..range.end)?);
+            // 여러 줄이면 편집을 붙일 때 원본 줄의 들여쓰기가 다시 붙으므로 미리 뺌 (들여쓰기가 다른 줄이 있으면 다시 출력)
+            let line_start = self.source[..range.start].rfind('\n').map_or(0, |newline| newline + 1);
+            let indent: String = self.source[line_start..range.start].chars().take_while(|c| c.is_whitespace()).collect();
+            let lines = code.split('\n').enumerate().map(|(index, line)| match index == 0 || line.is_empty() {
+                true => Some(line),
+                false => line.strip_prefix(indent.as_str()),
+            });
+            Some(lines.collect::<Option<Vec<&str>>>()?.join("\n"))
+        });
+
+        self.explain(rule, span, "matched");
+        let mut change = AppliedChange::new(rule, span, &self.original_text(span, expr_macro))
+            .in_function(self.fn_name_stack.last().map(String::as_str));
+        change.edit = code.map(|code| SourceEdit::new(&change.location, code));
+        self.record_change(change);
+        Some(Expr::Macro(new_macro))
+    }
+
+    /// 로드된 규칙을 순회하며 식 위치의 상수 경로를 템플릿으로 바꿉니다. (`static COUNT: AtomicUsize = ATOMIC_USIZE_INIT;`의 초기값 등)
+    /// 규칙의 경로는 `use`로 들여온 이름을 전체 경로로 풀어서 전체가 같을 때만 일치합니다.
+    fn transform_expr_path(&mut self, expr_path: &ExprPath, span: Span) -> Option<Expr> {
+        if expr_path.qself.is_some() {
+            return None;
+        }
+        let resolved = self.resolve_path(&expr_path.path);
+        for index in 0..self.rules.len() {
+            if self.rules[index].ast_type != "ExprPath" { continue; }
+            let rule = &self.rules[index].clone();
+            if path_prefix_len(&resolved, &rule.method_name) != Some(resolved.segments.len()) { continue; }
+            if self.is_disabled(rule, span) { continue; }
+            let Some(template) = self.select_template(rule, span) else { continue };
+
+            let original = Expr::Path(expr_path.clone());
+            let mut new_expr = match self.expand_template(rule, template, Captures::new(), &original, span) {
+                Ok(new_expr) => new_expr,
+                Err(err) => {
+                    self.template_error(rule, span, err);
+                    continue;
+                }
+            };
+            // 모듈 경로와 함께 쓴 상수는 템플릿의 맨 앞 경로에 같은 모듈 경로를 붙임
+            let module_len = expr_path.path.segments.len() - 1;
+            if module_len > 0 || expr_path.path.leading_colon.is_some() {
+                let Some(head) = head_path(&mut new_expr) else {
+                    self.explain(rule, span, "rejected: replacement_template does not start with a path");
+                    continue;
+                };
+                let segments = expr_path.path.segments.iter().take(module_len).chain(&head.segments).cloned().collect();
+                *head = syn::Path { leading_colon: expr_path.path.leading_colon, segments };
+            }
+
+            self.explain(rule, span, "matched");
+            self.explain_shadowed(&self.rules[index + 1..], rule, span);
+            let change = AppliedChange::new(rule, span, &self.original_text(span, &original))
+                .in_function(self.fn_name_stack.last().map(String::as_str));
+            self.record_change(change);
+            return Some(new_expr);
+        }
+        None
+    }
+}
+
+/// 2018 에디션에서도 extern prelude에 자동으로 들어가지 않아 `extern crate`가 필요한 sysroot 크레이트
+/// (`std`/`core`는 `#![no_std]` 크레이트에서 의미가 있으므로 함께 제외)
+const SYSROOT_CRATES: &[&str] = &["std", "core", "alloc", "proc_macro", "test"];
+
+/// 트레이트 객체로 자주 쓰는 표준 라이브러리 트레이트 (`std::`/`core::`/`alloc::` 다음 경로)
+const STD_TRAITS: &[&str] = &[
+    "any::Any", "error::Error", "fmt::Debug", "fmt::Display", "fmt::Write", "io::BufRead", "io::Read", "io::Seek", "io::Write",
+    "iter::Iterator", "ops::Fn", "ops::FnMut", "ops::FnOnce", "string::ToString",
+];
+
+/// prelude로 들여오는 트레이트 (`use` 없이 이름만 써도 트레이트)
+const PRELUDE_TRAITS: &[&str] = &["Iterator", "DoubleEndedIterator", "ExactSizeIterator", "ToString", "Fn", "FnMut", "FnOnce"];
+
+/// 기본 타입 이름. 이 이름으로 시작하는 경로(`u32::MAX`)는 연관 항목이라 `use`로 들여올 수 없고 앞에 `::`를 붙일 수 없음
+const PRIMITIVE_TYPES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize", "f32", "f64", "bool", "char", "str",
+];
+
+impl Modernizer {
+    /// 아이템 목록의 `extern crate` 선언, 아이템 위치 매크로, `static`, `use`, 트레이트 impl의 메서드, 구조체/열거형의 derive,
+    /// 트레이트 선언과 impl의 속성 매크로를 변환합니다. (대체 아이템이 없으면 지움)
+    fn transform_items(&mut self, items: &mut Vec<Item>) {
+        let mut index = 0;
+        let mut removed_attribute = None;
+        while index < items.len() {
+            if matches!(items[index], Item::Trait(_) | Item::Impl(_)) {
+                if let Some(rule) = self.transform_item_trait(&mut items[index]) {
+                    removed_attribute = Some(rule);
+                }
+            }
+            let replacement = match &items[index] {
+                Item::ExternCrate(item) => {
+                    let item = item.clone();
+                    self.transform_extern_crate(&item, items)
+                }
+                Item::Macro(item) => self.transform_item_macro(item),
+                Item::Static(item) => self.transform_item_static(item),
+                Item::Use(item) => self.transform_use(item),
+                Item::Impl(item) => self.transform_impl_fn(item, items),
+                Item::Struct(_) | Item::Enum(_) => {
+                    self.transform_item_derive(&mut items[index]);
+                    None
+                }
+                _ => None,
+            };
+            match replacement {
+                Some(replacement) => {
+                    let added = replacement.len();
+                    items.splice(index..=index, replacement);
+                    index += added;
+                }
+                None => index += 1,
+            }
+        }
+        if let Some(rule) = removed_attribute {
+            self.remove_attribute_import(&rule, items);
+        }
+    }
+
+    /// 로드된 규칙을 순회하며 트레이트 선언과 트레이트 impl에 붙은 속성 매크로(ItemTrait 규칙)를 지웁니다. 지웠으면 규칙을 반환합니다.
+    /// remove_async_trait: `#[async_trait]`을 지워 async 메서드를 네이티브 async fn in trait으로 둡니다. 파일에서 `dyn Trait`로
+    /// 쓰는 트레이트와, 파일에서 선언하지 않은 트레이트(아직 `#[async_trait]` 트레이트일 수 있음)의 impl은 건너뜁니다.
+    /// 트레이트 선언의 속성 자리에는 dyn 호환성과 `Send` 경계를 확인하라는 검토 안내를 남깁니다.
+    fn transform_item_trait(&mut self, item: &mut Item) -> Option<ModernizerRule> {
+        let (attrs, name, is_impl) = match &*item {
+            Item::Trait(item) => (&item.attrs, item.ident.to_string(), false),
+            Item::Impl(ItemImpl { attrs, trait_: Some((_, path, _)), .. }) => {
+                (attrs, path.segments.last()?.ident.to_string(), true)
+            }
+            _ => return None,
+        };
+        for index in 0..self.rules.len() {
+            if self.rules[index].ast_type != "ItemTrait" { continue; }
+            let rule = &self.rules[index].clone();
+            let Some(position) = attrs.iter().position(|attr| path_matches(attr.path(), &rule.method_name)) else { continue };
+            let attr = &attrs[position];
+            let span = attr.span();
+            if self.is_disabled(rule, span) { continue; }
+            let requires_send = async_trait::requires_send(attr);
+            let reason = if self.trait_objects.contains(&name) {
+                Some(format!("`{}` is used as a trait object (`dyn {}`), which native async fn in traits does not support", name, name))
+            } else if is_impl && !self.traits.contains(&name) {
+                Some(format!("trait `{}` is not declared in this file (it may still be an `#[{}]` trait)", name, rule.method_name))
+            } else {
+                requires_send.as_ref().err().cloned()
+            };
+            if let Some(reason) = reason {
+                self.record_skip(rule, span, &reason);
+                self.explain(rule, span, &format!("rejected: {}", reason));
+                continue;
+            }
+            let review = match is_impl {
+                true => None,
+                false => {
+                    let send = match requires_send {
+                        Ok(true) => " The returned futures are no longer `Send`: callers that spawn them need the method \
+                                     declared as `fn ..(..) -> impl Future<Output = T> + Send`.",
+                        _ => "",
+                    };
+                    self.review_annotation(
+                        rule,
+                        format!(
+                            "`#[{}]` removed, the async methods are native async fn in traits. `{}` is no longer dyn-compatible \
+                             (`dyn {}` stops compiling, also in downstream crates).{} Ref: {}",
+                            rule.method_name, name, name, send, rule.doc_url
+                        ),
+                        format!("`{}` is no longer dyn-compatible{}", name, if send.is_empty() { "" } else { ", futures are not `Send`" }),
+                    )
+                }
+            };
+
+            self.explain(rule, span, "matched");
+            let original = self.original_text(span, attr);
+            let mut change = AppliedChange::new(rule, span, &original).in_function(self.fn_name_stack.last().map(String::as_str));
+            change.replacement = review.as_ref().map(attribute_source);
+            // impl 쪽은 트레이트 선언을 따라가는 변환이므로 검토 안내는 트레이트 선언에만 남김
+            if is_impl {
+                change.review_note = None;
+            }
+            change.edit = match &review {
+                Some(review) => Some(SourceEdit::new(&change.location, attribute_source(review))),
+                None => self.removal_edit(&change.location),
+            };
+            let (recorded, was_changed) = (self.changes.len(), self.changed);
+            self.record_change(change);
+            if let ReviewDecision::Reject = self.review_change(recorded, was_changed, false) {
+                return None;
+            }
+            let attrs = item_attrs(item)?;
+            match review {
+                Some(review) => attrs[position] = review,
+                None => {
+                    attrs.remove(position);
+                }
+            }
+            return Some(rule.clone());
+        }
+        None
+    }
+
+    /// 속성 매크로를 지운 모듈에서 그 매크로를 들여오던 `use`(`use async_trait::async_trait;`)가 더는 쓰이지 않으면 지웁니다.
+    fn remove_attribute_import(&mut self, rule: &ModernizerRule, items: &mut Vec<Item>) {
+        let mut index = 0;
+        while index < items.len() {
+            let Item::Use(item) = &items[index] else {
+                index += 1;
+                continue;
+            };
+            let mut leaves = Vec::new();
+            use_leaves(&item.tree, &mut Vec::new(), &mut leaves);
+            let removed: Vec<Vec<Ident>> = leaves
+                .into_iter()
+                .filter(|(full, local)| {
+                    let path = syn::Path { leading_colon: None, segments: full.iter().cloned().map(PathSegment::from).collect() };
+                    path_matches(&path, &rule.method_name) && !async_trait::mentions(items, &local.to_string())
+                })
+                .map(|(full, _)| full)
+                .collect();
+            if removed.is_empty() || !matches!(item.vis, Visibility::Inherited) {
+                index += 1;
+                continue;
+            }
+
+            let span = item.span();
+            let mut remaining = item.clone();
+            let replacement: Vec<Item> = match remove_use_leaves(&mut remaining.tree, &mut Vec::new(), &removed) {
+                true => Vec::new(),
+                false => vec![Item::Use(remaining)],
+            };
+            let code: Vec<String> = replacement.iter().map(item_code).collect();
+            let original = self.original_text(span, item);
+            match self.replace_item(rule, span, &original, replacement, code) {
+                Some(replacement) => {
+                    let added = replacement.len();
+                    items.splice(index..=index, replacement);
+                    index += added;
+                }
+                None => index += 1,
+            }
+        }
+    }
+
+    /// 로드된 규칙을 순회하며 `extern crate` 선언을 변환합니다. 규칙이 적용되면 대체 아이템(지우면 빈 목록)을 반환합니다.
+    /// `items`는 선언이 있는 모듈의 아이템 목록입니다. (import_macros가 안쪽 모듈에 `use`를 추가)
+    fn transform_extern_crate(&mut self, item: &ItemExternCrate, items: &mut [Item]) -> Option<Vec<Item>> {
+        let span = item.span();
+        let name = item.ident.unraw().to_string();
+        let alias = item.rename.as_ref().map(|(_, alias)| alias.to_string());
+
+        for index in 0..self.rules.len() {
+            let rule = &self.rules[index];
+            if rule.ast_type != "ItemExternCrate" || !(rule.method_name.is_empty() || rule.method_name == name) {
+                continue;
+            }
+            if self.is_disabled(rule, span) {
+                continue;
+            }
+            let rule = &rule.clone();
+
+            let aliases = usize::from(alias.is_some());
+            if let Some(args_count) = rule.args_count.filter(|args_count| !args_count.matches(aliases)) {
+                self.explain(rule, span, &format!("rejected: args_count is {}, found {} alias(es)", args_count, aliases));
+                continue;
+            }
+            if rule.action == RuleAction::ImportMacros && macro_use::macro_list(&item.attrs).is_none() {
+                self.explain(rule, span, "rejected: no `#[macro_use]` attribute");
+                continue;
+            }
+            // 지우거나 `use`로 바꾸면 의미가 달라지는 선언은 건너뜀
+            let reason = if !item.attrs.is_empty() && rule.action != RuleAction::ImportMacros {
+                Some("has attributes (`#[macro_use]` imports macros that `use` does not)".to_string())
+            } else if !matches!(item.vis, Visibility::Inherited) {
+                Some("re-exports the crate (`pub extern crate`)".to_string())
+            } else if SYSROOT_CRATES.contains(&name.as_str()) || name == "self" {
+                Some(format!("`{}` is not in the extern prelude", name))
+            } else if alias.as_deref() == Some("_") {
+                Some("`as _` only forces the crate to be linked".to_string())
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                self.record_skip(rule, span, &reason);
+                self.explain(rule, span, &format!("rejected: {}", reason));
+                continue;
+            }
+            if rule.action == RuleAction::ImportMacros {
+                match self.import_macros(rule, item, items) {
+                    Some(replacement) => return Some(replacement),
+                    None => continue,
+                }
+            }
+            let Some(template) = self.select_template(rule, span) else { continue };
+
+            let replacement = match template.trim() {
+                "" => None,
+                template => {
+                    let mut captures = Captures::new();
+                    captures.insert("crate".to_string(), template::Capture::Ident(item.ident.clone()));
+                    if let Some((_, alias)) = &item.rename {
+                        captures.insert("rename".to_string(), template::Capture::Ident(alias.clone()));
+                    }
+                    match template::expand_item(template, &captures) {
+                        Ok(new_item) => Some(new_item),
+                        Err(err) => {
+                            self.template_error(rule, span, err);
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            let original = self.original_text(span, item);
+            let code = replacement.iter().map(item_code).collect();
+            return self.replace_item(rule, span, &original, replacement.into_iter().collect(), code);
+        }
+        None
+    }
+
+    /// 아이템 하나를 `replacement`(비어 있으면 삭제)로 바꾸는 변환을 기록합니다. 검토에서 거절되면 None을 반환합니다.
+    /// `code`는 대체 아이템마다의 소스 코드입니다.
+    fn replace_item(
+        &mut self,
+        rule: &ModernizerRule,
+        span: Span,
+        original: &str,
+        replacement: Vec<Item>,
+        code: Vec<String>,
+    ) -> Option<Vec<Item>> {
+        self.explain(rule, span, "matched");
+        let mut change = AppliedChange::new(rule, span, original).in_function(self.fn_name_stack.last().map(String::as_str));
+        change.replacement = Some(code.join(" "));
+        change.edit = match code.is_empty() {
+            true => self.removal_edit(&change.location),
+            false => Some(SourceEdit::new(&change.location, code.join("\n"))),
+        };
+        let (recorded, was_changed) = (self.changes.len(), self.changed);
+        self.record_change(change);
+        if let ReviewDecision::Reject = self.review_change(recorded, was_changed, false) {
+            return None;
+        }
+        Some(replacement)
+    }
+
+    /// 로드된 규칙을 순회하며 아이템 위치의 매크로 호출(`lazy_static! { ... }`)을 변환합니다.
+    /// 매크로 본문의 `static ref NAME: T = EXPR;` 선언마다 템플릿을 전개해 아이템 목록으로 바꿉니다.
+    fn transform_item_macro(&mut self, item: &ItemMacro) -> Option<Vec<Item>> {
+        let span = item.span();
+        'rules: for index in 0..self.rules.len() {
+            let rule = &self.rules[index];
+            if rule.ast_type != "ItemMacro" || !path_matches(&item.mac.path, &rule.method_name) {
+                continue;
+            }
+            if self.is_disabled(rule, span) {
+                continue;
+            }
+            let rule = &rule.clone();
+            if rule.action == RuleAction::ErrorChainToThiserror {
+                match self.transform_error_chain(rule, item) {
+                    Some(replacement) => return Some(replacement),
+                    None => continue,
+                }
+            }
+
+            let statics = match item.mac.parse_body_with(parse_lazy_statics) {
+                Ok(statics) => statics,
+                Err(err) => {
+                    let reason = format!("body is not a list of `static ref` declarations ({})", err);
+                    self.record_skip(rule, span, &reason);
+                    self.explain(rule, span, &format!("rejected: {}", reason));
+                    continue;
+                }
+            };
+            if let Some(args_count) = rule.args_count.filter(|args_count| !args_count.matches(statics.len())) {
+                self.explain(rule, span, &format!("rejected: args_count is {}, found {} declaration(s)", args_count, statics.len()));
+                continue;
+            }
+            let Some(template) = self.select_template(rule, span) else { continue };
+
+            // 매크로 호출의 속성(`#[cfg]` 등)은 모든 선언에, 선언의 속성(문서 주석 등)은 그 선언에만 붙임
+            let outer_attrs: Option<Vec<&str>> = item.attrs.iter().map(|attr| self.source_text(attr.span())).collect();
+            let (mut replacement, mut code) = (Vec::new(), Vec::new());
+            for declaration in statics {
+                let mut captures = Captures::new();
+                captures.insert("name".to_string(), template::Capture::Ident(declaration.name));
+                captures.insert("ty".to_string(), template::Capture::Type(Box::new(declaration.ty)));
+                captures.insert("init".to_string(), template::Capture::Expr(Box::new(declaration.init)));
+                let new_item = match template::expand_item(template, &captures) {
+                    Ok(new_item) => new_item,
+                    Err(err) => {
+                        self.template_error(rule, span, err);
+                        continue 'rules;
+                    }
+                };
+                let attrs = item.attrs.iter().cloned().chain(declaration.attrs).collect();
+                let new_item = with_attrs_and_vis(new_item, attrs, declaration.vis);
+                // 선언 앞부분(속성, 가시성)은 원본 텍스트를 그대로 옮김
+                let start = declaration.start.byte_range().start;
+                let indent = self.indent_at(start);
+                let source = match (&new_item, &outer_attrs) {
+                    (Item::Static(_), Some(outer_attrs)) => self
+                        .dedented_source(start..declaration.static_token.byte_range().start, indent)
+                        .map(|prefix| outer_attrs.iter().map(|attr| format!("{}\n", attr)).collect::<String>() + &prefix)
+                        .and_then(|prefix| self.template_source(template, &captures, &prefix, indent)),
+                    _ => None,
+                };
+                code.push(source.unwrap_or_else(|| item_code(&new_item)));
+                replacement.push(new_item);
+            }
+            let original = self.original_text(span, item);
+            return self.replace_item(rule, span, &original, replacement, code);
+        }
+        None
+    }
+
+    /// 로드된 규칙을 순회하며 `static` 아이템을 변환합니다. (예: `static X: Lazy<T> = Lazy::new(f);`)
+    fn transform_item_static(&mut self, item: &ItemStatic) -> Option<Vec<Item>> {
+        let span = item.span();
+        let Type::Path(type_path) = &*item.ty else { return None };
+        let resolved = self.resolve_path(&type_path.path);
+        let call = match &*item.expr {
+            Expr::Call(call) => Some(call),
+            _ => None,
+        };
+
+        for index in 0..self.rules.len() {
+            let rule = &self.rules[index];
+            if rule.ast_type != "ItemStatic" || !path_matches(&resolved, &rule.method_name) {
+                continue;
+            }
+            if self.is_disabled(rule, span) {
+                continue;
+            }
+            let rule = &rule.clone();
+
+            if let Some(args_count) = rule.args_count {
+                let found = call.map(|call| call.args.len());
+                if !found.is_some_and(|found| args_count.matches(found)) {
+                    let found = found.map_or("an initializer that is not a call".to_string(), |found| format!("{} argument(s)", found));
+                    self.explain(rule, span, &format!("rejected: args_count is {}, found {}", args_count, found));
+                    continue;
+                }
+            }
+            let Some(template) = self.select_template(rule, span) else { continue };
+            let inner_ty = type_path.path.segments.last().and_then(|segment| match &segment.arguments {
+                PathArguments::AngleBracketed(generics) if generics.args.len() == 1 => match &generics.args[0] {
+                    GenericArgument::Type(ty) => Some(ty.clone()),
+                    _ => None,
+                },
+                _ => None,
+            });
+            let reason = if matches!(item.mutability, StaticMutability::Mut(_)) {
+                Some("`static mut` is accessed through raw places, not a shared reference".to_string())
+            } else if inner_ty.is_none() && template::placeholder_names(template).iter().any(|name| name == "inner_ty") {
+                Some(format!("`{}` does not have exactly one type argument (#inner_ty)", type_path.to_token_stream()))
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                self.record_skip(rule, span, &reason);
+                self.explain(rule, span, &format!("rejected: {}", reason));
+                continue;
+            }
+
+            let mut captures = Captures::new();
+            captures.insert("name".to_string(), template::Capture::Ident(item.ident.clone()));
+            captures.insert("ty".to_string(), template::Capture::Type(item.ty.clone()));
+            if let Some(inner_ty) = inner_ty {
+                captures.insert("inner_ty".to_string(), template::Capture::Type(Box::new(inner_ty)));
+            }
+            captures.insert("init".to_string(), template::Capture::Expr(item.expr.clone()));
+            if let Some(call) = call {
+                captures.insert("func".to_string(), template::Capture::Expr(call.func.clone()));
+                template::bind_args(&mut captures, &call.args);
+            }
+            let new_item = match template::expand_item(template, &captures) {
+                Ok(new_item) => with_attrs_and_vis(new_item, item.attrs.clone(), item.vis.clone()),
+                Err(err) => {
+                    self.template_error(rule, span, err);
+                    continue;
+                }
+            };
+            // 속성과 가시성(`static` 앞부분)은 원본 텍스트를 그대로 옮김
+            let start = span.byte_range().start;
+            let indent = self.indent_at(start);
+            let source = match &new_item {
+                Item::Static(_) => self
+                    .dedented_source(start..item.static_token.span.byte_range().start, indent)
+                    .and_then(|prefix| self.template_source(template, &captures, &prefix, indent)),
+                _ => None,
+            };
+            let code = vec![source.unwrap_or_else(|| item_code(&new_item))];
+            let original = self.original_text(span, item);
+            return self.replace_item(rule, span, &original, vec![new_item], code);
+        }
+        None
+    }
+
+    /// 원본 소스의 `range`를 잘라 둘째 줄부터 들여쓰기를 `indent`칸까지 지웁니다. (다른 깊이로 옮기는 코드용)
+    fn dedented_source(&self, range: Range<usize>, indent: usize) -> Option<String> {
+        let text = self.source.get(range)?;
+        let lines: Vec<&str> = text
+            .split('\n')
+            .enumerate()
+            .map(|(index, line)| {
+                let blank = line.len() - line.trim_start_matches([' ', '\t']).len();
+                if index == 0 { line } else { &line[blank.min(indent)..] }
+            })
+            .collect();
+        Some(lines.join("\n"))
+    }
+
+    /// `offset`이 있는 줄의 들여쓰기 폭
+    fn indent_at(&self, offset: usize) -> usize {
+        let line = &self.source[self.source[..offset.min(self.source.len())].rfind('\n').map_or(0, |index| index + 1)..];
+        line.len() - line.trim_start_matches([' ', '\t']).len()
+    }
+
+    /// 템플릿으로 만든 아이템의 소스 코드를 `prefix` 뒤에 이어 만듭니다. 자리표시자에는 원본 소스 텍스트를 넣어 서식을 유지하고,
+    /// 원본 위치가 없거나 안쪽에 이미 변환이 있는 자리표시자는 prettyplease로 출력합니다. (`indent`: 원본 선언의 들여쓰기)
+    fn template_source(&self, template: &str, captures: &Captures, prefix: &str, indent: usize) -> Option<String> {
+        let mut sources = HashMap::new();
+        for (name, capture) in captures {
+            let range = match capture {
+                template::Capture::Ident(ident) => {
+                    sources.insert(name.clone(), ident.to_string());
+                    continue;
+                }
+                template::Capture::Expr(expr) => expr.span().byte_range(),
+                template::Capture::Type(ty) => ty.span().byte_range(),
+                template::Capture::List(list) => match (list.first(), list.last()) {
+                    (Some(first), Some(last)) => first.span().byte_range().start..last.span().byte_range().end,
+                    _ => 0..0,
+                },
+            };
+            let changed_inside = self.changes.iter().any(|change| range.contains(&change.location.byte_start));
+            let text = match self.dedented_source(range, indent) {
+                Some(text) if !text.is_empty() && !changed_inside => text,
+                _ => match capture {
+                    template::Capture::Expr(expr) => expr_code(expr),
+                    template::Capture::List(list) => list.iter().map(expr_code).collect::<Vec<_>>().join(", "),
+                    template::Capture::Type(ty) => ty.to_token_stream().to_string(),
+                    template::Capture::Ident(ident) => ident.to_string(),
+                },
+            };
+            sources.insert(name.clone(), text);
+        }
+        Some(format!("{}{}", prefix, template::fill_source(template, &sources)?))
+    }
+
+    /// error_chain_to_thiserror: `error_chain! { ... }`을 thiserror 오류 열거형과 `From` 구현, `Result` 별칭으로 바꿉니다.
+    /// 열거형에는 `ErrorKind`/`chain_err` 사용처를 옮기라는 검토 안내를 붙입니다.
+    fn transform_error_chain(&mut self, rule: &ModernizerRule, item: &ItemMacro) -> Option<Vec<Item>> {
+        let span = item.span();
+        let chain = match item.mac.parse_body::<error_chain::ErrorChain>() {
+            Ok(chain) => chain,
+            Err(err) => {
+                let reason = format!("body is not a supported `error_chain!` definition ({})", err);
+                self.record_skip(rule, span, &reason);
+                self.explain(rule, span, &format!("rejected: {}", reason));
+                return None;
+            }
+        };
+        let template = self.select_template(rule, span)?;
+        let derive = match syn::parse_str::<syn::Path>(template.trim()) {
+            Ok(derive) => derive,
+            Err(err) => {
+                self.template_error(rule, span, anyhow::Error::new(err).context(format!("`{}` is not a derive path", template)));
+                return None;
+            }
+        };
+        let review = self.review_annotation(
+            rule,
+            "generated from `error_chain!`. Replace `ErrorKind::X(..)` constructors with `Error::X { .. }`, \
+             `chain_err(|| ..)` with a variant that wraps the source error, and drop the `error-chain` dependency."
+                .to_string(),
+            "replace `ErrorKind` and `chain_err` uses".to_string(),
+        );
+        let replacement = match chain.items(&derive, review) {
+            Ok(replacement) => replacement,
+            Err(reason) => {
+                self.record_skip(rule, span, &reason);
+                self.explain(rule, span, &format!("rejected: {}", reason));
+                return None;
+            }
+        };
+        // 매크로 호출의 속성(`#[cfg]` 등)은 모든 생성 아이템에 붙임, 생성한 아이템 사이는 빈 줄로 구분
+        let replacement: Vec<Item> = replacement
+            .into_iter()
+            .map(|mut new_item| {
+                if let Some(attrs) = item_attrs(&mut new_item) {
+                    attrs.splice(0..0, item.attrs.iter().cloned());
+                }
+                new_item
+            })
+            .collect();
+        let last = replacement.len().saturating_sub(1);
+        let code = replacement.iter().enumerate().map(|(index, new_item)| item_code(new_item) + if index < last { "\n" } else { "" }).collect();
+        let original = self.original_text(span, item);
+        self.replace_item(rule, span, &original, replacement, code)
+    }
+
+    /// 로드된 규칙을 순회하며 트레이트 impl의 메서드(`impl Error for X`의 `fn description`)를 다른 아이템으로 옮깁니다.
+    /// 메서드는 impl에서 지우고, 템플릿으로 만든 아이템(원래 impl의 제네릭 매개변수를 붙임)은 impl 바로 뒤에 추가합니다.
+    /// 같은 모듈에 만들 트레이트의 impl이 이미 있으면 건너뜁니다. (`items`: impl이 있는 모듈의 아이템 목록)
+    fn transform_impl_fn(&mut self, item: &ItemImpl, items: &[Item]) -> Option<Vec<Item>> {
+        let (_, trait_path, _) = item.trait_.as_ref()?;
+        for index in 0..self.rules.len() {
+            if self.rules[index].ast_type != "ImplItemFn" { continue; }
+            let rule = &self.rules[index].clone();
+            let Some((trait_name, fn_name)) = rule.method_name.rsplit_once("::") else { continue };
+            if !path_matches(trait_path, trait_name) { continue; }
+            let found = item.items.iter().enumerate().find_map(|(position, impl_item)| match impl_item {
+                ImplItem::Fn(method) if method.sig.ident == fn_name => Some((position, method)),
+                _ => None,
+            });
+            let Some((position, method)) = found else { continue };
+            let span = method.span();
+            if self.is_disabled(rule, span) { continue; }
+            if contains_return(&method.block) {
+                let reason = "method body uses `return`, which cannot move into the generated item";
+                self.record_skip(rule, span, reason);
+                self.explain(rule, span, &format!("rejected: {}", reason));
+                continue;
+            }
+            let Some(template) = self.select_template(rule, span) else { continue };
+
+            let mut new_items = Vec::new();
+            if !template.trim().is_empty() {
+                let body = match method.block.stmts.as_slice() {
+                    [Stmt::Expr(expr, None)] => expr.clone(),
+                    _ => Expr::Block(syn::ExprBlock { attrs: Vec::new(), label: None, block: method.block.clone() }),
+                };
+                let mut captures = Captures::new();
+                captures.insert("self_ty".to_string(), template::Capture::Type(item.self_ty.clone()));
+                captures.insert("body".to_string(), template::Capture::Expr(Box::new(body)));
+                let mut new_item = match template::expand_item(template, &captures) {
+                    Ok(new_item) => new_item,
+                    Err(err) => {
+                        self.template_error(rule, span, err);
+                        continue;
+                    }
+                };
+                if let Item::Impl(new_impl) = &mut new_item {
+                    let trait_name = |item_impl: &ItemImpl| {
+                        item_impl.trait_.as_ref().and_then(|(_, path, _)| path.segments.last()).map(|segment| segment.ident.to_string())
+                    };
+                    let self_ty = item.self_ty.to_token_stream().to_string();
+                    let existing = items.iter().any(|other| {
+                        matches!(other, Item::Impl(other) if trait_name(other) == trait_name(new_impl)
+                            && other.self_ty.to_token_stream().to_string() == self_ty)
+                    });
+                    if existing {
+                        let reason = format!(
+                            "`{}` already implements `{}`",
+                            self_ty.replace(' ', ""),
+                            trait_name(new_impl).unwrap_or_default()
+                        );
+                        self.record_skip(rule, span, &reason);
+                        self.explain(rule, span, &format!("rejected: {}", reason));
+                        continue;
+                    }
+                    new_impl.generics = item.generics.clone();
+                }
+                new_items.push(new_item);
+            }
+
+            self.explain(rule, span, "matched");
+            let function = self.fn_name_stack.last().cloned();
+            let (recorded, was_changed) = (self.changes.len(), self.changed);
+            // 새 아이템은 impl의 닫는 중괄호 뒤에 추가 (메서드를 지우는 변환의 일부로 함께 검토)
+            let code: Vec<String> = new_items.iter().map(item_code).collect();
+            // prettyplease는 여러 줄 인자 뒤에 쉼표를 붙이므로, 출력한 코드를 다시 파싱해 AST와 원본 서식 보존 결과를 맞춤
+            let new_items: Vec<Item> =
+                new_items.into_iter().zip(&code).map(|(new_item, code)| syn::parse_str(code).unwrap_or(new_item)).collect();
+            if !new_items.is_empty() {
+                let brace = item.brace_token.span.close();
+                let mut change = AppliedChange::new(rule, brace, "}").in_function(function.as_deref());
+                change.replacement = Some(code.join(" "));
+                change.edit = Some(SourceEdit::new(&change.location, format!("}}\n\n{}", code.join("\n\n"))));
+                change.review_note = None;
+                self.record_change(change);
+            }
+            let mut change = AppliedChange::new(rule, span, &self.original_text(span, method)).in_function(function.as_deref());
+            change.replacement = Some(String::new());
+            change.edit = self.removal_edit(&change.location);
+            // 줄 전체를 지웠으면 메서드 사이의 빈 줄도 하나 지움 (뒤쪽 빈 줄, 없으면 앞쪽 빈 줄)
+            if let Some(edit) = change.edit.as_mut().filter(|edit| edit.range.start == 0 || self.source[..edit.range.start].ends_with('\n')) {
+                let rest = &self.source[edit.range.end..];
+                let before = &self.source[..edit.range.start];
+                let previous_line = before.trim_end_matches('\n').rfind('\n').map_or(0, |index| index + 1);
+                match rest.find('\n').filter(|&end| rest[..end].trim().is_empty()) {
+                    Some(end) => edit.range.end += end + 1,
+                    None if edit.range.start > 0 && before[previous_line..].trim().is_empty() => edit.range.start = previous_line,
+                    None => {}
+                }
+            }
+            self.record_change(change);
+            if let ReviewDecision::Reject = self.review_change(recorded, was_changed, false) {
+                return None;
+            }
+            let mut new_impl = item.clone();
+            new_impl.items.remove(position);
+            return Some(std::iter::once(Item::Impl(new_impl)).chain(new_items).collect());
+        }
+        None
+    }
+
+    /// 로드된 규칙을 순회하며 구조체/열거형의 `#[derive(...)]` 트레이트를 바꿉니다.
+    /// thiserror_derive는 `#[fail(...)]` 도우미 속성도 함께 바꾸며, 바꿀 수 없는 속성이 하나라도 있으면 건너뜁니다.
+    fn transform_item_derive(&mut self, item: &mut Item) {
+        let span = item.span();
+        for index in 0..self.rules.len() {
+            if self.rules[index].ast_type != "ItemDerive" { continue; }
+            let rule = &self.rules[index].clone();
+            let attrs = match &*item {
+                Item::Struct(item) => &item.attrs,
+                Item::Enum(item) => &item.attrs,
+                _ => return,
+            };
+            let found = attrs.iter().enumerate().find_map(|(attr_index, attr)| {
+                if !attr.path().is_ident("derive") {
+                    return None;
+                }
+                let traits = attr.parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated).ok()?;
+                let position = traits.iter().position(|path| path_matches(path, &rule.method_name))?;
+                Some((attr_index, traits, position))
+            });
+            let Some((attr_index, mut traits, position)) = found else { continue };
+            if self.is_disabled(rule, span) { continue; }
+            if !matches!(rule.action, RuleAction::Replace | RuleAction::ThiserrorDerive) {
+                self.explain(rule, span, "rejected: only the replace and thiserror_derive actions are supported for ItemDerive");
+                continue;
+            }
+            let Some(template) = self.select_template(rule, span) else { continue };
+            match syn::parse_str::<syn::Path>(template.trim()) {
+                Ok(new_trait) => traits[position] = new_trait,
+                Err(err) => {
+                    self.template_error(rule, span, anyhow::Error::new(err).context(format!("`{}` is not a derive path", template)));
+                    continue;
+                }
+            }
+            let derive: Attribute = parse_quote!(#[derive(#traits)]);
+
+            let mut helpers = Vec::new();
+            if rule.action == RuleAction::ThiserrorDerive {
+                let converted: Result<Vec<(Span, Attribute)>, String> = fail_derive::fail_attrs(item)
+                    .into_iter()
+                    .map(|attr| fail_derive::convert(attr).map(|new_attr| (attr.span(), new_attr)))
+                    .collect();
+                match converted {
+                    Ok(converted) => helpers = converted,
+                    Err(reason) => {
+                        self.record_skip(rule, span, &reason);
+                        self.explain(rule, span, &format!("rejected: {}", reason));
+                        continue;
+                    }
+                }
+            }
+
+            self.explain(rule, span, "matched");
+            let function = self.fn_name_stack.last().cloned();
+            let (recorded, was_changed) = (self.changes.len(), self.changed);
+            // 도우미 속성의 편집은 derive 변환의 일부로 함께 검토
+            for (helper_span, new_attr) in &helpers {
+                let original = self.source_text(*helper_span).map_or_else(|| attribute_source(new_attr), str::to_string);
+                let mut change = AppliedChange::new(rule, *helper_span, &original).in_function(function.as_deref());
+                change.replacement = Some(attribute_source(new_attr));
+                change.edit = Some(SourceEdit::new(&change.location, attribute_source(new_attr)));
+                change.review_note = None;
+                self.record_change(change);
+            }
+            let attrs = match &mut *item {
+                Item::Struct(item) => &mut item.attrs,
+                Item::Enum(item) => &mut item.attrs,
+                _ => return,
+            };
+            let derive_span = attrs[attr_index].span();
+            let mut change = AppliedChange::new(rule, derive_span, &self.original_text(derive_span, &attrs[attr_index]))
+                .in_function(function.as_deref());
+            change.replacement = Some(attribute_source(&derive));
+            change.edit = Some(SourceEdit::new(&change.location, attribute_source(&derive)));
+            self.record_change(change);
+            if let ReviewDecision::Reject = self.review_change(recorded, was_changed, false) {
+                return;
+            }
+            attrs[attr_index] = derive;
+            for (attr, (_, new_attr)) in fail_derive::fail_attrs(item).into_iter().zip(helpers) {
+                *attr = new_attr;
+            }
+            return;
+        }
+    }
+
+    /// 로드된 규칙을 순회하며 타입, 식, 매크로 경로의 앞부분을 바꿉니다. (`failure::Error` → `anyhow::Error`)
+    /// 쓰인 그대로의 경로만 비교하며, `use`로 들여온 이름은 `use` 선언 쪽을 바꿉니다. (transform_use)
+    /// 템플릿이 빈 규칙은 경로를 바꾸지 않고, `use`로 들여온 이름까지 풀어서 일치하는 사용처를 수동 검토 대상으로 보고합니다.
+    fn transform_path(&mut self, path: &mut syn::Path) {
+        let span = path.span();
+        for index in 0..self.rules.len() {
+            if self.rules[index].ast_type != "Path" { continue; }
+            let rule = &self.rules[index].clone();
+            let template = rule.replacement_template.trim();
+            let matched = match template.is_empty() {
+                true => path_prefix_len(&self.resolve_path(path), &rule.method_name),
+                false => path_prefix_len(path, &rule.method_name),
+            };
+            let Some(matched) = matched else { continue };
+            if self.is_disabled(rule, span) { continue; }
+
+            if template.is_empty() {
+                // 여러 패스에서 같은 위치는 한 번만 보고
+                let location = Location::from_span(span);
+                let reported = self.skipped.borrow().iter().any(|skip| {
+                    skip.rule_id == rule.id && (skip.location.byte_start, skip.location.byte_end) == (location.byte_start, location.byte_end)
+                });
+                if !reported {
+                    let original = self.original_text(span, &*path);
+                    let reason = rule.review_note.as_deref().map_or_else(
+                        || format!("`{}` needs manual migration", original),
+                        |note| template::fill_original(note, &original),
+                    );
+                    self.record_skip(rule, span, &reason);
+                }
+                self.explain(rule, span, "rejected: report-only rule (empty replacement_template)");
+                continue;
+            }
+            let new_prefix = match syn::parse_str::<syn::Path>(template) {
+                Ok(new_prefix) => new_prefix,
+                Err(err) => {
+                    self.template_error(rule, span, anyhow::Error::new(err).context(format!("`{}` is not a path", template)));
+                    continue;
+                }
+            };
+            // 마지막으로 일치한 세그먼트의 제네릭 인자(`Fallible<T>`의 `<T>`)는 새 경로의 마지막 세그먼트로 옮김
+            let leading = path.leading_colon.is_some() && new_prefix.leading_colon.is_none() && !is_primitive_path(&new_prefix);
+            let mut segments: Vec<PathSegment> = new_prefix.segments.into_iter().collect();
+            if let Some(last) = segments.last_mut() {
+                last.arguments = path.segments[matched - 1].arguments.clone();
+            }
+            segments.extend(path.segments.iter().skip(matched).cloned());
+            let leading_colon = if leading { path.leading_colon } else { new_prefix.leading_colon };
+            let new_path = syn::Path { leading_colon, segments: segments.into_iter().collect() };
+
+            // 원본 위치가 있으면 일치한 부분만 바꿔 뒤쪽(제네릭 인자 등)의 서식을 유지
+            let rest = path.segments[matched - 1].ident.span().byte_range().end..span.byte_range().end;
+            let code = self
+                .source_text(span)
+                .and_then(|_| self.source.get(rest))
+                .map(|rest| format!("{}{}{}", if leading { "::" } else { "" }, template, rest));
+
+            self.explain(rule, span, "matched");
+            let mut change = AppliedChange::new(rule, span, &self.original_text(span, &*path))
+                .in_function(self.fn_name_stack.last().map(String::as_str));
+            change.replacement = Some(code.clone().unwrap_or_else(|| new_path.to_token_stream().to_string().replace(' ', "")));
+            change.edit = code.map(|code| SourceEdit::new(&change.location, code));
+            let (recorded, was_changed) = (self.changes.len(), self.changed);
+            self.record_change(change);
+            if !matches!(self.review_change(recorded, was_changed, false), ReviewDecision::Reject) {
+                *path = new_path;
+            }
+            return;
+        }
+    }
+
+    /// 로드된 규칙을 순회하며 `dyn` 없이 쓴 트레이트 객체 타입을 템플릿으로 바꿉니다. (`Box<Error>` → `Box<dyn Error>`)
+    /// 규칙의 경로는 `use`로 들여온 이름을 전체 경로로 풀어서 비교합니다. 경로가 빈 규칙은 `dyn` 없는 트레이트 객체로 확실한 것,
+    /// 즉 `+`로 이은 경계, `Fn(...)` 표기, 표준 트레이트, 파일에서 선언한 트레이트만 바꿉니다. (`Box<Error>`는 구조체일 수도 있음)
+    fn transform_trait_object(&mut self, ty: &mut Type) {
+        let span = ty.span();
+        let (bounds, combined) = match &*ty {
+            Type::TraitObject(object) if object.dyn_token.is_none() => (object.bounds.clone(), true),
+            // 파싱하려고 미리 `dyn`을 넣은 `Fn(...)` (parse_with_bare_fn)
+            Type::TraitObject(TypeTraitObject { dyn_token: Some(token), bounds })
+                if self.inserted_dyn.remove(&token.span.byte_range().start) => (bounds.clone(), true),
+            Type::Path(TypePath { qself: None, path }) => {
+                let bound = TraitBound { paren_token: None, modifier: TraitBoundModifier::None, lifetimes: None, path: path.clone() };
+                (Punctuated::from_iter([TypeParamBound::Trait(bound)]), false)
+            }
+            _ => return,
+        };
+        let Some(trait_path) = bounds.iter().find_map(|bound| match bound {
+            TypeParamBound::Trait(bound) => Some(&bound.path),
+            _ => None,
+        }) else {
+            return;
+        };
+        for index in 0..self.rules.len() {
+            if self.rules[index].ast_type != "TraitObject" { continue; }
+            let rule = &self.rules[index].clone();
+            let matched = match rule.method_name.is_empty() {
+                true => combined || self.is_trait_path(trait_path),
+                false => path_matches(&self.resolve_path(trait_path), &rule.method_name),
+            };
+            if !matched { continue; }
+            if self.is_disabled(rule, span) { continue; }
+            if rule.action != RuleAction::Replace {
+                self.explain(rule, span, "rejected: only the replace action is supported for TraitObject");
+                continue;
+            }
+            let Some(template) = self.select_template(rule, span) else { continue };
+
+            let fill = |bounds: &str| template::fill_source(template, &HashMap::from([("bounds".to_string(), bounds.to_string())]));
+            let new_ty = match fill(&bounds.to_token_stream().to_string()).map(|code| syn::parse_str::<Type>(&code)) {
+                Some(Ok(new_ty)) => new_ty,
+                Some(Err(err)) => {
+                    self.template_error(rule, span, anyhow::Error::new(err).context(format!("`{}` is not a type", template)));
+                    continue;
+                }
+                None => {
+                    self.template_error(rule, span, anyhow::anyhow!("unknown placeholder in `{}` (TraitObject rules provide #bounds)", template));
+                    continue;
+                }
+            };
+            // 원본 위치가 있고 안쪽에 변환이 없으면 경계를 쓰인 그대로 옮겨 서식을 유지
+            let range = span.byte_range();
+            let changed_inside = self.changes.iter().any(|change| range.contains(&change.location.byte_start));
+            let code = match self.source_text(bounds.span()) {
+                Some(text) if !changed_inside => fill(text).unwrap_or_else(|| type_code(&new_ty)),
+                _ => type_code(&new_ty),
+            };
+
+            self.explain(rule, span, "matched");
+            let mut change = AppliedChange::new(rule, span, &self.original_text(bounds.span(), &bounds))
+                .in_function(self.fn_name_stack.last().map(String::as_str));
+            change.replacement = Some(code.clone());
+            change.edit = Some(SourceEdit::new(&change.location, code));
+            let (recorded, was_changed) = (self.changes.len(), self.changed);
+            self.record_change(change);
+            if !matches!(self.review_change(recorded, was_changed, false), ReviewDecision::Reject) {
+                *ty = new_ty;
+            }
+            return;
+        }
+    }
+
+    /// 로드된 규칙을 순회하며 `...`로 쓴 범위 패턴의 연산자를 바꿉니다. (`1...5 =>` → `1..=5 =>`)
+    /// syn은 패턴의 `...`을 `..=`로 읽으므로 원본 소스의 연산자 텍스트로 구분합니다.
+    /// (식의 `a...b`는 rustc와 syn 모두 받아들인 적이 없으므로 패턴만 해당)
+    fn transform_range_pattern(&mut self, range: &mut PatRange) {
+        let RangeLimits::Closed(limits) = &range.limits else { return };
+        let operator = Location::from_span(limits.span());
+        if self.source_text(limits.span()) != Some("...") {
+            return;
+        }
+        let span = range.span();
+        for index in 0..self.rules.len() {
+            if self.rules[index].ast_type != "PatRange" { continue; }
+            let rule = &self.rules[index].clone();
+            if self.is_disabled(rule, span) { continue; }
+            if rule.action != RuleAction::Replace {
+                self.explain(rule, span, "rejected: only the replace action is supported for PatRange");
+                continue;
+            }
+            let Some(template) = self.select_template(rule, span) else { continue };
+            let new_limits = match syn::parse_str::<RangeLimits>(template.trim()) {
+                Ok(new_limits) => new_limits,
+                Err(err) => {
+                    self.template_error(rule, span, anyhow::Error::new(err).context(format!("`{}` is not a range operator", template)));
+                    continue;
+                }
+            };
+
+            self.explain(rule, span, "matched");
+            let original = self.original_text(span, &*range);
+            // 연산자만 바꿔 양 끝 식의 서식을 유지
+            let mut change = AppliedChange::new(rule, span, &original).in_function(self.fn_name_stack.last().map(String::as_str));
+            change.replacement = Some(original.replacen("...", template.trim(), 1));
+            change.edit = Some(SourceEdit { range: operator.byte_start..operator.byte_end, code: template.trim().to_string() });
+            let (recorded, was_changed) = (self.changes.len(), self.changed);
+            self.record_change(change);
+            if !matches!(self.review_change(recorded, was_changed, false), ReviewDecision::Reject) {
+                range.limits = new_limits;
+            }
+            return;
+        }
+    }
+
+    /// 경로가 가리키는 것이 확실히 트레이트인지 여부 (`Fn(...)` 표기, 표준 라이브러리 트레이트, 파일에서 선언한 트레이트)
+    fn is_trait_path(&self, path: &syn::Path) -> bool {
+        let Some(last) = path.segments.last() else { return false };
+        if matches!(last.arguments, PathArguments::Parenthesized(_)) {
+            return true;
+        }
+        let resolved = self.resolve_path(path);
+        let from_std = resolved.segments.len() == 3 && ["std", "core", "alloc"].iter().any(|root| resolved.segments[0].ident == root);
+        if from_std && STD_TRAITS.iter().any(|name| path_matches(&resolved, name)) {
+            return true;
+        }
+        // `use`로 들여오지 않은 한 단어 이름 (또는 `crate::`/`self::`/`super::`로 시작하는 경로)
+        let name = last.ident.to_string();
+        let local = match path.segments.len() {
+            1 => path.leading_colon.is_none() && !self.imports.contains_key(&name),
+            _ => ["crate", "self", "super"].iter().any(|root| path.segments[0].ident == root),
+        };
+        local && (PRELUDE_TRAITS.contains(&name.as_str()) || self.traits.contains(&name))
+    }
+
+    /// `use` 선언에서 Path 규칙과 일치하는 이름을 새 경로의 `use`로 옮깁니다. 새 경로의 마지막 이름이 다르면
+    /// 원래 이름으로 들여와 사용처는 그대로 둡니다. (`use failure::Fallible;` → `use anyhow::Result as Fallible;`)
+    /// 그룹(`use failure::{Error, Fail};`)에서 옮긴 이름은 원래 선언에서 지웁니다.
+    /// ExprPath 규칙과 일치하는 상수는 지우고, 템플릿의 맨 앞 이름(`AtomicUsize::new(0)`의 `AtomicUsize`)을 아직 들여오지 않았으면 들여옵니다.
+    fn transform_use(&mut self, item: &ItemUse) -> Option<Vec<Item>> {
+        let span = item.span();
+        let mut leaves = Vec::new();
+        use_leaves(&item.tree, &mut Vec::new(), &mut leaves);
+
+        // (규칙, 옮긴 이름의 전체 경로, 새 `use`)
+        let mut moved: Vec<(ModernizerRule, Vec<Ident>, Option<Item>)> = Vec::new();
+        let mut added: Vec<Ident> = Vec::new();
+        for (full, local) in leaves {
+            let path = syn::Path { leading_colon: None, segments: full.iter().cloned().map(PathSegment::from).collect() };
+            let constant = self.rules.iter().find(|rule| rule.ast_type == "ExprPath" && path_prefix_len(&path, &rule.method_name) == Some(full.len()));
+            if let Some(rule) = constant.cloned() {
+                if self.is_disabled(&rule, span) { continue; }
+                let Some(template) = self.select_template(&rule, span) else { continue };
+                if !matches!(item.vis, Visibility::Inherited) {
+                    self.explain(&rule, span, "rejected: the constant is re-exported");
+                    continue;
+                }
+                let head = syn::parse_str::<Expr>(template.trim())
+                    .ok()
+                    .and_then(|mut expr| head_path(&mut expr).and_then(|head| head.segments.first().map(|first| first.ident.clone())));
+                let Some(head) = head else {
+                    self.explain(&rule, span, "rejected: replacement_template does not start with a path");
+                    continue;
+                };
+                let new_item = match self.imports.contains_key(&head.to_string()) || added.contains(&head) {
+                    true => None,
+                    false => {
+                        let new_path = full[..full.len() - 1].iter().chain([&head]);
+                        added.push(head.clone());
+                        Some(with_attrs_and_vis(parse_quote!(use #(#new_path)::*;), item.attrs.clone(), item.vis.clone()))
+                    }
+                };
+                moved.push((rule, full, new_item));
+                continue;
+            }
+            let rule = self.rules.iter().find(|rule| {
+                rule.ast_type == "Path" && !rule.replacement_template.trim().is_empty() && path_prefix_len(&path, &rule.method_name).is_some()
+            });
+            let Some(rule) = rule.cloned() else { continue };
+            if self.is_disabled(&rule, span) { continue; }
+            let Some(template) = self.select_template(&rule, span) else { continue };
+            let Ok(mut new_path) = syn::parse_str::<syn::Path>(template.trim()) else {
+                self.explain(&rule, span, "rejected: replacement_template is not a path");
+                continue;
+            };
+            if is_primitive_path(&new_path) {
+                self.explain(&rule, span, "rejected: the new path is an associated item of a primitive type, which `use` cannot import");
+                continue;
+            }
+            let matched = path_prefix_len(&path, &rule.method_name).unwrap_or_default();
+            new_path.segments.extend(full[matched..].iter().cloned().map(PathSegment::from));
+            let new_item: Item = match new_path.segments.last() {
+                Some(last) if last.ident != local => parse_quote!(use #new_path as #local;),
+                _ => parse_quote!(use #new_path;),
+            };
+            moved.push((rule, full, Some(with_attrs_and_vis(new_item, item.attrs.clone(), item.vis.clone()))));
+        }
+        if moved.is_empty() {
+            return None;
+        }
+
+        let mut remaining = item.clone();
+        let removed: Vec<Vec<Ident>> = moved.iter().map(|(_, full, _)| full.clone()).collect();
+        let mut replacement = Vec::new();
+        if !remove_use_leaves(&mut remaining.tree, &mut Vec::new(), &removed) {
+            replacement.push(Item::Use(remaining));
+        }
+        replacement.extend(moved.iter().filter_map(|(_, _, new_item)| new_item.clone()));
+        let code: Vec<String> = replacement.iter().map(item_code).collect();
+
+        // 옮긴 이름마다 규칙의 변환으로 기록 (편집은 모두 선언 전체를 같은 코드로 바꿈)
+        let original = self.original_text(span, item);
+        let (recorded, was_changed) = (self.changes.len(), self.changed);
+        for (rule, _, _) in &moved {
+            self.explain(rule, span, "matched");
+            let mut change = AppliedChange::new(rule, span, &original).in_function(self.fn_name_stack.last().map(String::as_str));
+            change.replacement = Some(code.join(" "));
+            change.edit = match code.is_empty() {
+                true => self.removal_edit(&change.location),
+                false => Some(SourceEdit::new(&change.location, code.join("\n"))),
+            };
+            self.record_change(change);
+        }
+        if let ReviewDecision::Reject = self.review_change(recorded, was_changed, false) {
+            return None;
+        }
+        Some(replacement)
+    }
+
+    /// 경로의 첫 세그먼트가 파일에서 `use`로 들여온 이름이면 전체 경로로 풀어 씁니다. (규칙 매칭용, 제네릭 인자 제외)
+    fn resolve_path(&self, path: &syn::Path) -> syn::Path {
+        let mut segments: Vec<Ident> = path.segments.iter().map(|segment| segment.ident.clone()).collect();
+        if path.leading_colon.is_none() {
+            if let Some(Some(full)) = segments.first().and_then(|first| self.imports.get(&first.to_string())) {
+                segments.splice(..1, full.iter().cloned());
+            }
+        }
+        syn::Path { leading_colon: path.leading_colon, segments: segments.into_iter().map(syn::PathSegment::from).collect() }
+    }
+
+    /// import_macros: `#[macro_use] extern crate`가 들여오던 매크로를 호출하는 모듈마다 `use`를 추가하고,
+    /// 선언은 파일 루트에서 호출하는 매크로의 `use`로 바꿉니다. (호출하지 않으면 지움)
+    fn import_macros(&mut self, rule: &ModernizerRule, item: &ItemExternCrate, items: &mut [Item]) -> Option<Vec<Item>> {
+        let span = item.span();
+        let uses = match macro_use::analyze(items, item) {
+            Ok(uses) => uses,
+            Err(reason) => {
+                self.record_skip(rule, span, &reason);
+                self.explain(rule, span, &format!("rejected: {}", reason));
+                return None;
+            }
+        };
+        let template = self.select_template(rule, span)?;
+        let imports = |macros: &BTreeSet<String>| -> Result<Vec<Item>> {
+            macros
+                .iter()
+                .map(|name| {
+                    let mut captures = Captures::new();
+                    captures.insert("crate".to_string(), template::Capture::Ident(item.ident.clone()));
+                    captures.insert("macro".to_string(), template::Capture::Ident(Ident::new(name, span)));
+                    template::expand_item(template, &captures)
+                })
+                .collect()
+        };
+        // 안쪽 모듈에 추가할 `use` (모듈 경로: 아이템 목록의 위치)
+        let mut pending = Vec::new();
+        let mut modules = vec![(Vec::new(), &*items, &uses)];
+        while let Some((path, module_items, module)) = modules.pop() {
+            for (index, inner) in &module.modules {
+                let Item::Mod(ItemMod { ident, content: Some((brace, content)), .. }) = &module_items[*index] else { continue };
+                let mut inner_path = path.clone();
+                inner_path.push(*index);
+                if !inner.macros.is_empty() {
+                    match imports(&inner.macros) {
+                        Ok(new_items) => pending.push((inner_path.clone(), brace.span.open(), ident.to_string(), new_items)),
+                        Err(err) => {
+                            self.template_error(rule, span, err);
+                            return None;
+                        }
+                    }
+                }
+                modules.push((inner_path, content, inner));
+            }
+        }
+        let replacement = match imports(&uses.macros) {
+            Ok(replacement) => replacement,
+            Err(err) => {
+                self.template_error(rule, span, err);
+                return None;
+            }
+        };
+
+        self.explain(rule, span, "matched");
+        let (recorded, was_changed) = (self.changes.len(), self.changed);
+        // 안쪽 모듈의 `use`는 여는 중괄호 바로 뒤에 들여쓰기해서 추가
+        // (다른 `#[macro_use]` 선언이 같은 모듈에 추가한 `use`가 있으면 그 편집을 이어서 덮어씀)
+        for (_, brace, module, new_items) in &pending {
+            let code: Vec<String> = new_items.iter().map(item_code).collect();
+            let mut change = AppliedChange::new(rule, *brace, &format!("mod {} {{", module));
+            let earlier = self
+                .changes
+                .iter()
+                .rev()
+                .filter(|earlier| earlier.rule_id == rule.id && earlier.location.byte_start == change.location.byte_start)
+                .find_map(|earlier| earlier.edit.as_ref())
+                .map_or("{", |edit| edit.code.as_str());
+            change.edit = Some(SourceEdit::new(&change.location, format!("{{\n    {}{}", code.join("\n    "), &earlier[1..])));
+            change.replacement = Some(code.join(" "));
+            change.review_note = None;
+            self.record_change(change);
+        }
+        let code: Vec<String> = replacement.iter().map(item_code).collect();
+        let mut change = AppliedChange::new(rule, span, &self.original_text(span, item));
+        change.replacement = Some(code.join(" "));
+        change.edit = match code.is_empty() {
+            true => self.removal_edit(&change.location),
+            false => Some(SourceEdit::new(&change.location, code.join("\n"))),
+        };
+        self.record_change(change);
+        if let ReviewDecision::Reject = self.review_change(recorded, was_changed, false) {
+            return None;
+        }
+
+        // 바깥 모듈에 먼저 추가하면 안쪽 모듈의 위치가 밀리므로 안쪽부터 추가
+        for (path, _, _, new_items) in pending.into_iter().rev() {
+            if let Some(content) = module_items(items, &path) {
+                content.splice(0..0, new_items);
+            }
+        }
+        Some(replacement)
+    }
+
+    /// 아이템을 지우는 소스 편집. 아이템이 줄을 혼자 차지하면 줄바꿈까지 지웁니다.
+    fn removal_edit(&self, location: &Location) -> Option<SourceEdit> {
+        let (start, end) = (location.byte_start, location.byte_end);
+        let line_start = self.source.get(..start)?.rfind('\n').map_or(0, |index| index + 1);
+        let line_end = self.source.get(end..)?.find('\n').map_or(self.source.len(), |index| end + index + 1);
+        let alone = self.source[line_start..start].trim().is_empty() && self.source[end..line_end].trim().is_empty();
+        let range = if alone { line_start..line_end } else { start..end };
+        Some(SourceEdit { range, code: String::new() })
+    }
+
+    /// 로드된 규칙을 순회하며 `?` 식을 변환합니다.
+    /// method_name이 있으면 `Ok(x?)`처럼 그 이름의 호출이 `?` 식 하나만 감싼 경우, 비어 있으면 모든 `x?`에 매칭합니다.
+    fn transform_expr_try(&mut self, expr: &Expr, span: Span) -> Option<Expr> {
+        let (func, expr_try) = match expr {
+            Expr::Try(expr_try) => (None, expr_try),
+            Expr::Call(expr_call) => match (&*expr_call.func, expr_call.args.first()) {
+                (Expr::Path(func), Some(Expr::Try(expr_try))) if expr_call.args.len() == 1 => (Some(func), expr_try),
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        for (index, rule) in self.rules.iter().enumerate() {
+            if rule.ast_type != "ExprTry" { continue; }
+            match func {
+                None if !rule.method_name.is_empty() => continue,
+                Some(func) if rule.method_name.is_empty() || !path_matches(&func.path, &rule.method_name) => continue,
+                _ => {}
+            }
+            if self.is_disabled(rule, span) { continue; }
+
+            if self.test_behavior(rule) != TestBehavior::Apply {
+                self.record_skip(rule, span, "inside #[test] fn");
+                self.explain(rule, span, "rejected: inside #[test] fn (test_behavior is not apply)");
+                continue;
+            }
+            let Some(template) = self.select_template(rule, span) else { continue };
+
+            let mut captures = Captures::new();
+            captures.insert("inner".to_string(), template::Capture::Expr(expr_try.expr.clone()));
+            captures.insert("try".to_string(), template::Capture::Expr(Box::new(Expr::Try(expr_try.clone()))));
+            if let Some(func) = func {
+                captures.insert("func".to_string(), template::Capture::Expr(Box::new(Expr::Path(func.clone()))));
+            }
+
+            match self.expand_template(rule, template, captures, expr, span) {
+                Ok(new_expr) => {
+                    self.explain(rule, span, "matched");
+                    self.explain_shadowed(&self.rules[index + 1..], rule, span);
+                    let change = AppliedChange::new(rule, span, &self.original_text(span, expr))
+                        .in_function(self.fn_name_stack.last().map(String::as_str));
+                    self.record_change(change);
+                    return Some(new_expr);
+                }
+                Err(err) => self.template_error(rule, span, err),
+            }
+        }
+        None
+    }
+
+    /// 로드된 규칙을 순회하며 이항 연산식을 변환합니다.
+    /// 왼쪽 피연산자가 method_name 메서드 호출이고, 연산자가 binary_ops에 있으며, 오른쪽 피연산자가 match_args를 만족할 때 적용합니다.
+    fn transform_expr_binary(&mut self, expr_binary: &ExprBinary, span: Span) -> Option<Expr> {
+        let Expr::MethodCall(left_call) = &*expr_binary.left else { return None };
+        let method_name = left_call.method.to_string();
+        let op = expr_binary.op.to_token_stream().to_string();
+
+        for (index, rule) in self.rules.iter().enumerate() {
+            if rule.ast_type != "ExprBinary" || rule.method_name != method_name { continue; }
+            if self.is_disabled(rule, span) { continue; }
+
+            if !rule.binary_ops.is_empty() && !rule.binary_ops.contains(&op) {
+                self.explain(rule, span, &format!(
+                    "rejected: binary_ops is [{}], found `{}`", rule.binary_ops.join(", "), op
+                ));
+                continue;
+            }
+            if let Some(args_count) = rule.args_count.filter(|args_count| !args_count.matches(left_call.args.len())) {
+                self.explain(rule, span, &format!(
+                    "rejected: args_count is {}, found {} argument(s)", args_count, left_call.args.len()
+                ));
+                continue;
+            }
+            if let Err(reason) = Self::check_match_args(rule, [&*expr_binary.right]) {
+                self.explain(rule, span, &format!("rejected: {}", reason));
+                continue;
+            }
+            if self.test_behavior(rule) != TestBehavior::Apply {
+                self.record_skip(rule, span, "inside #[test] fn");
+                self.explain(rule, span, "rejected: inside #[test] fn (test_behavior is not apply)");
+                continue;
+            }
+            let Some(template) = self.select_template(rule, span) else { continue };
+
+            let mut captures = Captures::new();
+            captures.insert("left".to_string(), template::Capture::Expr(expr_binary.left.clone()));
+            captures.insert("right".to_string(), template::Capture::Expr(expr_binary.right.clone()));
+            captures.insert("receiver".to_string(), template::Capture::Expr(left_call.receiver.clone()));
+            captures.insert("method".to_string(), template::Capture::Ident(left_call.method.clone()));
+            template::bind_args(&mut captures, &left_call.args);
+
+            let original = Expr::Binary(expr_binary.clone());
+            match self.expand_template(rule, template, captures, &original, span) {
+                Ok(new_expr) => {
+                    self.explain(rule, span, "matched");
+                    self.explain_shadowed(&self.rules[index + 1..], rule, span);
+                    let change = AppliedChange::new(rule, span, &self.original_text(span, &original))
+                        .in_function(self.fn_name_stack.last().map(String::as_str));
+                    self.record_change(change);
+                    return Some(new_expr);
+                }
+                Err(err) => self.template_error(rule, span, err),
+            }
+        }
+        None
+    }
+
+    /// 식 패턴 규칙(ast_type: Pattern)을 평가합니다. 패턴의 메타변수 바인딩을 템플릿 자리표시자로 사용합니다.
+    fn transform_expr_pattern(&mut self, expr: &Expr, span: Span) -> Option<Expr> {
+        for (index, rule) in self.rules.iter().enumerate() {
+            let Some(pattern) = &self.patterns[index] else { continue };
+            let Some(captures) = pattern.matches(expr) else { continue };
+            if self.is_disabled(rule, span) { continue; }
+
+            if self.test_behavior(rule) != TestBehavior::Apply {
+                self.record_skip(rule, span, "inside #[test] fn");
+                self.explain(rule, span, "rejected: inside #[test] fn (test_behavior is not apply)");
+                continue;
+            }
+            let Some(template) = self.select_template(rule, span) else { continue };
+
+            match self.expand_template(rule, template, captures, expr, span) {
+                Ok(new_expr) => {
+                    self.explain(rule, span, "matched");
+                    self.explain_shadowed(&self.rules[index + 1..], rule, span);
+                    let original = self.original_text(span, expr);
+                    self.record_change(AppliedChange::new(rule, span, &original).in_function(self.fn_name_stack.last().map(String::as_str)));
+                    return Some(new_expr);
+                }
+                Err(err) => self.template_error(rule, span, err),
+            }
+        }
+        None
+    }
+
+    /// 로드된 규칙을 순회하며 오류를 그대로 전파하는 match 식을 변환합니다. (ExprMatch 규칙, 예: `match x { Ok(v) => v, Err(e) => return Err(e) }`)
+    /// 갈래가 정확히 두 개이고 모양이 일치할 때만 적용하며, `Err(e.into())`로 변환하던 경우에는 검토 메모를 덧붙입니다.
+    fn transform_expr_match(&mut self, expr_match: &ExprMatch, span: Span) -> Option<Expr> {
+        for (index, rule) in self.rules.iter().enumerate() {
+            if rule.ast_type != "ExprMatch" { continue; }
+            // 갈래 두 개 중 하나가 `<method_name>(..)` 패턴이면 부분 일치 (--explain 대상)
+            let is_candidate = expr_match.arms.len() == 2
+                && expr_match.arms.iter().any(|arm| {
+                    matches!(&arm.pat, Pat::TupleStruct(pat) if path_matches(&pat.path, &rule.method_name))
+                });
+            if !is_candidate { continue; }
+            if self.is_disabled(rule, span) { continue; }
+
+            let converts_error = match propagation_match_shape(expr_match, &rule.method_name) {
+                Ok(converts_error) => converts_error,
+                Err(reason) => {
+                    self.explain(rule, span, &format!("rejected: {}", reason));
+                    continue;
+                }
+            };
+            if self.test_behavior(rule) != TestBehavior::Apply {
+                self.record_skip(rule, span, "inside #[test] fn");
+                self.explain(rule, span, "rejected: inside #[test] fn (test_behavior is not apply)");
+                continue;
+            }
+            let Some(template) = self.select_template(rule, span) else { continue };
+
+            let mut captures = Captures::new();
+            captures.insert("scrutinee".to_string(), template::Capture::Expr(expr_match.expr.clone()));
+
+            let original = Expr::Match(expr_match.clone());
+            match self.expand_template(rule, template, captures, &original, span) {
+                Ok(new_expr) => {
+                    self.explain(rule, span, "matched");
+                    self.explain_shadowed(&self.rules[index + 1..], rule, span);
+                    let mut change = AppliedChange::new(rule, span, &self.original_text(span, &original))
+                        .in_function(self.fn_name_stack.last().map(String::as_str));
+                    if converts_error {
+                        let note = "The `Err` arm converted the error with `.into()`; `?` converts with `From::from` instead. \
+                                    Verify the enclosing fn's error type has the same `From` impl the `.into()` call relied on.";
+                        change.review_note = Some(match change.review_note.take() {
+                            Some(rule_note) => format!("{} {}", rule_note, note),
+                            None => note.to_string(),
+                        });
+                    }
+                    self.record_change(change);
+                    return Some(new_expr);
+                }
+                Err(err) => self.template_error(rule, span, err),
+            }
+        }
+        None
+    }
+
+    /// 함수 본문의 마지막 문장 `return <식>;`을 세미콜론 없는 꼬리 식으로 바꿉니다. (ExprReturn 규칙)
+    /// 중첩 블록의 `return`은 바꾸면 의미가 달라지므로 함수 본문 블록에만 호출합니다.
+    fn transform_tail_return(&mut self, block: &mut Block) {
+        let Some(Stmt::Expr(Expr::Return(expr_return), _)) = block.stmts.last() else { return };
+        let Some(value) = expr_return.expr.as_deref() else { return };
+        let span = block.stmts.last().map_or_else(Span::call_site, |stmt| stmt.span());
+        let call = match value {
+            Expr::Call(expr_call) => match &*expr_call.func {
+                Expr::Path(func) => Some((func, expr_call)),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        for (index, rule) in self.rules.iter().enumerate() {
+            if rule.ast_type != "ExprReturn" { continue; }
+            let args = match call {
+                Some((func, expr_call)) if !rule.method_name.is_empty() && path_matches(&func.path, &rule.method_name) => {
+                    Some(&expr_call.args)
+                }
+                _ if rule.method_name.is_empty() => call.map(|(_, expr_call)| &expr_call.args),
+                _ => continue,
+            };
+            if self.is_disabled(rule, span) { continue; }
+
+            if let Some(args) = args {
+                if let Some(args_count) = rule.args_count.filter(|args_count| !args_count.matches(args.len())) {
+                    self.explain(rule, span, &format!(
+                        "rejected: args_count is {}, found {} argument(s)", args_count, args.len()
+                    ));
+                    continue;
+                }
+                if let Err(reason) = Self::check_match_args(rule, args) {
+                    self.explain(rule, span, &format!("rejected: {}", reason));
+                    continue;
+                }
+            }
+            if self.test_behavior(rule) != TestBehavior::Apply {
+                self.record_skip(rule, span, "inside #[test] fn");
+                self.explain(rule, span, "rejected: inside #[test] fn (test_behavior is not apply)");
+                continue;
+            }
+            let Some(template) = self.select_template(rule, span) else { continue };
+
+            let mut captures = Captures::new();
+            captures.insert("value".to_string(), template::Capture::Expr(Box::new(value.clone())));
+            if let Some((func, expr_call)) = call {
+                captures.insert("func".to_string(), template::Capture::Expr(Box::new(Expr::Path(func.clone()))));
+                template::bind_args(&mut captures, &expr_call.args);
+            }
+
+            let original = Expr::Return(expr_return.clone());
+            match self.expand_template(rule, template, captures, &original, span) {
+                Ok(new_expr) => {
+                    self.explain(rule, span, "matched");
+                    self.explain_shadowed(&self.rules[index + 1..], rule, span);
+                    let change = AppliedChange::new(rule, span, &self.original_text(span, &original))
+                        .in_function(self.fn_name_stack.last().map(String::as_str));
+                    let (recorded, was_changed) = (self.changes.len(), self.changed);
+                    self.record_change(change);
+                    self.record_replacement(recorded, &new_expr);
+                    let new_expr = match self.review_change(recorded, was_changed, true) {
+                        ReviewDecision::Accept => new_expr,
+                        ReviewDecision::Edit(edited) => *edited,
+                        ReviewDecision::Reject => return,
+                    };
+                    if let Some(last) = block.stmts.last_mut() {
+                        *last = Stmt::Expr(new_expr, None);
+                    }
+                    return;
+                }
+                Err(err) => self.template_error(rule, span, err),
+            }
+        }
+    }
+
+    /// futures_to_async: futures 0.1 퓨처를 반환하는 함수를 `async fn`으로 바꿉니다. (ItemFn 규칙, 하위 노드 변환 뒤에 호출)
+    /// 반환 타입은 `Result<Item, Error>`가 되고, 꼬리 식과 `return` 식의 조합자 체인은 `.await` 문장으로 풉니다.
+    /// 호출하는 쪽과 `.await`한 퓨처의 이식은 자동으로 할 수 없으므로 함수에 검토 안내를 붙입니다.
+    /// 트레이트가 시그니처를 정하는 트레이트 impl의 메서드와 `const fn`은 건너뜁니다.
+    fn transform_async_fn(
+        &mut self,
+        attrs: &mut Vec<Attribute>,
+        vis: &Visibility,
+        sig: &mut Signature,
+        block: &mut Block,
+        span: Span,
+        in_trait_impl: bool,
+    ) {
+        if sig.asyncness.is_some() {
+            return;
+        }
+        for index in 0..self.rules.len() {
+            if self.rules[index].ast_type != "ItemFn" { continue; }
+            let rule = &self.rules[index].clone();
+            let Some((item, error)) = futures_async::future_output(&sig.output, &rule.method_name) else { continue };
+            if self.is_disabled(rule, span) { continue; }
+            let converted = if sig.constness.is_some() {
+                Err("`const fn` cannot be async".to_string())
+            } else if in_trait_impl {
+                Err("the trait defines the method signature".to_string())
+            } else {
+                futures_async::convert_body(block)
+            };
+            let body = match converted {
+                Ok(body) => body,
+                Err(reason) => {
+                    self.record_skip(rule, span, &reason);
+                    self.explain(rule, span, &format!("rejected: {}", reason));
+                    continue;
+                }
+            };
+
+            let mut new_sig = sig.clone();
+            new_sig.asyncness = Some(Default::default());
+            new_sig.output = parse_quote!(-> Result<#item, #error>);
+            let awaited: Vec<String> = body.awaited.iter().map(|expr| format!("`{}`", expr_source(expr))).collect();
+            let awaited = match awaited.is_empty() {
+                true => String::new(),
+                false => format!("{} must be std futures (port them or call `.compat()`). ", awaited.join(", ")),
+            };
+            let scoped = match body.scoped {
+                true => "`async move { .. }.await` blocks may need the error type spelled out (`Ok::<_, E>(..)`). ",
+                false => "",
+            };
+            let review = self.review_annotation(
+                rule,
+                format!(
+                    "converted from a futures 0.1 combinator chain. {}{}Callers must `.await` this fn instead of chaining \
+                     combinators, the `Send`/`'static` bounds of the old return type are no longer stated, and closure \
+                     parameters now share the function scope. Ref: {}",
+                    awaited, scoped, rule.doc_url
+                ),
+                "port awaited futures and callers to async/await".to_string(),
+            );
+            let mut new_attrs = attrs.clone();
+            new_attrs.splice(0..0, review);
+
+            // prettyplease는 match 갈래의 쉼표나 한 식짜리 블록을 정리하므로, 출력한 코드를 다시 파싱해 AST를 원본 서식 보존 출력과 맞춤
+            let code = fn_code(&new_attrs, vis, &new_sig, &body.block);
+            let Ok(printed) = syn::parse_str::<ImplItemFn>(&code) else {
+                self.explain(rule, span, "rejected: converted function does not parse");
+                continue;
+            };
+
+            self.explain(rule, span, "matched");
+            self.explain_shadowed(&self.rules[index + 1..], rule, span);
+            let header = vis.span().join(sig.span()).unwrap_or_else(|| sig.span());
+            let original = format!("{} {{ ... }}", self.original_text(header, &*sig));
+            let mut change = AppliedChange::new(rule, span, &original).in_function(self.fn_name_stack.last().map(String::as_str));
+            let replacement = fn_code(&[], vis, &printed.sig, &parse_quote!({}));
+            change.replacement = Some(format!("{} {{ ... }}", replacement.trim_end_matches("{}").trim_end()));
+            change.edit = Some(SourceEdit::new(&change.location, code));
+            let (recorded, was_changed) = (self.changes.len(), self.changed);
+            self.record_change(change);
+            if let ReviewDecision::Reject = self.review_change(recorded, was_changed, false) {
+                return;
+            }
+            *attrs = printed.attrs;
+            *sig = printed.sig;
+            *block = printed.block;
+            return;
+        }
+    }
+
+    /// `#[derive(...)]` 속성의 트레이트 순서를 규칙의 order에 맞춰 정렬합니다.
+    /// 경로로 적힌 트레이트(`std::fmt::Debug` 등)는 마지막 이름으로 비교합니다.
+    fn transform_attribute(&mut self, attr: &mut Attribute) {
+        let span = attr.span();
+
+        for rule in &self.rules {
+            if rule.ast_type != "Attribute" || !path_matches(attr.path(), &rule.method_name) { continue; }
+            if self.is_disabled(rule, span) { continue; }
+            if rule.action != RuleAction::ReorderDerive {
+                self.explain(rule, span, "rejected: only the reorder_derive action is supported for Attribute");
+                continue;
+            }
+            let Some(order) = rule.order.as_deref() else {
+                self.explain(rule, span, "rejected: rule has no `order` list");
+                continue;
+            };
+            let Ok(traits) = attr.parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated) else {
+                self.explain(rule, span, "rejected: attribute arguments are not a list of paths");
+                continue;
+            };
+
+            let rank = |path: &syn::Path| {
+                let name = path.segments.last().map(|segment| segment.ident.to_string()).unwrap_or_default();
+                order.iter().position(|known| *known == name)
+            };
+            let unknown: Vec<String> = traits
+                .iter()
+                .filter(|path| rank(path).is_none())
+                .map(|path| path.to_token_stream().to_string().replace(' ', ""))
+                .collect();
+            if !unknown.is_empty() {
+                log!("[NOTE] {} ({}): unknown derive trait(s) kept after known ones: {}",
+                    rule.id, format_span(span), unknown.join(", "));
+            }
+
+            let keys: Vec<usize> = traits.iter().map(|path| rank(path).unwrap_or(order.len())).collect();
+            if keys.windows(2).all(|pair| pair[0] <= pair[1]) {
+                self.explain(rule, span, "rejected: already in canonical order");
+                continue;
+            }
+            let mut sorted: Vec<syn::Path> = traits.into_iter().collect();
+            sorted.sort_by_key(|path| rank(path).unwrap_or(order.len()));
+
+            let original = self.original_text(span, &*attr);
+            let path = attr.path().clone();
+            let meta = std::mem::replace(&mut attr.meta, parse_quote!(#path(#(#sorted),*)));
+            self.explain(rule, span, "matched");
+            let mut change = AppliedChange::new(rule, span, &original).in_function(self.fn_name_stack.last().map(String::as_str));
+            change.replacement = Some(attribute_source(attr));
+            change.edit = Some(SourceEdit::new(&change.location, attribute_source(attr)));
+            let (recorded, was_changed) = (self.changes.len(), self.changed);
+            self.record_change(change);
+            if let ReviewDecision::Reject = self.review_change(recorded, was_changed, false) {
+                attr.meta = meta;
+            }
+            return;
+        }
+    }
+
+    /// 변환된 문장에 붙일 검토 안내(`///` 문서 주석 속성)를 만듭니다.
+    /// --no-annotations이거나 규칙의 emit_comment가 false이면 None을 반환합니다.
+    fn review_annotation(&self, rule: &ModernizerRule, verbose: String, compact: String) -> Option<Attribute> {
+        if !rule.emit_comment {
+            return None;
+        }
+        let text = match self.annotations? {
+            AnnotationStyle::Verbose => format!(" MODERNIZER REVIEW ({}): {}", rule.id, verbose),
+            AnnotationStyle::Compact => format!(" REVIEW({}): {}", rule.id, compact),
+        };
+        Some(parse_quote!(#[doc = #text]))
+    }
+
+    /// `--scoped-maybeuninit`: 블록 안의 `let mut x = mem::uninitialized(); init(&mut x);` 패턴을
+    /// `MaybeUninit` 문장 세 개로 변환합니다. (식 단위 규칙보다 먼저 실행되어야 함)
+    fn transform_uninit_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        let mut index = 0;
+        while index + 1 < stmts.len() {
+            match self.scoped_uninit(&stmts[index], &stmts[index + 1]) {
+                Some(replacement) => {
+                    stmts.splice(index..index + 2, replacement);
+                    index += 3;
+                }
+                None => index += 1,
+            }
+        }
+    }
+
+    /// 선언문과 바로 다음 초기화 문장이 패턴과 일치하면 교체할 문장들을 만듭니다.
+    fn scoped_uninit(&mut self, decl: &Stmt, init: &Stmt) -> Option<[Stmt; 3]> {
+        // 1. `let mut x[: T] = [unsafe {] mem::uninitialized() [}];`
+        let Stmt::Local(local) = decl else { return None };
+        let (ident, ty) = match &local.pat {
+            Pat::Ident(pat) => (pat, None),
+            Pat::Type(pat_type) => match &*pat_type.pat {
+                Pat::Ident(pat) => (pat, Some(pat_type.ty.clone())),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        if ident.mutability.is_none() || ident.by_ref.is_some() || ident.subpat.is_some() {
+            return None;
+        }
+        let ident = ident.ident.clone();
+
+        let local_init = local.init.as_ref()?;
+        if local_init.diverge.is_some() {
+            return None;
+        }
+        let call = match &*local_init.expr {
+            Expr::Call(call) => call,
+            Expr::Unsafe(unsafe_block) => match unsafe_block.block.stmts.as_slice() {
+                [Stmt::Expr(Expr::Call(call), None)] => call,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let Expr::Path(func) = &*call.func else { return None };
+        if !call.args.is_empty() {
+            return None;
+        }
+        let rule = self
+            .rules
+            .iter()
+            .find(|rule| {
+                rule.ast_type == "ExprCall"
+                    && rule.disabled_by.is_none()
+                    && rule.method_name.rsplit("::").next() == Some("uninitialized")
+                    && path_matches(&func.path, &rule.method_name)
+            })?
+            .clone();
+
+        // 2. 바로 다음 문장: `&mut x`를 인자로 넘기는 호출 → `x.as_mut_ptr()`
+        let mut init = init.clone();
+        let Stmt::Expr(init_expr, Some(_)) = &mut init else { return None };
+        let args = match init_expr {
+            Expr::Call(call) => &mut call.args,
+            Expr::MethodCall(method_call) => &mut method_call.args,
+            _ => return None,
+        };
+        let mut replaced = false;
+        for arg in args.iter_mut() {
+            if let Expr::Reference(reference) = arg {
+                let is_target = matches!(&*reference.expr, Expr::Path(path) if path.path.is_ident(&ident));
+                if reference.mutability.is_some() && is_target {
+                    *arg = parse_quote!(#ident.as_mut_ptr());
+                    replaced = true;
+                }
+            }
+        }
+        if !replaced {
+            return None;
+        }
+
+        let span = decl.span();
+        if let Err(reason) = self.check_fn_context(&rule) {
+            self.record_skip(&rule, span, &reason);
+            return None;
+        }
+        self.explain(&rule, span, "matched");
+        let original = self.original_text(span, decl);
+        self.record_change(AppliedChange::new(&rule, span, &original).in_function(self.fn_name_stack.last().map(String::as_str)));
+
+        // 3. 선언 → MaybeUninit, 초기화 후 assume_init으로 다시 바인딩
+        //    (parse_quote! 주석은 출력되지 않으므로 검토 안내는 `///` 문서 주석 속성으로 남김)
+        let mut declare: Stmt = match ty {
+            Some(ty) => parse_quote!(let mut #ident = std::mem::MaybeUninit::<#ty>::uninit();),
+            None => parse_quote!(let mut #ident = std::mem::MaybeUninit::uninit();),
+        };
+        let review = self.review_annotation(
+            &rule,
+            format!(
+                "`{}` is now a MaybeUninit. Verify the call below fully initializes it \
+                 through the raw pointer before `assume_init()`. Ref: {}",
+                ident, rule.doc_url
+            ),
+            format!("verify `{}` is fully initialized before `assume_init()`", ident),
+        );
+        if let (Stmt::Local(declare), Some(review)) = (&mut declare, review) {
+            declare.attrs.push(review);
+        }
+        let finish: Stmt = parse_quote!(let #ident = unsafe { #ident.assume_init() };);
+
+        Some([declare, init, finish])
+    }
+}
+
+/// 수신자 체인의 호출 인자를 바인딩합니다. 한 단계 안쪽 호출마다 접두사 `recv_`가 붙습니다.
+/// (예: `v.get(i).unwrap()` → `#recv_arg0` = `i`, `a.f(x).g(y).h()` → `#recv_arg0` = `y`, `#recv_recv_arg0` = `x`)
+fn bind_receiver_chain_args(captures: &mut Captures, mut receiver: &Expr) {
+    let mut prefix = String::from("recv_");
+    loop {
+        match receiver {
+            Expr::MethodCall(call) => {
+                template::bind_prefixed_args(captures, &prefix, &call.args);
+                receiver = &call.receiver;
+            }
+            Expr::Call(call) => {
+                template::bind_prefixed_args(captures, &prefix, &call.args);
+                return;
+            }
+            _ => return,
+        }
+        prefix.insert_str(0, "recv_");
+    }
+}
+
+/// Result를 반환하는 것으로 널리 알려진 메서드/함수 이름 (receiver_kind 추정용)
+const RESULT_RETURNING: &[&str] = &[
+    "parse", "read_to_string", "read_to_end", "read_line", "read_dir", "read", "write", "write_all", "flush",
+    "open", "create", "metadata", "canonicalize", "try_into", "try_from", "from_str", "from_utf8", "var",
+    "lock", "join", "send", "recv", "bind", "connect", "accept",
+];
+/// Option을 반환하는 것으로 널리 알려진 메서드/함수 이름 (receiver_kind 추정용)
+const OPTION_RETURNING: &[&str] = &[
+    "get", "get_mut", "first", "last", "pop", "next", "next_back", "find", "find_map", "position", "max", "min",
+    "max_by_key", "min_by_key", "nth", "peek", "strip_prefix", "strip_suffix", "checked_add", "checked_sub",
+    "checked_mul", "checked_div", "ok", "err", "take", "to_str", "file_name", "extension", "parent",
+];
+
+/// 수신자 식의 모양으로 Result/Option 여부를 추정합니다. (예: `s.parse()` → "Result", `v.first()` → "Option")
+fn guess_receiver_type(receiver: &Expr) -> Option<&'static str> {
+    let name = match receiver {
+        Expr::MethodCall(method_call) => method_call.method.to_string(),
+        Expr::Call(call) => match &*call.func {
+            Expr::Path(func) => func.path.segments.last()?.ident.to_string(),
+            _ => return None,
+        },
+        Expr::Paren(paren) => return guess_receiver_type(&paren.expr),
+        _ => return None,
+    };
+    match name.as_str() {
+        "Ok" | "Err" => Some("Result"),
+        "Some" => Some("Option"),
+        name if RESULT_RETURNING.contains(&name) => Some("Result"),
+        name if OPTION_RETURNING.contains(&name) => Some("Option"),
+        _ => None,
+    }
+}
+
+/// `#[test]`, `#[tokio::test]`처럼 마지막 경로가 `test`인 속성이 붙은 함수인지 확인합니다.
+fn is_test_fn(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path().segments.last().is_some_and(|segment| segment.ident == "test"))
+}
+
+/// 호출 경로의 끝부분이 규칙의 (선택적으로 한정된) 경로와 일치하는지 확인합니다.
+/// 예: "uninitialized", "mem::uninitialized" 모두 `std::mem::uninitialized`와 일치
+fn path_matches(path: &syn::Path, pattern: &str) -> bool {
+    let wanted: Vec<&str> = pattern.trim_start_matches("::").split("::").collect();
+    if wanted.len() > path.segments.len() {
+        return false;
+    }
+    path.segments
+        .iter()
+        .rev()
+        .zip(wanted.iter().rev())
+        .all(|(segment, name)| segment.ident.unraw() == name)
+}
+
+/// 블록 안에 (클로저와 중첩 아이템 밖의) `return`이 있는지 여부
+fn contains_return(block: &Block) -> bool {
+    struct Finder(bool);
+    impl<'ast> syn::visit::Visit<'ast> for Finder {
+        fn visit_expr_return(&mut self, _: &'ast syn::ExprReturn) {
+            self.0 = true;
+        }
+        fn visit_expr_closure(&mut self, _: &'ast ExprClosure) {}
+        fn visit_item(&mut self, _: &'ast Item) {}
+    }
+    let mut finder = Finder(false);
+    syn::visit::visit_block(&mut finder, block);
+    finder.0
+}
+
+/// 경로가 규칙의 전체 경로(예: "failure::Error")로 시작하면 일치한 세그먼트 개수를 반환합니다. (앞의 `::`는 무시)
+fn path_prefix_len(path: &syn::Path, pattern: &str) -> Option<usize> {
+    let wanted: Vec<&str> = pattern.trim_start_matches("::").split("::").collect();
+    let matches = wanted.len() <= path.segments.len() && path.segments.iter().zip(&wanted).all(|(segment, name)| segment.ident.unraw() == name);
+    matches.then_some(wanted.len())
+}
+
+/// 식의 맨 앞 경로 (`AtomicUsize::new(0)`의 `AtomicUsize::new`, 메서드 호출은 수신자의 경로)
+pub(crate) fn head_path(expr: &mut Expr) -> Option<&mut syn::Path> {
+    match expr {
+        Expr::Path(expr_path) if expr_path.qself.is_none() => Some(&mut expr_path.path),
+        Expr::Call(call) => head_path(&mut call.func),
+        Expr::MethodCall(call) => head_path(&mut call.receiver),
+        _ => None,
+    }
+}
+
+/// 서식 문자열에 캡처할 수 있는 식별자 하나뿐인 식이면 그 이름 (`self`와 raw 식별자 제외)
+fn captured_ident(expr: &Expr) -> Option<String> {
+    let Expr::Path(ExprPath { attrs, qself: None, path }) = expr else { return None };
+    let name = path.get_ident()?.to_string();
+    (attrs.is_empty() && name != "self" && !name.starts_with("r#")).then_some(name)
+}
+
+/// 기본 타입으로 시작하는 경로인지 확인합니다. (`u32::MAX`)
+fn is_primitive_path(path: &syn::Path) -> bool {
+    path.leading_colon.is_none() && path.segments.first().is_some_and(|first| PRIMITIVE_TYPES.iter().any(|name| first.ident == name))
+}
+
+/// rename_macro: 매크로 토큰과 구분자는 그대로 두고 경로만 바꿉니다.
+fn rename_macro(expr_macro: &ExprMacro, new_path: &str) -> Result<Expr> {
+    let path = syn::parse_str::<syn::Path>(new_path.trim_end_matches('!'))
+        .with_context(|| format!("`{}` is not a valid macro path", new_path))?;
+    let mut renamed = expr_macro.clone();
+    renamed.mac.path = path;
+    Ok(Expr::Macro(renamed))
+}
+
+/// 오류를 그대로 전파하는 match인지 확인합니다. (`value_variant`: "Ok" 또는 "Some")
+/// `Ok(v) => v` 갈래와 `Err(e) => return Err(e)` (또는 `Err(e.into())`, `{ return Err(e); }`) 갈래만 허용하며,
+/// 일치하면 오류를 `.into()`로 변환하던 경우인지를, 아니면 거부 이유를 반환합니다.
+fn propagation_match_shape(expr_match: &ExprMatch, value_variant: &str) -> Result<bool, String> {
+    let error_variant = match value_variant.rsplit("::").next() {
+        Some("Ok") => "Err",
+        Some("Some") => "None",
+        _ => return Err(format!("method_name must be `Ok` or `Some`, found `{}`", value_variant)),
+    };
+    if expr_match.arms.iter().any(|arm| arm.guard.is_some()) {
+        return Err("match arm has a guard".to_string());
+    }
+    let is_value_arm = |arm: &&syn::Arm| matches!(&arm.pat, Pat::TupleStruct(pat) if path_matches(&pat.path, value_variant));
+    let (Some(value_arm), Some(error_arm)) = (
+        expr_match.arms.iter().find(is_value_arm),
+        expr_match.arms.iter().find(|arm| !is_value_arm(arm)),
+    ) else {
+        return Err(format!("expected one `{}(..)` arm and one `{}` arm", value_variant, error_variant));
+    };
+
+    // `Ok(v) => v`
+    let value_binding = single_binding(&value_arm.pat)
+        .ok_or_else(|| format!("`{}` arm must bind exactly one plain name", value_variant))?;
+    if !matches!(&*value_arm.body, Expr::Path(path) if path.path.is_ident(value_binding)) {
+        return Err(format!("`{}` arm must evaluate to its binding `{}`", value_variant, value_binding));
+    }
+
+    // `Err(e) => return Err(e)` / `None => return None`
+    let returned = match &*error_arm.body {
+        Expr::Return(expr_return) => expr_return.expr.as_deref(),
+        Expr::Block(block) => match block.block.stmts.as_slice() {
+            [Stmt::Expr(Expr::Return(expr_return), _)] => expr_return.expr.as_deref(),
+            _ => return Err(format!("`{}` arm block must contain only a `return`", error_variant)),
+        },
+        _ => return Err(format!("`{}` arm must `return` the error", error_variant)),
+    };
+    if error_variant == "None" {
+        let is_none_pat = |pat: &Pat| matches!(pat, Pat::Ident(ident) if ident.ident == "None" && ident.subpat.is_none())
+            || matches!(pat, Pat::Path(path) if path_matches(&path.path, "None"));
+        let returns_none = matches!(returned, Some(Expr::Path(path)) if path_matches(&path.path, "None"));
+        return if is_none_pat(&error_arm.pat) && returns_none {
+            Ok(false)
+        } else {
+            Err("expected `None => return None`".to_string())
+        };
+    }
+
+    let error_binding = match &error_arm.pat {
+        Pat::TupleStruct(pat) if path_matches(&pat.path, "Err") => single_binding(&error_arm.pat),
+        _ => None,
+    }
+    .ok_or_else(|| "`Err` arm must be `Err(<name>)`".to_string())?;
+    let Some(Expr::Call(call)) = returned else {
+        return Err("`Err` arm must `return Err(..)`".to_string());
+    };
+    let is_err_call = matches!(&*call.func, Expr::Path(path) if path_matches(&path.path, "Err"));
+    let is_binding = |expr: &Expr| matches!(expr, Expr::Path(path) if path.path.is_ident(error_binding));
+    match call.args.first() {
+        Some(arg) if is_err_call && call.args.len() == 1 && is_binding(arg) => Ok(false),
+        Some(Expr::MethodCall(method_call))
+            if is_err_call
+                && call.args.len() == 1
+                && method_call.method == "into"
+                && method_call.args.is_empty()
+                && is_binding(&method_call.receiver) =>
+        {
+            Ok(true)
+        }
+        _ => Err(format!("`Err` arm must return `Err({0})` or `Err({0}.into())`", error_binding)),
+    }
+}
+
+/// `Variant(name)` 패턴의 바인딩 이름을 반환합니다. (`ref`, 하위 패턴이 있으면 None)
+fn single_binding(pat: &Pat) -> Option<&Ident> {
+    let Pat::TupleStruct(pat) = pat else { return None };
+    match pat.elems.first() {
+        Some(Pat::Ident(binding)) if pat.elems.len() == 1 && binding.by_ref.is_none() && binding.subpat.is_none() => {
+            Some(&binding.ident)
+        }
+        _ => None,
+    }
+}
+
+impl Modernizer {
+    /// 보고서용 메서드 이름 (`Type::method`, impl/trait 밖이면 메서드 이름만)
+    fn method_name(&self, ident: &Ident) -> String {
+        match self.impl_name_stack.last() {
+            Some(owner) => format!("{}::{}", owner, ident),
+            None => ident.to_string(),
+        }
+    }
+
+    /// --doc-tests: 연속된 문서 주석 속성에서 ```rust 코드 블록을 찾아 변환한 뒤 같은 형식의 속성으로 되돌려 놓습니다.
+    fn transform_doc_tests(&mut self, attrs: &mut Vec<Attribute>) {
+        if !self.doc_tests {
+            return;
+        }
+
+        let mut index = 0;
+        while index < attrs.len() {
+            let Some(info) = doc_line(&attrs[index]).and_then(|line| fence_info(&line)) else {
+                index += 1;
+                continue;
+            };
+            // 닫는 펜스까지의 문서 주석 줄 (중간에 다른 속성이 끼면 블록으로 보지 않음)
+            let mut lines = Vec::new();
+            let mut close = None;
+            for (offset, attr) in attrs[index + 1..].iter().enumerate() {
+                let Some(line) = doc_line(attr) else { break };
+                if fence_info(&line).is_some() {
+                    close = Some(index + 1 + offset);
+                    break;
+                }
+                lines.push(line);
+            }
+            let Some(close) = close else { break };
+
+            let recorded = self.changes.len();
+            let replacement = if is_rust_fence(&info) {
+                self.transform_doc_block(&lines, Location::from_span(attrs[index].span()))
+            } else {
+                None
+            };
+            match replacement {
+                Some(new_lines) => {
+                    // 원본이 `///`/`//!` 주석이면 펜스 사이의 주석 줄만 바꾸는 편집을 블록의 변환에 붙임
+                    if let Some(edit) = self.doc_block_edit(&attrs[index + 1..close], &new_lines) {
+                        for change in &mut self.changes[recorded..] {
+                            change.edit = Some(edit.clone());
+                        }
+                    }
+                    let style = attrs[index].style;
+                    let new_attrs: Vec<Attribute> = new_lines
+                        .iter()
+                        .map(|line| {
+                            let mut attr: Attribute = parse_quote!(#[doc = #line]);
+                            attr.style = style;
+                            attr
+                        })
+                        .collect();
+                    let count = new_attrs.len();
+                    attrs.splice(index + 1..close, new_attrs);
+                    index += count + 2;
+                }
+                None => index = close + 1,
+            }
+        }
+    }
+
+    /// 펜스 사이의 문서 주석 속성(`body`)을 변환된 줄로 바꾸는 소스 편집을 만듭니다.
+    /// 빈 블록이거나 `///`/`//!`가 아닌 형식(`/** */`, `#[doc = ...]`)이 섞여 있으면 None을 반환합니다.
+    fn doc_block_edit(&self, body: &[Attribute], new_lines: &[String]) -> Option<SourceEdit> {
+        let marker = match body.first()?.style {
+            syn::AttrStyle::Outer => "///",
+            syn::AttrStyle::Inner(_) => "//!",
+        };
+        if !body.iter().all(|attr| self.source_text(attr.span()).is_some_and(|text| text.starts_with(marker))) {
+            return None;
+        }
+        let range = body.first()?.span().byte_range().start..body.last()?.span().byte_range().end;
+        let code = new_lines.iter().map(|line| format!("{}{}", marker, line)).collect::<Vec<_>>().join("\n");
+        Some(SourceEdit { range, code })
+    }
+
+    /// 코드 블록 하나를 현재 규칙으로 변환합니다. 변경이 없거나 처리할 수 없으면 None을 반환합니다.
+    /// (`fence`: 여는 펜스 위치. 블록 안의 변경은 보고서에서 이 위치로 기록됩니다)
+    fn transform_doc_block(&mut self, lines: &[String], fence: Location) -> Option<Vec<String>> {
+        // 각 줄의 공통 들여쓰기(보통 `/// ` 뒤의 공백 하나)를 떼어 내고 나중에 다시 붙임
+        let indent = lines
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.len() - line.trim_start().len())
+            .min()?;
+        let prefix = " ".repeat(indent);
+        let code_lines: Vec<&str> = lines.iter().map(|line| line.get(indent..).unwrap_or("")).collect();
+        if code_lines.iter().any(|line| *line == "#" || line.starts_with("# ")) {
+            log!("[DOCTEST] ({}:{}): hidden `# ` lines are not supported, block skipped", fence.line, fence.column);
+            return None;
+        }
+        let code = code_lines.join("\n");
+
+        // doc-test는 rustdoc이 `fn main() { ... }`으로 감싸므로, 아이템만으로 파싱되지 않으면 같은 방식으로 감쌈
+        let (mut ast, source, wrapped) = match syn::parse_file(&code) {
+            Ok(ast) => (ast, code, false),
+            Err(_) => {
+                let wrapped = format!("fn main() {{\n{}\n}}", code);
+                (syn::parse_file(&wrapped).ok()?, wrapped, true)
+            }
+        };
+
+        log!("[DOCTEST] ({}:{}): doc-test block", fence.line, fence.column);
+        let mut block = Modernizer::new(self.rules.clone());
+        block.scoped_maybeuninit = self.scoped_maybeuninit;
+        block.annotations = self.annotations;
+        block.source_path = self.source_path.clone();
+        block.suppressions = Suppressions::scan(&source, &ast);
+        block.source = source;
+        block.audit = self.audit.as_ref().map(|_| RefCell::default());
+        block.review = self.review.take();
+        block.visit_file_mut(&mut ast);
+        self.review = block.review.take();
+        if let (Some(audit), Some(block_audit)) = (&self.audit, block.audit.take()) {
+            let function = self.fn_name_stack.last();
+            audit.borrow_mut().extend(block_audit.into_inner().into_iter().map(|mut entry| {
+                entry.location = fence.clone();
+                entry.function = function.cloned();
+                entry
+            }));
+        }
+        if !block.changed {
+            return None;
+        }
+
+        self.changed = true;
+        for (id, count) in block.counters {
+            *self.counters.entry(id).or_insert(0) += count;
+        }
+        // 블록 안에서도 변환된 식만 바꾸고 블록의 주석/서식은 유지 (부분 치환할 수 없으면 블록 전체를 재출력)
+        let spliced = splice_changes(&block.source, &block.changes, &ast, "\n");
+        let function = self.fn_name_stack.last().map(String::as_str);
+        self.changes.extend(block.changes.into_iter().map(|change| {
+            let mut change = change.in_function(function);
+            // 블록 기준 편집은 파일에 적용할 수 없으므로 지움 (doc-test는 transform_doc_tests가 문서 주석 범위의 편집을 붙임)
+            change.location = fence.clone();
+            change.edit = None;
+            change
+        }));
+        self.skipped.borrow_mut().extend(block.skipped.into_inner().into_iter().map(|mut skip| {
+            skip.location = fence.clone();
+            skip.function = function.map(str::to_string);
+            skip
+        }));
+
+        let reprinted = spliced.is_none();
+        let modernized = spliced.unwrap_or_else(|| prettyplease::unparse(&ast));
+        let mut new_lines: Vec<&str> = modernized.lines().collect();
+        if wrapped {
+            // `fn main() {`와 `}`를 떼고 재출력했다면 본문 들여쓰기를 되돌림
+            new_lines = new_lines[1..new_lines.len().saturating_sub(1)]
+                .iter()
+                .map(|line| if reprinted { line.strip_prefix("    ").unwrap_or(line) } else { line })
+                .collect();
+        }
+        Some(
+            new_lines
+                .into_iter()
+                .map(|line| if line.is_empty() { String::new() } else { format!("{}{}", prefix, line) })
+                .collect(),
+        )
+    }
+
+    /// Markdown 문서의 ```rust 코드 블록을 doc-test와 같은 방식으로 변환합니다.
+    /// 변경이 있으면 코드 블록만 바꾼 문서 전체를 반환합니다. (본문, 펜스 info 문자열, 다른 언어 블록은 그대로)
+    fn transform_markdown(&mut self, source: &str) -> Option<String> {
+        let lines: Vec<&str> = source.split_inclusive('\n').collect();
+        let mut output = String::with_capacity(source.len());
+        let mut changed = false;
+        let mut offset = 0;
+        let mut index = 0;
+
+        while index < lines.len() {
+            let open = lines[index];
+            output.push_str(open);
+            let open_offset = offset;
+            offset += open.len();
+            index += 1;
+            let Some((fence, info)) = markdown_fence(open) else { continue };
+
+            // 닫는 펜스가 없으면 문서 끝까지 블록이므로 그대로 둠
+            let Some(close) = lines[index..].iter().position(|line| is_closing_fence(line, &fence)).map(|found| index + found)
+            else {
+                continue;
+            };
+            let body = &lines[index..close];
+            let body_len: usize = body.iter().map(|line| line.len()).sum();
+            let newline = if open.ends_with("\r\n") { "\r\n" } else { "\n" };
+            let location = Location {
+                line: index,
+                column: open.len() - open.trim_start().len() + 1,
+                end_line: close + 1,
+                end_column: lines[close].trim_end().len() + 1,
+                byte_start: open_offset,
+                byte_end: offset + body_len + lines[close].trim_end().len(),
+            };
+
+            let replacement = if is_markdown_rust_fence(&info) {
+                let code_lines: Vec<String> = body.iter().map(|line| line.trim_end_matches(['\r', '\n']).to_string()).collect();
+                self.transform_doc_block(&code_lines, location)
+            } else {
+                None
+            };
+            match replacement {
+                Some(new_lines) => {
+                    changed = true;
+                    for line in new_lines {
+                        output.push_str(&line);
+                        output.push_str(newline);
+                    }
+                }
+                None => body.iter().for_each(|line| output.push_str(line)),
+            }
+            output.push_str(lines[close]);
+            offset += body_len + lines[close].len();
+            index = close + 1;
+        }
+        changed.then_some(output)
+    }
+}
+
+/// --interactive: 제안된 변환을 diff 형식으로 보여 주고 표준 입력에서 결정을 읽습니다.
+/// (프롬프트는 stdout의 보고서/코드 출력과 섞이지 않도록 stderr에 출력)
+fn prompt_review(path: &Path, change: &AppliedChange, editable: bool, review: &mut Review) -> ReviewDecision {
+    eprintln!("\n@@ {}:{}:{} {} ({}) @@", path.display(), change.location.line, change.location.column, change.rule_id, change.risk.as_str());
+    eprintln!("-{}", change.original);
+    eprintln!("+{}", change.replacement.as_deref().unwrap_or("(문장 단위 변환)"));
+    if let Some(note) = &change.review_note {
+        eprintln!("  ⚠️ {}", note);
+    }
+    let choices = if editable { "[y]적용 / [n]거절 / [e]직접 수정 / [a]남은 변환 모두 적용 / [q]남은 변환 모두 거절" } else { "[y]적용 / [n]거절 / [a]남은 변환 모두 적용 / [q]남은 변환 모두 거절" };
+
+    loop {
+        eprint!("{} > ", choices);
+        let mut answer = String::new();
+        // 입력이 끝나면(EOF) 남은 변환을 적용하지 않음
+        if std::io::stdin().read_line(&mut answer).unwrap_or(0) == 0 {
+            review.remaining = Some(false);
+            return ReviewDecision::Reject;
+        }
+        match answer.trim() {
+            "y" | "Y" => return ReviewDecision::Accept,
+            "n" | "N" => return ReviewDecision::Reject,
+            "a" | "A" => {
+                review.remaining = Some(true);
+                return ReviewDecision::Accept;
+            }
+            "q" | "Q" => {
+                review.remaining = Some(false);
+                return ReviewDecision::Reject;
+            }
+            "e" | "E" if editable => {
+                eprint!("대체할 식 > ");
+                let mut code = String::new();
+                let _ = std::io::stdin().read_line(&mut code);
+                match syn::parse_str::<Expr>(code.trim()) {
+                    Ok(expr) => return ReviewDecision::Edit(Box::new(expr)),
+                    Err(err) => eprintln!("❌ 식으로 파싱할 수 없습니다: {}", err),
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 보고서용으로 식을 한 줄 소스 코드로 출력합니다. (prettyplease는 아이템 단위로만 출력하므로 상수 초기값으로 감싸서 출력)
+fn expr_source(expr: &Expr) -> String {
+    expr_code(expr).split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 타입을 prettyplease 서식으로 한 줄 소스 코드로 출력합니다. (타입 별칭으로 감싸서 출력)
+fn type_code(ty: &Type) -> String {
+    let file: syn::File = parse_quote!(type T = #ty;);
+    let text = prettyplease::unparse(&file);
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    text.strip_prefix("type T = ").and_then(|text| text.strip_suffix(';')).unwrap_or(&text).to_string()
+}
+
+/// 식을 prettyplease 서식 그대로 소스 코드로 출력합니다. (여러 줄이면 둘째 줄부터 식 기준 들여쓰기)
+fn expr_code(expr: &Expr) -> String {
+    let file: syn::File = parse_quote!(const _: () = #expr;);
+    let text = prettyplease::unparse(&file);
+    let text = text.trim();
+    text.strip_prefix("const _: () = ").and_then(|text| text.strip_suffix(';')).unwrap_or(text).to_string()
+}
+
+/// `lazy_static!` 본문의 선언 하나 (`#[attr] pub static ref NAME: T = EXPR;`)
+struct LazyStatic {
+    /// 선언의 첫 토큰 (속성 또는 가시성 포함)
+    start: Span,
+    static_token: Span,
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    name: Ident,
+    ty: Type,
+    init: Expr,
+}
+
+/// `lazy_static!` 본문을 선언 목록으로 파싱합니다.
+fn parse_lazy_statics(input: ParseStream) -> syn::Result<Vec<LazyStatic>> {
+    let mut statics = Vec::new();
+    while !input.is_empty() {
+        let start = input.span();
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        let static_token = input.parse::<Token![static]>()?.span;
+        input.parse::<Token![ref]>()?;
+        let name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let init = input.parse()?;
+        input.parse::<Token![;]>()?;
+        statics.push(LazyStatic { start, static_token, attrs, vis, name, ty, init });
+    }
+    Ok(statics)
+}
+
+/// 템플릿으로 만든 `static`(또는 옮긴 `use`)에 원래 선언의 속성과 가시성을 붙입니다.
+fn with_attrs_and_vis(item: Item, attrs: Vec<Attribute>, vis: Visibility) -> Item {
+    match item {
+        Item::Static(mut item_static) => {
+            item_static.attrs = attrs;
+            item_static.vis = vis;
+            Item::Static(item_static)
+        }
+        Item::Use(mut item_use) => {
+            item_use.attrs = attrs;
+            item_use.vis = vis;
+            Item::Use(item_use)
+        }
+        item => item,
+    }
+}
+
+/// 파일의 `use` 선언(인라인 모듈 포함)이 들여오는 이름 → 전체 경로. 같은 이름이 다른 경로를 가리키면 None
+fn use_paths(items: &[Item], imports: &mut HashMap<String, Option<Vec<Ident>>>) {
+    fn walk(tree: &UseTree, prefix: &mut Vec<Ident>, imports: &mut HashMap<String, Option<Vec<Ident>>>) {
+        let (name, full) = match tree {
+            UseTree::Path(path) => {
+                prefix.push(path.ident.clone());
+                walk(&path.tree, prefix, imports);
+                prefix.pop();
+                return;
+            }
+            UseTree::Group(group) => {
+                group.items.iter().for_each(|tree| walk(tree, prefix, imports));
+                return;
+            }
+            UseTree::Glob(_) => return,
+            // `use a::b::{self}`는 `b`를 들여옴
+            UseTree::Name(name) if name.ident == "self" => match prefix.last() {
+                Some(last) => (last.to_string(), prefix.clone()),
+                None => return,
+            },
+            UseTree::Name(name) => (name.ident.to_string(), prefix.iter().chain([&name.ident]).cloned().collect()),
+            UseTree::Rename(rename) => (rename.rename.to_string(), prefix.iter().chain([&rename.ident]).cloned().collect()),
+        };
+        let entry = imports.entry(name).or_insert_with(|| Some(full.clone()));
+        if entry.as_ref().is_some_and(|existing| *existing != full) {
+            *entry = None;
+        }
+    }
+    for item in items {
+        match item {
+            Item::Use(item_use) => walk(&item_use.tree, &mut Vec::new(), imports),
+            Item::Mod(ItemMod { content: Some((_, content)), .. }) => use_paths(content, imports),
+            _ => {}
+        }
+    }
+}
+
+/// 파일의 아이템(인라인 모듈 포함)에서 선언한 트레이트 이름을 모읍니다.
+fn declared_traits(items: &[Item], traits: &mut BTreeSet<String>) {
+    for item in items {
+        match item {
+            Item::Trait(item_trait) => {
+                traits.insert(item_trait.ident.to_string());
+            }
+            Item::Mod(ItemMod { content: Some((_, content)), .. }) => declared_traits(content, traits),
+            _ => {}
+        }
+    }
+}
+
+/// `use` 트리가 들여오는 이름마다 (전체 경로, 들여오는 이름)을 모읍니다. (glob과 `self`는 제외)
+fn use_leaves(tree: &UseTree, prefix: &mut Vec<Ident>, leaves: &mut Vec<(Vec<Ident>, Ident)>) {
+    match tree {
+        UseTree::Path(path) => {
+            prefix.push(path.ident.clone());
+            use_leaves(&path.tree, prefix, leaves);
+            prefix.pop();
+        }
+        UseTree::Group(group) => group.items.iter().for_each(|tree| use_leaves(tree, prefix, leaves)),
+        UseTree::Name(name) if name.ident != "self" => {
+            leaves.push((prefix.iter().chain([&name.ident]).cloned().collect(), name.ident.clone()));
+        }
+        UseTree::Rename(rename) if rename.ident != "self" => {
+            leaves.push((prefix.iter().chain([&rename.ident]).cloned().collect(), rename.rename.clone()));
+        }
+        _ => {}
+    }
+}
+
+/// `use` 트리에서 전체 경로가 `removed`에 있는 이름을 지웁니다. 트리가 비면 true를 반환합니다.
+fn remove_use_leaves(tree: &mut UseTree, prefix: &mut Vec<Ident>, removed: &[Vec<Ident>]) -> bool {
+    match tree {
+        UseTree::Path(path) => {
+            prefix.push(path.ident.clone());
+            let empty = remove_use_leaves(&mut path.tree, prefix, removed);
+            prefix.pop();
+            empty
+        }
+        UseTree::Group(group) => {
+            let items = std::mem::take(&mut group.items);
+            group.items = items.into_iter().filter_map(|mut tree| (!remove_use_leaves(&mut tree, prefix, removed)).then_some(tree)).collect();
+            // 하나만 남은 그룹은 prettyplease가 중괄호 없이 출력하므로 AST도 같은 모양으로 맞춤
+            if group.items.len() == 1 {
+                if let Some(only) = group.items.pop() {
+                    *tree = only.into_value();
+                }
+                return false;
+            }
+            group.items.is_empty()
+        }
+        UseTree::Name(syn::UseName { ident }) | UseTree::Rename(syn::UseRename { ident, .. }) => {
+            let full: Vec<Ident> = prefix.iter().chain([&*ident]).cloned().collect();
+            removed.contains(&full)
+        }
+        UseTree::Glob(_) => false,
+    }
+}
+
+/// 아이템 목록의 위치 경로(`path`)를 따라 인라인 모듈의 아이템 목록을 찾습니다.
+fn module_items<'a>(items: &'a mut [Item], path: &[usize]) -> Option<&'a mut Vec<Item>> {
+    let (first, rest) = path.split_first()?;
+    let Item::Mod(ItemMod { content: Some((_, content)), .. }) = items.get_mut(*first)? else { return None };
+    if rest.is_empty() { Some(content) } else { module_items(content, rest) }
+}
+
+/// 아이템을 prettyplease 서식 그대로 소스 코드로 출력합니다.
+fn item_code(item: &Item) -> String {
+    let file = syn::File { shebang: None, attrs: Vec::new(), items: vec![item.clone()] };
+    prettyplease::unparse(&file).trim().to_string()
+}
+
+/// 함수(또는 메서드)를 prettyplease 서식 그대로 소스 코드로 출력합니다. (impl 블록으로 감싸서 출력한 뒤 들여쓰기를 뺌)
+fn fn_code(attrs: &[Attribute], vis: &Visibility, sig: &Signature, block: &Block) -> String {
+    let file: syn::File = parse_quote!(impl __Modernizer { #(#attrs)* #vis #sig #block });
+    let text = prettyplease::unparse(&file);
+    let lines: Vec<&str> = text.trim().lines().collect();
+    let body = lines.get(1..lines.len().saturating_sub(1)).unwrap_or_default();
+    body.iter().map(|line| line.strip_prefix("    ").unwrap_or(line)).collect::<Vec<_>>().join("\n")
+}
+
+/// 보고서용으로 속성을 소스 코드로 출력합니다.
+fn attribute_source(attr: &Attribute) -> String {
+    let file: syn::File = parse_quote!(#attr struct __Modernizer;);
+    prettyplease::unparse(&file).lines().next().unwrap_or_default().to_string()
+}
+
+/// Markdown 입력인지 확인합니다. (확장자 `.md`/`.markdown`)
+pub fn is_markdown(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "md" || ext == "markdown")
+}
+
+/// Markdown 코드 펜스(``` 또는 ~~~, 3개 이상)를 여는 줄이면 (펜스 문자열, info 문자열)을 반환합니다.
+fn markdown_fence(line: &str) -> Option<(String, String)> {
+    let trimmed = line.trim_start();
+    let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let fence: String = trimmed.chars().take_while(|c| *c == marker).collect();
+    if fence.len() < 3 {
+        return None;
+    }
+    let info = trimmed[fence.len()..].trim();
+    // 백틱 펜스의 info 문자열에는 백틱이 올 수 없음 (인라인 코드와 구분)
+    if marker == '`' && info.contains('`') {
+        return None;
+    }
+    Some((fence, info.to_string()))
+}
+
+/// 여는 펜스와 같은 문자로 같은 길이 이상이고 info 문자열이 없는 닫는 펜스인지 확인합니다.
+fn is_closing_fence(line: &str, fence: &str) -> bool {
+    let trimmed = line.trim();
+    let marker = fence.chars().next().unwrap_or('`');
+    trimmed.len() >= fence.len() && trimmed.chars().all(|c| c == marker)
+}
+
+/// Markdown에서 Rust 코드로 취급할 펜스인지 확인합니다. (rustdoc과 달리 info가 없는 블록은 제외, 첫 토큰이 `rust`여야 함)
+fn is_markdown_rust_fence(info: &str) -> bool {
+    info.split([',', ' ', '\t']).next() == Some("rust") && is_rust_fence(info)
+}
+
+/// `#[doc = "..."]`(`///`, `//!`) 속성이면 한 줄 내용을 반환합니다.
+fn doc_line(attr: &Attribute) -> Option<String> {
+    let syn::Meta::NameValue(meta) = &attr.meta else { return None };
+    if !meta.path.is_ident("doc") {
+        return None;
+    }
+    match &meta.value {
+        Expr::Lit(ExprLit { lit: Lit::Str(lit_str), .. }) => Some(lit_str.value()),
+        _ => None,
+    }
+}
+
+/// 이 도구가 남긴 검토 안내 문서 주석(`review_annotation`, verbose/compact 형식)인지 확인합니다.
+fn is_review_annotation(attr: &Attribute) -> bool {
+    doc_line(attr).is_some_and(|line| line.starts_with(" MODERNIZER REVIEW (") || line.starts_with(" REVIEW("))
+}
+
+/// 코드 펜스(```) 줄이면 info 문자열(예: "rust,no_run")을 반환합니다.
+fn fence_info(line: &str) -> Option<String> {
+    line.trim_start().strip_prefix("```").map(|info| info.trim().to_string())
+}
+
+/// rustdoc이 Rust 코드로 취급하는 펜스인지 확인합니다. (compile_fail은 일부러 깨진 코드이므로 제외)
+fn is_rust_fence(info: &str) -> bool {
+    const RUSTDOC_ATTRIBUTES: &[&str] = &[
+        "rust", "ignore", "no_run", "should_panic", "test_harness", "edition2015", "edition2018", "edition2021",
+        "edition2024",
+    ];
+    info.split([',', ' ', '\t'])
+        .filter(|token| !token.is_empty())
+        .all(|token| RUSTDOC_ATTRIBUTES.contains(&token))
+}
+
+/// 아이템의 속성 목록 (doc-test 변환용)
+fn item_attrs(item: &mut Item) -> Option<&mut Vec<Attribute>> {
+    match item {
+        Item::Const(item) => Some(&mut item.attrs),
+        Item::Enum(item) => Some(&mut item.attrs),
+        Item::ExternCrate(item) => Some(&mut item.attrs),
+        Item::Fn(item) => Some(&mut item.attrs),
+        Item::ForeignMod(item) => Some(&mut item.attrs),
+        Item::Impl(item) => Some(&mut item.attrs),
+        Item::Macro(item) => Some(&mut item.attrs),
+        Item::Mod(item) => Some(&mut item.attrs),
+        Item::Static(item) => Some(&mut item.attrs),
+        Item::Struct(item) => Some(&mut item.attrs),
+        Item::Trait(item) => Some(&mut item.attrs),
+        Item::TraitAlias(item) => Some(&mut item.attrs),
+        Item::Type(item) => Some(&mut item.attrs),
+        Item::Union(item) => Some(&mut item.attrs),
+        Item::Use(item) => Some(&mut item.attrs),
+        _ => None,
+    }
+}
+
+impl VisitMut for Modernizer {
+    // --doc-tests: 문서 주석을 가질 수 있는 노드마다 속성 목록의 코드 블록을 변환합니다.
+    fn visit_file_mut(&mut self, i: &mut syn::File) {
+        self.transform_doc_tests(&mut i.attrs);
+        self.imports.clear();
+        use_paths(&i.items, &mut self.imports);
+        self.traits.clear();
+        declared_traits(&i.items, &mut self.traits);
+        self.trait_objects = async_trait::trait_objects(i, &self.traits);
+        visit_mut::visit_file_mut(self, i);
+        // 안쪽 식의 변환이 대체 아이템에 반영되도록 아이템은 자식 노드를 방문한 뒤에 변환
+        self.transform_items(&mut i.items);
+    }
+
+    fn visit_item_mod_mut(&mut self, i: &mut ItemMod) {
+        visit_mut::visit_item_mod_mut(self, i);
+        if let Some((_, items)) = &mut i.content {
+            self.transform_items(items);
+        }
+    }
+
+    fn visit_item_mut(&mut self, i: &mut Item) {
+        if let Some(attrs) = item_attrs(i) {
+            self.transform_doc_tests(attrs);
+        }
+        visit_mut::visit_item_mut(self, i);
+    }
+
+    fn visit_impl_item_mut(&mut self, i: &mut ImplItem) {
+        match i {
+            ImplItem::Const(item) => self.transform_doc_tests(&mut item.attrs),
+            ImplItem::Fn(item) => self.transform_doc_tests(&mut item.attrs),
+            ImplItem::Type(item) => self.transform_doc_tests(&mut item.attrs),
+            ImplItem::Macro(item) => self.transform_doc_tests(&mut item.attrs),
+            _ => {}
+        }
+        visit_mut::visit_impl_item_mut(self, i);
+    }
+
+    fn visit_trait_item_mut(&mut self, i: &mut TraitItem) {
+        match i {
+            TraitItem::Const(item) => self.transform_doc_tests(&mut item.attrs),
+            TraitItem::Fn(item) => self.transform_doc_tests(&mut item.attrs),
+            TraitItem::Type(item) => self.transform_doc_tests(&mut item.attrs),
+            TraitItem::Macro(item) => self.transform_doc_tests(&mut item.attrs),
+            _ => {}
+        }
+        visit_mut::visit_trait_item_mut(self, i);
+    }
+
+    fn visit_field_mut(&mut self, i: &mut Field) {
+        self.transform_doc_tests(&mut i.attrs);
+        visit_mut::visit_field_mut(self, i);
+    }
+
+    fn visit_variant_mut(&mut self, i: &mut Variant) {
+        self.transform_doc_tests(&mut i.attrs);
+        visit_mut::visit_variant_mut(self, i);
+    }
+
+    // 함수 본문을 순회하는 동안 반환 타입을 스택에 기록합니다. (require_fn_returns 판정용)
+    fn visit_item_fn_mut(&mut self, i: &mut ItemFn) {
+        self.fn_return_stack.push(return_type_name(&i.sig.output));
+        self.test_fn_stack.push(is_test_fn(&i.attrs));
+        let name = match self.fn_name_stack.last() {
+            Some(outer) => format!("{}::{}", outer, i.sig.ident),
+            None => i.sig.ident.to_string(),
+        };
+        self.fn_name_stack.push(name);
+        let span = i.span();
+        visit_mut::visit_item_fn_mut(self, i);
+        self.transform_tail_return(&mut i.block);
+        self.transform_async_fn(&mut i.attrs, &i.vis, &mut i.sig, &mut i.block, span, false);
+        self.fn_name_stack.pop();
+        self.test_fn_stack.pop();
+        self.fn_return_stack.pop();
+    }
+
+    fn visit_impl_item_fn_mut(&mut self, i: &mut ImplItemFn) {
+        self.fn_return_stack.push(return_type_name(&i.sig.output));
+        self.test_fn_stack.push(is_test_fn(&i.attrs));
+        self.fn_name_stack.push(self.method_name(&i.sig.ident));
+        let span = i.span();
+        visit_mut::visit_impl_item_fn_mut(self, i);
+        self.transform_tail_return(&mut i.block);
+        self.transform_async_fn(&mut i.attrs, &i.vis, &mut i.sig, &mut i.block, span, self.in_trait_impl);
+        self.fn_name_stack.pop();
+        self.test_fn_stack.pop();
+        self.fn_return_stack.pop();
+    }
+
+    fn visit_trait_item_fn_mut(&mut self, i: &mut TraitItemFn) {
+        self.fn_return_stack.push(return_type_name(&i.sig.output));
+        self.test_fn_stack.push(false);
+        self.fn_name_stack.push(self.method_name(&i.sig.ident));
+        visit_mut::visit_trait_item_fn_mut(self, i);
+        if let Some(block) = &mut i.default {
+            self.transform_tail_return(block);
+        }
+        self.fn_name_stack.pop();
+        self.test_fn_stack.pop();
+        self.fn_return_stack.pop();
+    }
+
+    fn visit_item_impl_mut(&mut self, i: &mut ItemImpl) {
+        let self_ty = i.self_ty.to_token_stream().to_string().replace(' ', "");
+        self.impl_name_stack.push(self_ty);
+        let in_trait_impl = std::mem::replace(&mut self.in_trait_impl, i.trait_.is_some());
+        visit_mut::visit_item_impl_mut(self, i);
+        self.in_trait_impl = in_trait_impl;
+        self.impl_name_stack.pop();
+    }
+
+    fn visit_item_trait_mut(&mut self, i: &mut ItemTrait) {
+        self.impl_name_stack.push(i.ident.to_string());
+        visit_mut::visit_item_trait_mut(self, i);
+        self.impl_name_stack.pop();
+    }
+
+    // `println!(...);`처럼 문장 위치의 매크로는 Expr::Macro가 아니라 Stmt::Macro로 파싱되므로 식과 같은 규칙으로 변환합니다.
+    fn visit_stmt_mut(&mut self, i: &mut Stmt) {
+        // 이전 실행이 검토 안내를 붙여 만든 문장은 이미 변환된 코드이므로 다시 건드리지 않음
+        if let Stmt::Local(local) = i {
+            if local.attrs.iter().any(is_review_annotation) {
+                return;
+            }
+        }
+        let Stmt::Macro(stmt_macro) = i else {
+            visit_mut::visit_stmt_mut(self, i);
+            return;
+        };
+        let span = stmt_macro.mac.span();
+        visit_mut::visit_stmt_macro_mut(self, stmt_macro);
+        let expr_macro = ExprMacro { attrs: stmt_macro.attrs.clone(), mac: stmt_macro.mac.clone() };
+        let recorded = self.changes.len();
+        let was_changed = self.changed;
+        if let Some(new_expr) = self.transform_expr_macro(&expr_macro, span) {
+            if self.guard_restacking(recorded, was_changed, &new_expr) {
+                return;
+            }
+            self.record_replacement(recorded, &new_expr);
+            let new_expr = match self.review_change(recorded, was_changed, true) {
+                ReviewDecision::Accept => new_expr,
+                ReviewDecision::Edit(edited) => *edited,
+                ReviewDecision::Reject => return,
+            };
+            *i = Stmt::Expr(new_expr, stmt_macro.semi_token);
+        }
+    }
+
+    // 문장 단위 패턴은 하위 식이 변환되기 전에 먼저 처리합니다.
+    fn visit_block_mut(&mut self, i: &mut Block) {
+        if self.scoped_maybeuninit {
+            self.transform_uninit_stmts(&mut i.stmts);
+        }
+        visit_mut::visit_block_mut(self, i);
+    }
+
+    // 타입, 식, 매크로, 트레이트 경로 (제네릭 인자 안의 경로를 먼저 변환)
+    fn visit_path_mut(&mut self, i: &mut syn::Path) {
+        visit_mut::visit_path_mut(self, i);
+        self.transform_path(i);
+    }
+
+    // `match` 갈래, `let`, 함수 인자 등의 패턴 (안쪽 패턴을 먼저 변환)
+    fn visit_pat_mut(&mut self, i: &mut Pat) {
+        visit_mut::visit_pat_mut(self, i);
+        if let Pat::Range(range) = i {
+            self.transform_range_pattern(range);
+        }
+    }
+
+    // 타입 위치의 트레이트 객체 (`Box<Fn(&Error)>`처럼 안쪽 타입을 먼저 변환)
+    fn visit_type_mut(&mut self, i: &mut Type) {
+        visit_mut::visit_type_mut(self, i);
+        self.transform_trait_object(i);
+    }
+
+    fn visit_attribute_mut(&mut self, i: &mut Attribute) {
+        self.transform_attribute(i);
+        visit_mut::visit_attribute_mut(self, i);
+    }
+
+    // 클로저 본문의 `?`는 바깥 함수가 아니라 클로저에서 반환되므로 클로저의 반환 타입을 기준으로 판정합니다.
+    fn visit_expr_closure_mut(&mut self, i: &mut ExprClosure) {
+        self.fn_return_stack.push(closure_return_type_name(i));
+        visit_mut::visit_expr_closure_mut(self, i);
+        self.fn_return_stack.pop();
+    }
+
+    fn visit_expr_mut(&mut self, i: &mut Expr) {
+        // 0. 하위 노드가 변환되기 전의 원본 위치를 기록 (보고서 위치가 원본 소스를 가리키도록)
+        let span = i.span();
+
+        // 1. 깊이 우선 순회
+        visit_mut::visit_expr_mut(self, i); 
+        
+        let recorded = self.changes.len();
+        let was_changed = self.changed;
+        let new_expr = match i {
+            // (1) 메서드 호출 변환 (데이터 기반)
+            Expr::MethodCall(method_call) => self.transform_method_call(method_call, span),
+            
+            // (2) 함수 호출 변환 (데이터 기반, `Ok(x?)`처럼 `?` 식을 감싼 호출은 ExprTry 규칙도 평가)
+            Expr::Call(expr_call) => self.transform_expr_call(expr_call, span)
+                .or_else(|| self.transform_expr_try(i, span)),
+
+            // (3) 매크로 호출 변환 (데이터 기반)
+            Expr::Macro(expr_macro) => self.transform_expr_macro(expr_macro, span),
+
+            // (4) `?` 식 변환 (데이터 기반)
+            Expr::Try(_) => self.transform_expr_try(i, span),
+
+            // (5) 이항 연산식 변환 (데이터 기반, `x.len() == 0` 등)
+            Expr::Binary(expr_binary) => self.transform_expr_binary(expr_binary, span),
+
+            // (6) 오류 전파 match 변환 (데이터 기반)
+            Expr::Match(expr_match) => self.transform_expr_match(expr_match, span),
+
+            // (7) 폐기된 상수 변환 (데이터 기반, `ATOMIC_USIZE_INIT` 등)
+            Expr::Path(expr_path) => self.transform_expr_path(expr_path, span),
+
+            // (8) 기타 리터럴 패턴 확인 (유지)
+            Expr::Lit(expr_lit) => {
+                if let Lit::Str(lit_str) = &expr_lit.lit {
+                    if lit_str.value().contains("mem::uninitialized") {
+                        log!("[MOD] ℹ️ Found deprecated string pattern in literal.");
+                        self.changed = true;
+                    }
+                }
+                None
+            }
+            
+            _ => None
+        };
+        // (9) 식 패턴 규칙 (ast_type: Pattern, 모든 식에 대해 평가)
+        let new_expr = new_expr.or_else(|| self.transform_expr_pattern(i, span));
+
+        if let Some(expr) = new_expr {
+            if self.guard_restacking(recorded, was_changed, &expr) {
+                return;
+            }
+            self.record_replacement(recorded, &expr);
+            match self.review_change(recorded, was_changed, true) {
+                ReviewDecision::Accept => *i = expr,
+                ReviewDecision::Edit(edited) => *i = *edited,
+                ReviewDecision::Reject => {}
+            }
+        }
+    }
+}
+
+/// ----------------------------------------------------
+/// 3. 규칙 파일 로드
+/// ----------------------------------------------------
+pub fn load_rules(rules_files: &[PathBuf]) -> Result<Vec<ModernizerRule>> {
+    load_rules_with_format(rules_files, None)
+}
+
+/// 규칙 파일을 지정한 형식으로 읽습니다. (None이면 확장자로 판별하고, 알 수 없는 확장자는 JSON)
+pub fn load_rules_with_format(rules_files: &[PathBuf], format: Option<RulesFormat>) -> Result<Vec<ModernizerRule>> {
+    let mut rules: Vec<ModernizerRule> = Vec::new();
+    // 규칙 id → 처음 정의한 파일 (중복 검사용)
+    let mut defined_in: BTreeMap<String, PathBuf> = BTreeMap::new();
+
+    for (file_path, rule) in load_rules_by_file(rules_files, format)? {
+        if let Some(first) = defined_in.get(&rule.id) {
+            return Err(ValidationError(format!(
+                "Duplicate rule id `{}`: defined in both {} and {}",
+                rule.id, first.display(), file_path.display()
+            ))
+            .into());
+        }
+        defined_in.insert(rule.id.clone(), file_path);
+        rules.push(rule);
+    }
+
+    Ok(rules)
+}
+
+/// 규칙을 정의한 파일과 함께 읽습니다. (ID 중복은 검사하지 않음, validate-rules용)
+pub fn load_rules_by_file(rules_files: &[PathBuf], format: Option<RulesFormat>) -> Result<Vec<(PathBuf, ModernizerRule)>> {
+    let mut rules = Vec::new();
+    for file_path in expand_rule_dirs(rules_files)? {
+        for rule in load_rules_file(&file_path, format)? {
+            rules.push((file_path.clone(), rule));
+        }
+    }
+    Ok(rules)
+}
+
+/// 디렉터리 인자를 그 안의 규칙 파일(`*.json`, `*.toml`, `*.yaml`, `*.yml`) 목록(이름순)으로 펼칩니다.
+fn expand_rule_dirs(rules_files: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in rules_files {
+        if !path.is_dir() {
+            files.push(path.clone());
+            continue;
+        }
+        let entries = fs::read_dir(path)
+            .with_context(|| format!("Failed to read rule directory: {}", path.display()))?;
+        let mut dir_files = Vec::new();
+        for entry in entries {
+            let entry_path = entry
+                .with_context(|| format!("Failed to read rule directory: {}", path.display()))?
+                .path();
+            if entry_path.is_file() && RulesFormat::from_path(&entry_path).is_some() {
+                dir_files.push(entry_path);
+            }
+        }
+        if dir_files.is_empty() {
+            bail!("Rule directory contains no .json, .toml or .yaml files: {}", path.display());
+        }
+        dir_files.sort();
+        files.extend(dir_files);
+    }
+    Ok(files)
+}
+
+/// 규칙 파일 하나를 읽습니다.
+fn load_rules_file(file_path: &Path, format: Option<RulesFormat>) -> Result<Vec<ModernizerRule>> {
+    log!("📖 규칙 파일 로드 중: {}", file_path.display());
+    
+    let contents = fs::read_to_string(file_path)
+        .with_context(|| format!("Failed to read rule file: {}", file_path.display()))?;
+    
+    let format = format.or_else(|| RulesFormat::from_path(file_path)).unwrap_or(RulesFormat::Json);
+    let mut rules = format
+        .parse(&contents)
+        .with_context(|| format!("Failed to parse {}. Check {} format.", file_path.display(), format.as_str()))?;
+
+    // doc_url의 환경 변수 전개 (CI에서는 사내 위키, 로컬에서는 공식 문서를 가리키도록)
+    for rule in &mut rules {
+        rule.doc_url = expand_env(&rule.doc_url)
+            .with_context(|| format!("Failed to expand doc_url of rule `{}`", rule.id))?;
+        // 패턴은 변환 중이 아니라 로드 시 검사
+        match (rule.ast_type.as_str(), rule.pattern.as_deref()) {
+            ("Pattern", Some(pattern)) => {
+                Pattern::compile(pattern).with_context(|| format!("Invalid pattern of rule `{}`", rule.id))?;
+            }
+            ("Pattern", None) => bail!("Rule `{}` has ast_type Pattern but no pattern", rule.id),
+            _ => {}
+        }
+    }
+
+    Ok(rules)
+}
+
+/// `$VAR`, `${VAR}`를 환경 변수 값으로 전개합니다. (`$$`는 `$` 그대로, 정의되지 않은 변수는 오류)
+fn expand_env(value: &str) -> Result<String> {
+    let mut output = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            output.push(c);
+            continue;
+        }
+        let name = match chars.peek() {
+            Some('$') => {
+                chars.next();
+                output.push('$');
+                continue;
+            }
+            Some('{') => {
+                chars.next();
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') if !name.is_empty() => break name,
+                        Some(next) if next.is_ascii_alphanumeric() || next == '_' => name.push(next),
+                        _ => bail!("Malformed `${{...}}` in: {}", value),
+                    }
+                }
+            }
+            Some(&next) if next.is_ascii_alphabetic() || next == '_' => {
+                let mut name = String::new();
+                while let Some(&next) = chars.peek() {
+                    if !(next.is_ascii_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    name.push(next);
+                    chars.next();
+                }
+                name
+            }
+            // 변수 이름이 뒤따르지 않는 `$`는 그대로 둠
+            _ => {
+                output.push('$');
+                continue;
+            }
+        };
+        let expanded = std::env::var(&name)
+            .with_context(|| format!("Environment variable `{}` is not set (in: {})", name, value))?;
+        output.push_str(&expanded);
+    }
+    Ok(output)
+}
+
+/// 경로 앞의 `~`를 홈 디렉터리로 바꾸고 환경 변수를 전개합니다.
+pub fn expand_path(path: &Path) -> Result<PathBuf> {
+    let path = path.to_str().context("Path is not valid UTF-8")?;
+    let expanded = expand_env(path)?;
+    let expanded = match expanded.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+            let home = std::env::var("HOME").context("Cannot expand `~`: HOME is not set")?;
+            format!("{}{}", home, rest)
+        }
+        _ => expanded,
+    };
+    Ok(PathBuf::from(expanded))
+}
+
+/// 내장 기본 규칙을 파싱합니다. 표준 라이브러리 이름 변경 표(`std_renames.json`)의 규칙이 뒤에 붙습니다.
+pub fn default_rules() -> Result<Vec<ModernizerRule>> {
+    let mut rules: Vec<ModernizerRule> = serde_json::from_str(DEFAULT_RULES_JSON)
+        .with_context(|| "Failed to parse built-in default rules.")?;
+    rules.extend(std_renames::rules()?);
+    Ok(rules)
+}
+
+/// 기본 규칙 위에 규칙을 덧붙입니다. 같은 id의 기본 규칙은 그 자리에서 교체하고, 새 규칙은 뒤에 추가합니다.
+pub fn layer_rules(mut base: Vec<ModernizerRule>, overrides: Vec<ModernizerRule>) -> Vec<ModernizerRule> {
+    for rule in overrides {
+        match base.iter_mut().find(|existing| existing.id == rule.id) {
+            Some(existing) => *existing = rule,
+            None => base.push(rule),
+        }
+    }
+    base
+}
+
+
+/// ----------------------------------------------------
+/// 4. 파일 변환 파이프라인 (라이브러리 API)
+/// ----------------------------------------------------
+/// 변환 옵션. CLI는 명령줄 인자로 채우고, 라이브러리 사용자는 `Default`에서 필요한 값만 바꿉니다.
+#[derive(Debug, Clone)]
+pub struct TransformOptions {
+    /// 변경이 없을 때까지 규칙 적용을 최대 N번 반복 (1 이상)
+    pub max_iterations: u32,
+    /// (실험적) `let mut x = mem::uninitialized(); init(&mut x);`를 문장 단위로 `MaybeUninit`으로 변환
+    pub scoped_maybeuninit: bool,
+    /// 문장/아이템 단위 변환이 남기는 검토 안내 주석 형식 (None: 남기지 않음, 식 단위 규칙에는 영향 없음)
+    pub annotations: Option<AnnotationStyle>,
+    /// 문서 주석의 ```rust 코드 블록(doc-test)에도 규칙을 적용
+    pub doc_tests: bool,
+    /// 이 규칙과 부분적으로 일치한 노드마다 적용/거부 이유를 로그로 출력
+    pub explain: Option<String>,
+    /// 규칙 모양과 일치한 모든 노드의 판정을 `TransformResult::audit`에 기록
+    pub audit: bool,
+    /// 변환마다 표준 입력으로 적용 여부를 묻습니다. (터미널에서만 사용)
+    pub interactive: bool,
+    /// 보고서에 감싸는 함수별 규칙 적용 건수를 추가
+    pub by_function: bool,
+    /// 변환 결과 코드를 만들지 않고 건수만 집계
+    pub only_count: bool,
+    /// 변환된 식만 바꾸는 대신 파일 전체를 prettyplease로 재출력
+    pub reformat: bool,
+    /// 변환된 코드를 rustfmt로 정리 (파일 경로 기준으로 rustfmt.toml과 edition을 찾음)
+    pub format: bool,
+    /// 적용된 규칙이 없어도 재출력한 코드를 반환
+    pub force_reprint: bool,
+    /// 결과 코드의 줄바꿈 형식
+    pub line_endings: LineEndings,
+    /// 변환 결과를 다시 파싱해 `TransformResult::reparse_error`에 기록
+    pub fail_on_unparseable_output: bool,
+}
+
+impl Default for TransformOptions {
+    fn default() -> Self {
+        TransformOptions {
+            max_iterations: 1,
+            scoped_maybeuninit: false,
+            annotations: Some(AnnotationStyle::Verbose),
+            doc_tests: false,
+            explain: None,
+            audit: false,
+            interactive: false,
+            by_function: false,
+            only_count: false,
+            reformat: false,
+            format: false,
+            force_reprint: false,
+            line_endings: LineEndings::Preserve,
+            fail_on_unparseable_output: false,
+        }
+    }
+}
+
+/// 파일(또는 문자열) 하나의 변환 결과
+#[derive(Debug)]
+pub struct TransformResult {
+    pub report: FileReport,
+    /// 규칙이 코드를 바꿨는지 (보고서에 남지 않는 변경도 포함)
+    pub changed: bool,
+    /// 원본 소스
+    pub original: String,
+    /// 변환된 코드. 변경이 없거나 only_count이면 None (force_reprint이면 변경이 없어도 재출력한 코드)
+    pub code: Option<String>,
+    /// audit 옵션을 켠 경우 규칙 판정 기록
+    pub audit: Vec<AuditEntry>,
+    /// fail_on_unparseable_output 옵션을 켠 경우 변환 결과를 다시 파싱하지 못한 이유와 적용된 변환 목록 (규칙 ID와 줄:열)
+    pub reparse_error: Option<String>,
+}
+
+impl Modernizer {
+    /// 변환 옵션을 적용합니다.
+    pub fn with_options(mut self, options: TransformOptions) -> Self {
+        self.explain = options.explain.clone();
+        self.scoped_maybeuninit = options.scoped_maybeuninit;
+        self.annotations = options.annotations;
+        self.doc_tests = options.doc_tests;
+        self.audit = options.audit.then(RefCell::default);
+        self.review = options.interactive.then(Review::default);
+        self.options = options;
+        self
+    }
+
+    /// 기준선에서 `file`에 기록된 변환은 적용하지 않습니다. (같은 규칙/함수/원본 코드의 변환을 기록된 건수만큼 가림)
+    pub fn with_baseline(mut self, baseline: &Baseline, file: &Path) -> Self {
+        self.baseline = Some(BaselineState { remaining: baseline.for_file(file), ..BaselineState::default() });
+        self
+    }
+
+    /// 규칙의 `handler`가 가리키는 처리기 목록을 설정합니다.
+    pub fn with_handlers(mut self, handlers: HandlerRegistry) -> Self {
+        self.handlers = handlers;
+        self
+    }
+
+    /// Rust 소스 문자열 하나를 변환합니다. (보고서 경로는 비어 있음)
+    pub fn modernize_str(self, source: &str) -> Result<TransformResult> {
+        self.modernize(source.to_string(), false)
+    }
+
+    /// 파일 하나를 읽어 변환합니다. 파일은 쓰지 않으며, `.md` 파일은 ```rust 코드 블록만 변환합니다.
+    pub fn modernize_file(mut self, path: &Path) -> Result<TransformResult> {
+        let source_code = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read input file: {}", path.display()))?;
+        self.source_path = path.to_path_buf();
+        self.modernize(source_code, is_markdown(path))
+    }
+
+    fn modernize(mut self, source_code: String, markdown: bool) -> Result<TransformResult> {
+        let input = self.source_path.clone();
+        // Markdown은 문서 전체가 아니라 ```rust 코드 블록만 파싱하므로 빈 AST로 시작
+        let mut bare_fn_source = None;
+        let mut ast = match markdown {
+            true => syn::File { shebang: None, attrs: Vec::new(), items: Vec::new() },
+            false => match syn::parse_file(&source_code) {
+                Ok(ast) => ast,
+                Err(err) => match self.parse_with_bare_fn(&source_code) {
+                    Some((source, ast)) => {
+                        bare_fn_source = Some(source);
+                        ast
+                    }
+                    None => return Err(err).with_context(|| format!("Failed to parse Rust code as AST: {}", input.display())),
+                },
+            },
+        };
+        // AST 변환 적용
+        log!("\n⚙️ Modernizing code using AST traversal...");
+        // syn은 BOM을 건너뛰고 파싱하므로 span의 바이트 위치도 BOM 이후 기준
+        self.source = bare_fn_source.unwrap_or_else(|| source_code.strip_prefix('\u{feff}').unwrap_or(&source_code).to_string());
+        if !markdown {
+            self.suppressions = Suppressions::scan(&self.source, &ast);
+        }
+        let markdown_code = match markdown {
+            true => self.transform_markdown(&source_code),
+            false => {
+                let max_iterations = self.options.max_iterations;
+                apply_passes(&mut self, &mut ast, max_iterations)?;
+                None
+            }
+        };
+        // 반복 패스에서 같은 노드를 다시 평가한 중복 판정은 한 번만 기록
+        let mut audit = self.audit.take().map(RefCell::into_inner).unwrap_or_default();
+        let mut seen = BTreeSet::new();
+        audit.retain(|entry| seen.insert(serde_json::to_string(entry).unwrap_or_default()));
+
+        if let Some(rule_id) = &self.explain {
+            if self.explain_candidates.get() == 0 {
+                log!("[EXPLAIN] {}: 이 파일에는 method_name이 일치하는 후보 노드가 없습니다.", rule_id);
+            }
+        }
+
+        let mut risk_counts = BTreeMap::new();
+        for change in &self.changes {
+            *risk_counts.entry(change.risk).or_insert(0) += 1;
+        }
+        let mut functions: BTreeMap<String, BTreeMap<String, u32>> = BTreeMap::new();
+        if self.options.by_function {
+            for change in &self.changes {
+                let function = change.function.clone().unwrap_or_else(|| TOP_LEVEL_FN.to_string());
+                *functions.entry(function).or_default().entry(change.rule_id.clone()).or_insert(0) += 1;
+            }
+        }
+        let report = FileReport {
+            path: input,
+            counts: self.counters.clone(),
+            risk_counts,
+            functions,
+            changes: self.changes.clone(),
+            skipped: self.skipped.take(),
+            baselined: self.baseline.as_ref().map_or(0, |baseline| baseline.suppressed.len() as u32),
+            reparse_error: None,
+        };
+        let mut result = TransformResult {
+            report,
+            changed: self.changed,
+            original: String::new(),
+            code: None,
+            audit,
+            reparse_error: None,
+        };
+
+        // 변경 사항 확인 (보고서는 모든 파일을 처리한 뒤 한 번에 출력)
+        // --only-count: 건수만 필요하므로 가장 비싼 코드 재구성(unparse)을 건너뜀
+        if self.options.only_count || (!self.changed && markdown) {
+            result.original = source_code;
+            return Ok(result);
+        }
+        if !self.changed {
+            result.code = reprint_unchanged(&self, &source_code, &ast);
+            result.original = source_code;
+            return Ok(result);
+        }
+
+        // AST를 코드 문자열로 재구성
+        //    (prettyplease는 BOM 없이 LF로 출력하므로 원본의 BOM/줄바꿈/마지막 줄바꿈을 다시 적용)
+        //    (reformat이 아니면 변환된 식의 원본 범위만 바꿔 주석/빈 줄/서식을 그대로 유지하고,
+        //     그렇게 할 수 없는 변환이 있으면 파일 전체를 재출력)
+        //    (Markdown은 코드 블록만 바꾼 원문을 그대로 사용)
+        let layout = SourceLayout::detect(&source_code);
+        let mut modernized_code = match markdown_code {
+            Some(code) => code,
+            None => match preserve_layout(&self, &ast, &layout) {
+                Some(code) => code,
+                None => layout.apply(&prettyplease::unparse(&ast), self.options.line_endings),
+            },
+        };
+        // --format: 팀의 rustfmt 설정으로 정리 (rustfmt는 BOM을 다루지 않으므로 떼었다가 다시 붙임)
+        if self.options.format && !markdown {
+            let code = modernized_code.strip_prefix('\u{feff}').unwrap_or(&modernized_code);
+            let formatted = rustfmt(&self.source_path, code)?;
+            modernized_code = if layout.bom { format!("\u{feff}{}", formatted) } else { formatted };
+        }
+
+        // 재파싱 검증: 템플릿 조합이 잘못된 코드를 만들었는지 쓰기 전에 확인
+        if self.options.fail_on_unparseable_output && !markdown {
+            if let Err(err) = syn::parse_file(&modernized_code) {
+                // 어떤 변환이 잘못된 코드를 만들었는지 찾을 수 있도록 적용된 변환을 위치와 함께 나열
+                let mut message = format!("{}\n   관련 규칙 적용 내역:", err);
+                for change in &self.changes {
+                    let _ = write!(message, "\n  - {} ({}:{})", change.rule_id, change.location.line, change.location.column);
+                }
+                result.report.reparse_error = Some(message.clone());
+                result.reparse_error = Some(message);
+            }
+        }
+
+        result.original = source_code;
+        result.code = Some(modernized_code);
+        Ok(result)
+    }
+
+    /// syn이 파싱하지 못하는 `dyn` 없는 `Fn(...)` 트레이트 객체 앞에 `dyn `을 넣어 다시 파싱합니다. (BOM을 뗀 소스와 AST 반환)
+    /// 경로가 빈 `dyn #bounds` TraitObject 규칙이 있을 때만 시도하며, 넣은 `dyn`은 그 규칙의 변환으로 보고합니다.
+    /// (파싱하려면 필요하므로 검토에서 거절하거나 `// modernizer:ignore`로 막아도 되돌리지 않음)
+    fn parse_with_bare_fn(&mut self, source_code: &str) -> Option<(String, syn::File)> {
+        let applies = self.rules.iter().any(|rule| {
+            rule.ast_type == "TraitObject" && rule.method_name.is_empty() && rule.action == RuleAction::Replace
+                && rule.replacement_template.trim() == "dyn #bounds"
+        });
+        if !applies {
+            return None;
+        }
+        let source = source_code.strip_prefix('\u{feff}').unwrap_or(source_code);
+        let points = bare_fn::insertion_points(source);
+        if points.is_empty() {
+            return None;
+        }
+        let mut rewritten = String::with_capacity(source.len() + points.len() * 4);
+        let mut cursor = 0;
+        for &point in &points {
+            rewritten.push_str(&source[cursor..point]);
+            rewritten.push_str("dyn ");
+            cursor = point;
+        }
+        rewritten.push_str(&source[cursor..]);
+        let ast = syn::parse_file(&rewritten).ok()?;
+        self.inserted_dyn = points.iter().enumerate().map(|(index, point)| point + index * "dyn ".len()).collect();
+        Some((rewritten, ast))
+    }
+}
+
+/// 변환된 노드의 원본 범위만 대체 코드로 바꾼 결과를 반환합니다. (reformat이거나 부분 치환할 수 없으면 None)
+fn preserve_layout(modernizer: &Modernizer, ast: &syn::File, layout: &SourceLayout) -> Option<String> {
+    let options = &modernizer.options;
+    if options.reformat {
+        return None;
+    }
+    let newline = if layout.crlf { "\r\n" } else { "\n" };
+    let spliced = splice_changes(&modernizer.source, &modernizer.changes, ast, newline);
+    if spliced.is_none() {
+        log!("ℹ️ {}: 원본 서식을 유지한 채 바꿀 수 없는 변환이 있어 파일 전체를 재출력합니다.", modernizer.source_path.display());
+    }
+    spliced.map(|spliced| layout.apply_spliced(&spliced, options.line_endings))
+}
+
+/// 변환의 소스 편집을 원본에 적용합니다. 여러 줄 대체 코드의 둘째 줄부터는 원본 줄의 들여쓰기를 붙입니다.
+/// 편집이 없는 변환(문장 단위 변환 등)이 있거나, 범위가 일부만 겹치거나, 결과를 다시 파싱한 토큰이
+/// 변환된 AST와 다르면 (검토 주석 추가 등) None을 반환합니다.
+fn splice_changes(source: &str, changes: &[AppliedChange], ast: &syn::File, newline: &str) -> Option<String> {
+    let mut edits = Vec::with_capacity(changes.len());
+    for (index, change) in changes.iter().enumerate() {
+        let edit = change.edit.as_ref()?;
+        if edit.range.is_empty() || source.get(edit.range.clone()).is_none() {
+            return None;
+        }
+        edits.push((index, edit));
+    }
+    // 바깥 노드의 대체 코드는 안쪽 변환을 이미 포함하므로 가장 바깥 범위만 사용 (범위가 같으면 나중 변환)
+    edits.sort_by(|(a_index, a), (b_index, b)| {
+        a.range.start.cmp(&b.range.start).then(b.range.end.cmp(&a.range.end)).then(b_index.cmp(a_index))
+    });
+
+    let mut spliced = String::with_capacity(source.len());
+    let mut cursor = 0;
+    for (_, edit) in edits {
+        if edit.range.start < cursor {
+            if edit.range.end <= cursor {
+                continue;
+            }
+            return None;
+        }
+        let line_start = source[..edit.range.start].rfind('\n').map_or(0, |index| index + 1);
+        let indent: String = source[line_start..edit.range.start].chars().take_while(|c| c.is_whitespace()).collect();
+        let lines: Vec<String> = edit
+            .code
+            .split('\n')
+            .enumerate()
+            .map(|(index, line)| if index == 0 || line.is_empty() { line.to_string() } else { format!("{}{}", indent, line) })
+            .collect();
+        spliced.push_str(&source[cursor..edit.range.start]);
+        spliced.push_str(&lines.join(newline));
+        cursor = edit.range.end;
+    }
+    spliced.push_str(&source[cursor..]);
+
+    let reparsed = syn::parse_file(&spliced).ok()?;
+    (reparsed.to_token_stream().to_string() == ast.to_token_stream().to_string()).then_some(spliced)
+}
+
+/// --format: `code`를 rustfmt로 정리합니다. 입력 파일의 상위 디렉터리에서 가장 가까운 rustfmt.toml(.rustfmt.toml)을
+/// 설정으로, 가장 가까운 Cargo.toml의 `edition`을 에디션으로 사용합니다. (rustfmt.toml의 edition이 있으면 그쪽이 우선)
+fn rustfmt(input: &Path, code: &str) -> Result<String> {
+    let input = fs::canonicalize(input).unwrap_or_else(|_| input.to_path_buf());
+    let dirs: Vec<&Path> = input.ancestors().skip(1).collect();
+    let config = dirs
+        .iter()
+        .flat_map(|dir| [dir.join("rustfmt.toml"), dir.join(".rustfmt.toml")])
+        .find(|path| path.is_file());
+    let config_edition = config
+        .as_ref()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .is_some_and(|contents| toml_string(&contents, "edition").is_some());
+    let edition = manifest_edition(&input);
+
+    let mut command = process::Command::new("rustfmt");
+    command.args(["--emit", "stdout", "--quiet"]);
+    if let Some(config) = &config {
+        command.arg("--config-path").arg(config);
+    }
+    if let Some(edition) = edition.filter(|_| !config_edition) {
+        command.args(["--edition", &edition]);
+    }
+    let mut child = command
+        .stdin(process::Stdio::piped())
+        .stdout(process::Stdio::piped())
+        .stderr(process::Stdio::piped())
+        .spawn()
+        .with_context(|| "Failed to run `rustfmt` (is it installed and on PATH? `rustup component add rustfmt`)")?;
+
+    // 출력이 파이프 버퍼보다 크면 쓰기와 읽기가 서로를 기다리므로 표준 입력은 별도 스레드에서 씀
+    let mut stdin = child.stdin.take().with_context(|| "Failed to open rustfmt stdin")?;
+    let output = std::thread::scope(|scope| {
+        scope.spawn(move || std::io::Write::write_all(&mut stdin, code.as_bytes()));
+        child.wait_with_output()
+    })
+    .with_context(|| "Failed to read `rustfmt` output")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("`rustfmt` failed for {}: {}", input.display(), stderr.trim());
+    }
+    String::from_utf8(output.stdout).with_context(|| "`rustfmt` output is not valid UTF-8")
+}
+
+/// TOML 문서에서 `key = "value"` 형식의 첫 문자열 값을 찾습니다. (테이블 구분 없이 줄 단위로만 확인)
+fn toml_string(contents: &str, key: &str) -> Option<String> {
+    contents.lines().find_map(|line| {
+        let (name, value) = line.split_once('=')?;
+        if name.trim() != key {
+            return None;
+        }
+        let value = value.split('#').next()?.trim();
+        Some(value.strip_prefix('"')?.strip_suffix('"')?.to_string())
+    })
+}
+
+/// `path`에서 상위로 올라가며 가장 가까운 Cargo.toml의 `edition`을 찾습니다.
+pub fn manifest_edition(path: &Path) -> Option<String> {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    path.ancestors()
+        .skip(1)
+        .find_map(|dir| fs::read_to_string(dir.join("Cargo.toml")).ok())
+        .and_then(|contents| toml_string(&contents, "edition"))
+}
+
+/// 규칙이 적용되지 않은 파일을 재출력(prettyplease)했을 때 원본과 달라지는지 확인합니다.
+/// 공백 외의 내용(주석, 속성 위치 등)이 바뀌면 경고만 하고, force_reprint이면 재출력한 코드를 반환합니다.
+fn reprint_unchanged(modernizer: &Modernizer, source_code: &str, ast: &syn::File) -> Option<String> {
+    let options = &modernizer.options;
+    let reprinted = SourceLayout::detect(source_code).apply(&prettyplease::unparse(ast), options.line_endings);
+    if reprinted == source_code {
+        return None;
+    }
+    if !options.force_reprint {
+        let without_whitespace = |text: &str| text.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+        if without_whitespace(&reprinted) != without_whitespace(source_code) {
+            log!(
+                "⚠️ {}: 적용된 규칙은 없지만 재출력하면 공백 외의 내용(주석, 속성 위치 등)이 바뀝니다. 파일을 쓰지 않습니다. (--force-reprint로 저장 가능)",
+                modernizer.source_path.display()
+            );
+        }
+        return None;
+    }
+
+    log!("🖨️ --force-reprint: 적용된 규칙은 없지만 재출력한 코드를 저장합니다.");
+    Some(reprinted)
+}
+
+/// AST의 루트 노드(File)부터 변환기를 적용합니다. --max-iterations가 2 이상이면 새 변환이 없을 때까지 반복하며,
+/// 서로를 되돌리는 규칙 때문에 이전 패스의 코드로 돌아가면 무한 반복 대신 오류를 반환합니다.
+fn apply_passes(modernizer: &mut Modernizer, ast: &mut syn::File, max_iterations: u32) -> Result<()> {
+    // 각 패스 직후의 코드(0: 원본)와 그 다음 패스가 시작될 때의 변환 기록 위치
+    let mut states = Vec::new();
+    let mut pass_starts = Vec::new();
+    if max_iterations > 1 {
+        states.push(ast.to_token_stream().to_string());
+        pass_starts.push(0);
+    }
+
+    for pass in 1..=max_iterations {
+        let before = modernizer.changes.len();
+        modernizer.visit_file_mut(ast);
+        let applied = modernizer.changes.len() - before;
+        if applied == 0 || max_iterations == 1 {
+            break;
+        }
+
+        let state = ast.to_token_stream().to_string();
+        // 소스 편집만 있고 토큰이 그대로인 패스(`dyn`을 미리 넣고 파싱한 `Fn(...)` 보고 등)는 다시 적용할 새 코드가 없음
+        if states.last() == Some(&state) && !modernizer.changes[before..].iter().any(|change| change.edit.is_none()) {
+            break;
+        }
+        if let Some(previous) = states.iter().position(|earlier| *earlier == state) {
+            let rules: BTreeSet<&str> = modernizer.changes[pass_starts[previous]..]
+                .iter()
+                .map(|change| change.rule_id.as_str())
+                .collect();
+            let rules: Vec<&str> = rules.into_iter().collect();
+            let restored = if previous == 0 { "the original code".to_string() } else { format!("the code of pass {}", previous) };
+            return Err(ValidationError(format!(
+                "Rules undo each other in {}: pass {} restored {} (rules: {})",
+                modernizer.source_path.display(), pass, restored, rules.join(", ")
+            ))
+            .into());
+        }
+        states.push(state);
+        pass_starts.push(modernizer.changes.len());
+
+        if pass == max_iterations {
+            log!("⚠️ --max-iterations {}에 도달했습니다. 마지막 패스에서도 {}건이 변환되었습니다.", max_iterations, applied);
+        } else {
+            log!("🔁 패스 {}: {}건 변환, 새로 만든 코드에 규칙을 다시 적용합니다.", pass, applied);
+        }
+    }
+    Ok(())
+}
+
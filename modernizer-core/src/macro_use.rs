@@ -0,0 +1,240 @@
+/// ----------------------------------------------------
+/// `#[macro_use] extern crate`의 매크로 사용처 분석
+/// ----------------------------------------------------
+/// `#[macro_use]`는 크레이트 루트 뒤쪽 전체(안쪽 모듈 포함)에 매크로를 들여오지만 `use`는 선언한 모듈에만 적용되므로,
+/// 매크로를 호출하는 모듈(파일 루트와 인라인 `mod name { }`)마다 `use`로 들여올 매크로 이름을 모읍니다.
+/// 다른 파일의 모듈(`mod name;`)은 볼 수 없으므로 그런 모듈이 있으면 바꾸지 않습니다.
+use proc_macro2::{TokenStream, TokenTree};
+use std::collections::BTreeSet;
+use syn::{
+    punctuated::Punctuated,
+    visit::{self, Visit},
+    Attribute, Ident, Item, ItemExternCrate, ItemMacro, ItemMod, Macro, Meta, Token, UseTree,
+};
+
+/// 표준 prelude와 컴파일러 내장 매크로 (`#[macro_use]` 크레이트의 매크로로 보지 않음)
+const STD_MACROS: &[&str] = &[
+    "assert", "assert_eq", "assert_ne", "cfg", "column", "compile_error", "concat", "dbg", "debug_assert", "debug_assert_eq",
+    "debug_assert_ne", "env", "eprint", "eprintln", "file", "format", "format_args", "include", "include_bytes", "include_str",
+    "line", "macro_rules", "matches", "module_path", "option_env", "panic", "print", "println", "stringify", "thread_local", "todo",
+    "try", "unimplemented", "unreachable", "vec", "write", "writeln",
+];
+
+/// 표준 라이브러리의 derive 매크로
+const STD_DERIVES: &[&str] = &["Debug", "Clone", "Copy", "PartialEq", "Eq", "PartialOrd", "Ord", "Hash", "Default"];
+
+/// `#[macro_use]`가 들여오는 매크로
+pub(crate) enum MacroList {
+    /// `#[macro_use]`: 크레이트가 내보내는 모든 매크로
+    All,
+    /// `#[macro_use(a, b)]`: 지정한 매크로만
+    Only(BTreeSet<String>),
+}
+
+/// 선언의 `#[macro_use]` 속성을 읽습니다. (없으면 None)
+pub(crate) fn macro_list(attrs: &[Attribute]) -> Option<MacroList> {
+    let attr = attrs.iter().find(|attr| attr.path().is_ident("macro_use"))?;
+    match &attr.meta {
+        Meta::List(_) => {
+            let names = attr.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated).ok()?;
+            Some(MacroList::Only(names.iter().map(Ident::to_string).collect()))
+        }
+        _ => Some(MacroList::All),
+    }
+}
+
+/// 모듈 하나에서 `use`로 들여올 매크로와, 매크로를 호출하는 안쪽 인라인 모듈 (모듈 아이템 목록의 위치)
+#[derive(Default)]
+pub(crate) struct ModuleMacros {
+    pub(crate) macros: BTreeSet<String>,
+    pub(crate) modules: Vec<(usize, ModuleMacros)>,
+}
+
+impl ModuleMacros {
+    fn is_empty(&self) -> bool {
+        self.macros.is_empty() && self.modules.is_empty()
+    }
+}
+
+/// 파일 루트 아이템에서 `declaration`이 들여오는 매크로의 모듈별 사용처를 찾습니다.
+/// 선언을 `use`로 바꾸면 의미가 달라질 수 있으면 그 이유를 반환합니다.
+pub(crate) fn analyze(items: &[Item], declaration: &ItemExternCrate) -> Result<ModuleMacros, String> {
+    let Some(list) = macro_list(&declaration.attrs) else { return Err("has no `#[macro_use]` attribute".to_string()) };
+    if declaration.attrs.len() > 1 {
+        return Err("has attributes other than `#[macro_use]`".to_string());
+    }
+
+    let mut scan = FileScan::default();
+    for item in items {
+        scan.visit_item(item);
+    }
+    if scan.outline_module {
+        return Err("the crate has out-of-line modules (`mod name;`) that may use the macros".to_string());
+    }
+    if scan.nested_module {
+        return Err("a function body declares a module that may use the macros".to_string());
+    }
+    // 다른 `#[macro_use]` 선언이 이름을 지정해 들여오는 매크로는 이 크레이트의 매크로가 아님
+    let mut other_lists = BTreeSet::new();
+    for item in items {
+        let Item::ExternCrate(other) = item else { continue };
+        if other.ident == declaration.ident {
+            continue;
+        }
+        match macro_list(&other.attrs) {
+            Some(MacroList::Only(names)) => other_lists.extend(names),
+            Some(MacroList::All) if matches!(list, MacroList::All) => {
+                return Err(format!(
+                    "`extern crate {}` also imports all its macros; cannot tell which crate defines each macro",
+                    other.ident
+                ));
+            }
+            _ => {}
+        }
+    }
+    let is_candidate = |name: &str| match &list {
+        MacroList::Only(names) => names.contains(name),
+        MacroList::All => !STD_MACROS.contains(&name) && !scan.defined.contains(name) && !other_lists.contains(name),
+    };
+    if matches!(list, MacroList::All) {
+        let derives: Vec<&str> = scan.derives.iter().map(String::as_str).filter(|name| !STD_DERIVES.contains(name)).collect();
+        if !derives.is_empty() {
+            return Err(format!("derives {} may come from the crate", derives.join(", ")));
+        }
+    }
+    if let Some(name) = scan.macro_bodies.iter().find(|name| is_candidate(name)) {
+        return Err(format!("a `macro_rules!` body calls `{}!`, which a `use` does not bring into its expansion sites", name));
+    }
+
+    let uses = collect(items, &is_candidate);
+    if matches!(list, MacroList::All) && uses.is_empty() {
+        return Err("no invocation of the crate's macros found (it may provide attribute macros)".to_string());
+    }
+    Ok(uses)
+}
+
+/// 모듈 아이템 목록에서 호출하는 후보 매크로를 모읍니다. 모듈에서 이미 `use`한 이름은 제외합니다.
+fn collect(items: &[Item], is_candidate: &dyn Fn(&str) -> bool) -> ModuleMacros {
+    let mut module = ModuleMacros::default();
+    let mut invocations = Invocations::default();
+    let mut imported = BTreeSet::new();
+    for (index, item) in items.iter().enumerate() {
+        match item {
+            Item::Mod(ItemMod { content: Some((_, content)), .. }) => {
+                let inner = collect(content, is_candidate);
+                if !inner.is_empty() {
+                    module.modules.push((index, inner));
+                }
+            }
+            Item::Use(item_use) => use_names(&item_use.tree, &mut imported),
+            item => invocations.visit_item(item),
+        }
+    }
+    module.macros = invocations.names.into_iter().filter(|name| is_candidate(name) && !imported.contains(name)).collect();
+    module
+}
+
+/// `use` 트리가 들여오는 마지막 이름들
+fn use_names(tree: &UseTree, names: &mut BTreeSet<String>) {
+    match tree {
+        UseTree::Path(path) => use_names(&path.tree, names),
+        UseTree::Name(name) => {
+            names.insert(name.ident.to_string());
+        }
+        UseTree::Rename(rename) => {
+            names.insert(rename.rename.to_string());
+        }
+        UseTree::Group(group) => group.items.iter().for_each(|tree| use_names(tree, names)),
+        UseTree::Glob(_) => {}
+    }
+}
+
+/// 토큰 안의 `name!` 호출 이름 (매크로 인자 속의 중첩 호출용)
+fn bang_names(tokens: TokenStream, names: &mut BTreeSet<String>) {
+    let mut previous: Option<Ident> = None;
+    for token in tokens {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == '!' => {
+                if let Some(ident) = previous.take() {
+                    names.insert(ident.to_string());
+                }
+            }
+            TokenTree::Ident(ident) => previous = Some(ident),
+            TokenTree::Group(group) => {
+                previous = None;
+                bang_names(group.stream(), names);
+            }
+            _ => previous = None,
+        }
+    }
+}
+
+fn is_macro_rules(mac: &Macro) -> bool {
+    mac.path.is_ident("macro_rules")
+}
+
+/// 경로 없이 호출한 매크로 이름 (`name!(...)`)을 모읍니다. `macro_rules!` 정의의 본문은 제외합니다.
+#[derive(Default)]
+struct Invocations {
+    names: BTreeSet<String>,
+}
+
+impl<'ast> Visit<'ast> for Invocations {
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        if is_macro_rules(mac) {
+            return;
+        }
+        if let Some(ident) = mac.path.get_ident() {
+            self.names.insert(ident.to_string());
+        }
+        bang_names(mac.tokens.clone(), &mut self.names);
+    }
+}
+
+/// 파일 전체에서 매크로 정의, derive, 모듈 구조를 모읍니다.
+#[derive(Default)]
+struct FileScan {
+    /// 파일에서 정의한 `macro_rules!` 이름
+    defined: BTreeSet<String>,
+    /// `macro_rules!` 본문에서 호출하는 매크로 이름
+    macro_bodies: BTreeSet<String>,
+    /// `#[derive(...)]`의 이름 (경로 없이 쓴 것만)
+    derives: BTreeSet<String>,
+    outline_module: bool,
+    nested_module: bool,
+    /// 함수 본문 안을 방문 중인지 여부
+    in_body: bool,
+}
+
+impl<'ast> Visit<'ast> for FileScan {
+    fn visit_item_macro(&mut self, i: &'ast ItemMacro) {
+        if is_macro_rules(&i.mac) {
+            if let Some(ident) = &i.ident {
+                self.defined.insert(ident.to_string());
+            }
+            bang_names(i.mac.tokens.clone(), &mut self.macro_bodies);
+        }
+        visit::visit_item_macro(self, i);
+    }
+
+    fn visit_item_mod(&mut self, i: &'ast ItemMod) {
+        self.outline_module |= i.content.is_none();
+        self.nested_module |= self.in_body;
+        visit::visit_item_mod(self, i);
+    }
+
+    fn visit_block(&mut self, i: &'ast syn::Block) {
+        let in_body = std::mem::replace(&mut self.in_body, true);
+        visit::visit_block(self, i);
+        self.in_body = in_body;
+    }
+
+    fn visit_attribute(&mut self, i: &'ast Attribute) {
+        if i.path().is_ident("derive") {
+            if let Ok(paths) = i.parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated) {
+                self.derives.extend(paths.iter().filter_map(|path| path.get_ident()).map(Ident::to_string));
+            }
+        }
+        visit::visit_attribute(self, i);
+    }
+}
@@ -0,0 +1,217 @@
+/// ----------------------------------------------------
+/// 식 패턴 매칭 (ast_type: Pattern)
+/// ----------------------------------------------------
+/// `$x.ok().unwrap()`, `std::mem::replace($a, Default::default())`처럼 메타변수를 포함한 식으로 노드를 매칭합니다.
+/// 메타변수(`$name` 또는 `#name`)는 임의의 식과 일치하여 같은 이름의 템플릿 자리표시자로 바인딩되고,
+/// 같은 이름이 두 번 나오면 같은 코드여야 합니다. `$_`는 바인딩하지 않는 와일드카드, 인자 목록 끝의
+/// `$rest...`는 남은 인자 전체, 메서드 이름 자리의 `$m`은 메서드 이름(식별자)과 일치합니다.
+/// 메타변수가 없는 부분은 토큰이 같아야 일치합니다.
+use crate::template::{Capture, Captures, PLACEHOLDER_PREFIX};
+use anyhow::{anyhow, Result};
+use proc_macro2::Ident;
+use quote::ToTokens;
+use std::collections::HashSet;
+use syn::{parse::Parser, punctuated::Punctuated, Expr, Macro, Token};
+
+/// 컴파일된 패턴
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    expr: Expr,
+    /// 메타변수 이름 (나오는 순서대로, 중복 없이)
+    names: Vec<String>,
+    /// 인자 목록 끝에서 남은 인자 전체와 일치하는 메타변수 (`$rest...`)
+    spreads: HashSet<String>,
+}
+
+/// 메타변수 이름까지 같은 패턴이면 같은 노드와 일치합니다.
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        tokens_eq(&self.expr, &other.expr)
+    }
+}
+
+impl Pattern {
+    /// 패턴 문자열을 식으로 파싱합니다. (메타변수는 `__modernizer_name` 식별자로 바꿔 파싱)
+    pub fn compile(pattern: &str) -> Result<Self> {
+        let mut source = String::with_capacity(pattern.len());
+        let mut names = Vec::new();
+        let mut spreads = HashSet::new();
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => {
+                    // 문자열 리터럴 내부는 치환하지 않음
+                    source.push(c);
+                    let mut escaped = false;
+                    for inner in chars.by_ref() {
+                        source.push(inner);
+                        match inner {
+                            '\\' if !escaped => escaped = true,
+                            '"' if !escaped => break,
+                            _ => escaped = false,
+                        }
+                    }
+                }
+                '$' | '#' if chars.peek().is_some_and(|next| next.is_alphabetic() || *next == '_') => {
+                    let mut name = String::new();
+                    while let Some(&next) = chars.peek() {
+                        if !(next.is_alphanumeric() || next == '_') {
+                            break;
+                        }
+                        name.push(next);
+                        chars.next();
+                    }
+                    if chars.clone().take(3).eq("...".chars()) {
+                        chars.nth(2);
+                        spreads.insert(name.clone());
+                    }
+                    source.push_str(PLACEHOLDER_PREFIX);
+                    source.push_str(&name);
+                    if name != "_" && !names.contains(&name) {
+                        names.push(name);
+                    }
+                }
+                _ => source.push(c),
+            }
+        }
+        let expr = syn::parse_str(&source).map_err(|err| anyhow!("{}\n  pattern: {}", err, pattern))?;
+        Ok(Pattern { expr, names, spreads })
+    }
+
+    /// 패턴이 바인딩하는 메타변수 이름 (`$_` 제외)
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// 남은 인자 전체와 일치하는 메타변수(`$rest...`)인지 여부
+    pub fn is_spread(&self, name: &str) -> bool {
+        self.spreads.contains(name)
+    }
+
+    /// 식이 패턴과 일치하면 메타변수 바인딩을 반환합니다.
+    pub fn matches(&self, target: &Expr) -> Option<Captures> {
+        let mut captures = Captures::new();
+        self.match_expr(&self.expr, target, &mut captures).then_some(captures)
+    }
+
+    fn match_expr(&self, pattern: &Expr, target: &Expr, captures: &mut Captures) -> bool {
+        if let Some(name) = metavariable(pattern) {
+            return bind(captures, &name, Capture::Expr(Box::new(target.clone())));
+        }
+        if !has_metavariable(pattern) {
+            return tokens_eq(pattern, target);
+        }
+        match (pattern, target) {
+            (Expr::MethodCall(pattern), Expr::MethodCall(target)) => {
+                self.match_ident(&pattern.method, &target.method, captures)
+                    && tokens_eq(&pattern.turbofish, &target.turbofish)
+                    && self.match_expr(&pattern.receiver, &target.receiver, captures)
+                    && self.match_list(&pattern.args, &target.args, captures)
+            }
+            (Expr::Call(pattern), Expr::Call(target)) => {
+                self.match_expr(&pattern.func, &target.func, captures) && self.match_list(&pattern.args, &target.args, captures)
+            }
+            (Expr::Macro(pattern), Expr::Macro(target)) => self.match_macro(&pattern.mac, &target.mac, captures),
+            (Expr::Binary(pattern), Expr::Binary(target)) => {
+                tokens_eq(&pattern.op, &target.op)
+                    && self.match_expr(&pattern.left, &target.left, captures)
+                    && self.match_expr(&pattern.right, &target.right, captures)
+            }
+            (Expr::Unary(pattern), Expr::Unary(target)) => {
+                tokens_eq(&pattern.op, &target.op) && self.match_expr(&pattern.expr, &target.expr, captures)
+            }
+            (Expr::Reference(pattern), Expr::Reference(target)) => {
+                pattern.mutability.is_some() == target.mutability.is_some() && self.match_expr(&pattern.expr, &target.expr, captures)
+            }
+            (Expr::Field(pattern), Expr::Field(target)) => {
+                tokens_eq(&pattern.member, &target.member) && self.match_expr(&pattern.base, &target.base, captures)
+            }
+            (Expr::Index(pattern), Expr::Index(target)) => {
+                self.match_expr(&pattern.expr, &target.expr, captures) && self.match_expr(&pattern.index, &target.index, captures)
+            }
+            (Expr::Cast(pattern), Expr::Cast(target)) => {
+                tokens_eq(&pattern.ty, &target.ty) && self.match_expr(&pattern.expr, &target.expr, captures)
+            }
+            (Expr::Try(pattern), Expr::Try(target)) => self.match_expr(&pattern.expr, &target.expr, captures),
+            (Expr::Await(pattern), Expr::Await(target)) => self.match_expr(&pattern.base, &target.base, captures),
+            (Expr::Paren(pattern), Expr::Paren(target)) => self.match_expr(&pattern.expr, &target.expr, captures),
+            (Expr::Tuple(pattern), Expr::Tuple(target)) => self.match_list(&pattern.elems, &target.elems, captures),
+            (Expr::Array(pattern), Expr::Array(target)) => self.match_list(&pattern.elems, &target.elems, captures),
+            _ => false,
+        }
+    }
+
+    /// 인자/원소 목록을 위치별로 매칭합니다. 마지막 패턴이 `$rest...`이면 남은 원소 전체와 일치합니다.
+    fn match_list(&self, patterns: &Punctuated<Expr, Token![,]>, targets: &Punctuated<Expr, Token![,]>, captures: &mut Captures) -> bool {
+        let mut targets = targets.iter();
+        for (index, pattern) in patterns.iter().enumerate() {
+            if let Some(name) = metavariable(pattern).filter(|name| self.spreads.contains(name)) {
+                if index + 1 == patterns.len() {
+                    return bind(captures, &name, Capture::List(targets.cloned().collect()));
+                }
+            }
+            match targets.next() {
+                Some(target) if self.match_expr(pattern, target, captures) => {}
+                _ => return false,
+            }
+        }
+        targets.next().is_none()
+    }
+
+    /// 매크로는 경로가 같고 인자를 쉼표로 구분된 식으로 파싱할 수 있을 때 인자별로 매칭합니다.
+    fn match_macro(&self, pattern: &Macro, target: &Macro, captures: &mut Captures) -> bool {
+        if !tokens_eq(&pattern.path, &target.path) {
+            return false;
+        }
+        let parse = |mac: &Macro| Punctuated::<Expr, Token![,]>::parse_terminated.parse2(mac.tokens.clone()).ok();
+        match (parse(pattern), parse(target)) {
+            (Some(patterns), Some(targets)) => self.match_list(&patterns, &targets, captures),
+            _ => pattern.tokens.to_string() == target.tokens.to_string(),
+        }
+    }
+
+    fn match_ident(&self, pattern: &Ident, target: &Ident, captures: &mut Captures) -> bool {
+        match pattern.to_string().strip_prefix(PLACEHOLDER_PREFIX) {
+            Some(name) => bind(captures, name, Capture::Ident(target.clone())),
+            None => pattern == target,
+        }
+    }
+}
+
+/// 식이 메타변수 하나이면 이름을 반환합니다.
+fn metavariable(expr: &Expr) -> Option<String> {
+    let Expr::Path(path) = expr else { return None };
+    let ident = path.path.get_ident()?.to_string();
+    ident.strip_prefix(PLACEHOLDER_PREFIX).map(str::to_string)
+}
+
+fn has_metavariable(expr: &Expr) -> bool {
+    expr.to_token_stream().to_string().contains(PLACEHOLDER_PREFIX)
+}
+
+/// 메타변수를 바인딩합니다. 이미 바인딩된 이름이면 같은 코드인지 확인하고, `_`는 바인딩하지 않습니다.
+fn bind(captures: &mut Captures, name: &str, capture: Capture) -> bool {
+    if name == "_" {
+        return true;
+    }
+    match captures.get(name) {
+        Some(existing) => capture_tokens(existing) == capture_tokens(&capture),
+        None => {
+            captures.insert(name.to_string(), capture);
+            true
+        }
+    }
+}
+
+fn capture_tokens(capture: &Capture) -> String {
+    match capture {
+        Capture::Expr(expr) => expr.to_token_stream().to_string(),
+        Capture::List(list) => list.iter().map(|expr| expr.to_token_stream().to_string()).collect::<Vec<_>>().join(","),
+        Capture::Ident(ident) => ident.to_string(),
+        Capture::Type(ty) => ty.to_token_stream().to_string(),
+    }
+}
+
+fn tokens_eq(a: &impl ToTokens, b: &impl ToTokens) -> bool {
+    a.to_token_stream().to_string() == b.to_token_stream().to_string()
+}
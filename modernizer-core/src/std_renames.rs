@@ -0,0 +1,54 @@
+/// ----------------------------------------------------
+/// 표준 라이브러리 이름 변경 표 → 규칙
+/// ----------------------------------------------------
+/// `std_renames.json`의 대응 표(이전 이름, 새 이름, 새 이름이 안정화된 버전)를 기본 규칙으로 펼칩니다.
+/// methods는 메서드 이름만 바꾸는 ExprMethodCall 규칙(`trim_left` → `trim_start`), paths는 경로 앞부분을 바꾸는
+/// Path 규칙(`std::u32::MAX` → `u32::MAX`)이 되므로, 표에 줄을 더하는 것만으로 폐기된 API를 추가할 수 있습니다.
+use crate::ModernizerRule;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+
+const STD_RENAMES_JSON: &str = include_str!("../std_renames.json");
+
+#[derive(Deserialize)]
+struct RenameTable {
+    methods: Vec<Rename>,
+    paths: Vec<Rename>,
+}
+
+/// 표의 한 줄
+#[derive(Deserialize)]
+struct Rename {
+    from: String,
+    to: String,
+    /// 새 이름을 쓸 수 있는 최소 Rust 버전
+    since: String,
+    doc_url: String,
+}
+
+/// 이름 변경 표의 규칙 (methods 다음 paths 순서, ID는 `std_rename_<이전 이름>`)
+pub(crate) fn rules() -> Result<Vec<ModernizerRule>> {
+    let table: RenameTable = serde_json::from_str(STD_RENAMES_JSON).context("Failed to parse built-in std rename table.")?;
+    let methods = table.methods.iter().map(|rename| (rename, "ExprMethodCall", "rename_method"));
+    let paths = table.paths.iter().map(|rename| (rename, "Path", "replace"));
+    methods
+        .chain(paths)
+        .map(|(rename, ast_type, action)| {
+            let rule = json!({
+                "id": format!("std_rename_{}", rename.from.replace("::", "_").to_lowercase()),
+                "ast_type": ast_type,
+                "method_name": rename.from,
+                "action": action,
+                "replacement_template": rename.to,
+                "severity": "info",
+                "risk": "low",
+                "doc_url": rename.doc_url,
+                "nested_method": null,
+                "emit_comment": false,
+                "min_rust_version": rename.since,
+            });
+            serde_json::from_value(rule).with_context(|| format!("Invalid std rename entry `{}`", rename.from))
+        })
+        .collect()
+}
@@ -0,0 +1,147 @@
+/// ----------------------------------------------------
+/// 인라인 억제 주석 (`// modernizer:ignore`)
+/// ----------------------------------------------------
+/// `// modernizer:ignore`는 모든 규칙을, `// modernizer:ignore(rule_a, rule_b)`는 지정한 규칙만 막습니다.
+/// 주석만 있는 줄이면 다음 코드 줄에서 시작하는 식/문장/아이템 전체(여러 줄이면 끝까지)에,
+/// 코드 뒤에 붙은 주석이면 그 줄에서 시작하거나 끝나는 변환에 적용됩니다.
+use proc_macro2::Span;
+use std::collections::{BTreeMap, BTreeSet};
+use syn::{
+    spanned::Spanned,
+    visit::{self, Visit},
+    Expr, ImplItem, Item, Stmt, TraitItem,
+};
+
+const MARKER: &str = "modernizer:ignore";
+
+/// 억제 주석 하나
+struct Suppression {
+    /// 주석이 있는 줄 (1부터)
+    comment_line: usize,
+    /// 억제하는 줄 범위
+    first: usize,
+    last: usize,
+    /// 코드 뒤에 붙은 주석인지 여부
+    trailing: bool,
+    /// 억제할 규칙 (None: 모든 규칙)
+    rules: Option<Vec<String>>,
+}
+
+/// 파일 하나의 억제 주석 목록
+#[derive(Default)]
+pub(crate) struct Suppressions {
+    entries: Vec<Suppression>,
+}
+
+impl Suppressions {
+    /// 소스의 억제 주석을 찾고, 주석 다음 줄에서 시작하는 노드의 끝 줄까지 범위를 넓힙니다.
+    pub(crate) fn scan(source: &str, ast: &syn::File) -> Self {
+        let lines: Vec<&str> = source.lines().collect();
+        let mut entries = Vec::new();
+        for (index, line) in lines.iter().enumerate() {
+            let Some((code, rules)) = parse_comment(line) else { continue };
+            let trailing = !code.trim().is_empty();
+            // 주석만 있는 줄이면 빈 줄과 다른 주석을 건너뛴 다음 코드 줄이 대상
+            let target = match trailing {
+                true => Some(index + 1),
+                false => lines[index + 1..]
+                    .iter()
+                    .position(|next| !next.trim().is_empty() && !next.trim_start().starts_with("//"))
+                    .map(|offset| index + offset + 2),
+            };
+            if let Some(target) = target {
+                entries.push(Suppression { comment_line: index + 1, first: target, last: target, trailing, rules });
+            }
+        }
+        if entries.is_empty() {
+            return Suppressions::default();
+        }
+
+        let targets: BTreeSet<usize> = entries.iter().filter(|entry| !entry.trailing).map(|entry| entry.first).collect();
+        let mut extents = Extents { targets: &targets, ends: BTreeMap::new() };
+        extents.visit_file(ast);
+        for entry in entries.iter_mut().filter(|entry| !entry.trailing) {
+            entry.last = extents.ends.get(&entry.first).copied().unwrap_or(entry.first);
+        }
+        Suppressions { entries }
+    }
+
+    /// `line`..=`end_line`에서 적용된 규칙 변환을 막는 주석이 있으면 그 주석의 줄 번호를 반환합니다.
+    pub(crate) fn find(&self, rule_id: &str, line: usize, end_line: usize) -> Option<usize> {
+        self.entries
+            .iter()
+            .find(|entry| {
+                let covers = (entry.first..=entry.last).contains(&line) || (entry.trailing && end_line == entry.first);
+                covers && entry.rules.as_ref().is_none_or(|rules| rules.iter().any(|rule| rule == rule_id))
+            })
+            .map(|entry| entry.comment_line)
+    }
+}
+
+/// 줄에서 억제 주석을 찾아 (주석 앞의 코드, 억제할 규칙)을 반환합니다. 문서 주석(`///`, `//!`)은 제외합니다.
+fn parse_comment(line: &str) -> Option<(&str, Option<Vec<String>>)> {
+    let mut search = 0;
+    while let Some(found) = line[search..].find("//") {
+        let start = search + found;
+        search = start + 2;
+        let comment = &line[start + 2..];
+        if comment.starts_with(['/', '!']) {
+            continue;
+        }
+        let Some(rest) = comment.trim_start().strip_prefix(MARKER) else { continue };
+        let rules = match rest.strip_prefix('(') {
+            Some(list) => {
+                let list = &list[..list.find(')')?];
+                Some(list.split(',').map(str::trim).filter(|id| !id.is_empty()).map(str::to_string).collect())
+            }
+            // `modernizer:ignored`처럼 이어지는 단어는 억제 주석이 아님 (뒤에 붙는 설명은 허용)
+            None if rest.is_empty() || rest.starts_with(char::is_whitespace) => None,
+            None => continue,
+        };
+        return Some((&line[..start], rules));
+    }
+    None
+}
+
+/// 대상 줄에서 시작하는 식/문장/아이템의 가장 늦은 끝 줄을 모읍니다.
+struct Extents<'a> {
+    targets: &'a BTreeSet<usize>,
+    ends: BTreeMap<usize, usize>,
+}
+
+impl Extents<'_> {
+    fn extend(&mut self, span: Span) {
+        let (start, end) = (span.start().line, span.end().line);
+        if self.targets.contains(&start) {
+            let last = self.ends.entry(start).or_insert(start);
+            *last = (*last).max(end);
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Extents<'_> {
+    fn visit_item(&mut self, i: &'ast Item) {
+        self.extend(i.span());
+        visit::visit_item(self, i);
+    }
+
+    fn visit_impl_item(&mut self, i: &'ast ImplItem) {
+        self.extend(i.span());
+        visit::visit_impl_item(self, i);
+    }
+
+    fn visit_trait_item(&mut self, i: &'ast TraitItem) {
+        self.extend(i.span());
+        visit::visit_trait_item(self, i);
+    }
+
+    fn visit_stmt(&mut self, i: &'ast Stmt) {
+        self.extend(i.span());
+        visit::visit_stmt(self, i);
+    }
+
+    fn visit_expr(&mut self, i: &'ast Expr) {
+        self.extend(i.span());
+        visit::visit_expr(self, i);
+    }
+}
@@ -0,0 +1,359 @@
+/// ----------------------------------------------------
+/// 규칙 템플릿(replacement_template) 런타임 전개
+/// ----------------------------------------------------
+/// `#name`(또는 `$name`) 자리표시자를 먼저 식별자(`__modernizer_name`)로 바꿔 `syn::parse_str`로 파싱한 뒤,
+/// AST 단계에서 매칭된 노드로 치환합니다. 문자열 치환과 달리 연산자 우선순위
+/// (`#receiver?`에 `a + b`가 들어오는 경우 등)와 원본 span이 그대로 보존됩니다.
+use anyhow::{anyhow, bail, Result};
+use proc_macro2::{Group, Ident, TokenStream, TokenTree};
+use quote::ToTokens;
+use std::collections::HashMap;
+use syn::{
+    punctuated::Punctuated,
+    visit_mut::{self, VisitMut},
+    Expr, ExprCall, ExprMethodCall, Item, Macro, Token, Type,
+};
+
+/// 파싱 가능한 식별자로 바꾼 자리표시자의 접두사
+pub(crate) const PLACEHOLDER_PREFIX: &str = "__modernizer_";
+
+/// 자리표시자에 바인딩된 값
+#[derive(Clone)]
+pub enum Capture {
+    /// 단일 식 (예: `#receiver`, `#arg0`)
+    Expr(Box<Expr>),
+    /// 쉼표로 이어지는 식 목록 (예: `#args`)
+    List(Vec<Expr>),
+    /// 식별자 (예: `#method` → 메서드 이름, `#receiver.#method()`처럼 사용)
+    Ident(Ident),
+    /// 타입 (예: ItemStatic 규칙의 `#ty`, `#inner_ty`)
+    Type(Box<Type>),
+}
+
+/// 자리표시자 이름(`#` 제외) → 바인딩 값
+pub type Captures = HashMap<String, Capture>;
+
+/// 호출 인자를 `#args`, `#arg0`, `#arg1`, ... 로 바인딩합니다.
+pub fn bind_args<'a>(captures: &mut Captures, args: impl IntoIterator<Item = &'a Expr>) {
+    bind_prefixed_args(captures, "", args);
+}
+
+/// 호출 인자를 접두사를 붙여 바인딩합니다. (예: 접두사 "recv_" → `#recv_args`, `#recv_arg0`, ...)
+pub fn bind_prefixed_args<'a>(captures: &mut Captures, prefix: &str, args: impl IntoIterator<Item = &'a Expr>) {
+    let args: Vec<Expr> = args.into_iter().cloned().collect();
+    for (index, arg) in args.iter().enumerate() {
+        captures.insert(format!("{}arg{}", prefix, index), Capture::Expr(Box::new(arg.clone())));
+    }
+    captures.insert(format!("{}args", prefix), Capture::List(args));
+}
+
+/// 템플릿을 전개하여 새 식을 만듭니다.
+pub fn expand(template: &str, captures: &Captures) -> Result<Expr> {
+    let source = rewrite_placeholders(template, captures)?;
+
+    let mut expr: Expr = syn::parse_str(&source).map_err(|err| {
+        // 오류 위치는 자리표시자가 치환된 소스 기준이므로 치환 결과를 함께 보여줍니다.
+        // (입력 끝에서 실패하면 call_site span이라 위치 정보가 없음)
+        let span = err.span();
+        let position = if span.byte_range().is_empty() {
+            String::new()
+        } else {
+            format!(" at {}:{}", span.start().line, span.start().column + 1)
+        };
+        anyhow!("{}{}\n  template:    {}\n  substituted: {}", err, position, template, source)
+    })?;
+
+    Substitute { captures }.visit_expr_mut(&mut expr);
+    Ok(expr)
+}
+
+/// 템플릿을 전개하여 아이템 하나를 만듭니다. (ItemExternCrate 규칙의 `use #crate as #rename;`, ItemStatic 규칙의 `static` 등)
+pub fn expand_item(template: &str, captures: &Captures) -> Result<Item> {
+    let source = rewrite_placeholders(template, captures)?;
+    let mut item: Item =
+        syn::parse_str(&source).map_err(|err| anyhow!("{}\n  template:    {}\n  substituted: {}", err, template, source))?;
+    Substitute { captures }.visit_item_mut(&mut item);
+    Ok(item)
+}
+
+/// 텍스트(review_note 등)의 `{original}`을 원본 소스 텍스트로 바꿉니다.
+pub fn fill_original(text: &str, original: &str) -> String {
+    text.replace("{original}", original)
+}
+
+/// 템플릿의 문자열 리터럴 안에 있는 `{original}`만 원본 소스 텍스트로 바꿉니다. (따옴표와 `\`는 이스케이프)
+/// 문자열 밖의 코드에서는 텍스트 대신 `#original` 자리표시자로 매칭된 식을 사용합니다.
+pub fn fill_original_in_strings(template: &str, original: &str) -> String {
+    let escaped = original.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut output = String::with_capacity(template.len());
+    let mut chars = template.chars();
+
+    while let Some(c) = chars.next() {
+        output.push(c);
+        if c != '"' {
+            continue;
+        }
+        let mut literal = String::new();
+        let mut escaped_char = false;
+        let mut closed = false;
+        for inner in chars.by_ref() {
+            match inner {
+                '\\' if !escaped_char => escaped_char = true,
+                '"' if !escaped_char => {
+                    closed = true;
+                    break;
+                }
+                _ => escaped_char = false,
+            }
+            literal.push(inner);
+        }
+        output.push_str(&literal.replace("{original}", &escaped));
+        if closed {
+            output.push('"');
+        }
+    }
+    output
+}
+
+/// `#name`/`$name`을 `__modernizer_name` 식별자로 바꿉니다. 문자열 리터럴과 `#[...]` 속성은 그대로 둡니다.
+/// 목록 자리표시자 뒤의 `...`(예: `$args...`)는 펼쳐 넣는다는 표시로 보고 지웁니다.
+fn rewrite_placeholders(template: &str, captures: &Captures) -> Result<String> {
+    let mut output = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                // 문자열 리터럴 내부는 치환하지 않음
+                output.push(c);
+                let mut escaped = false;
+                for inner in chars.by_ref() {
+                    output.push(inner);
+                    match inner {
+                        '\\' if !escaped => escaped = true,
+                        '"' if !escaped => break,
+                        _ => escaped = false,
+                    }
+                }
+            }
+            '#' | '$' if chars.peek().is_some_and(|next| next.is_alphabetic() || *next == '_') => {
+                let mut name = String::new();
+                while let Some(&next) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    name.push(next);
+                    chars.next();
+                }
+                let capture = captures.get(&name);
+                if capture.is_none() {
+                    if let Some(count) = arg_count_for(&name, captures) {
+                        bail!(
+                            "Placeholder `{}{}` is out of range: the call it refers to has {} argument(s) (template: {})",
+                            c, name, count, template
+                        );
+                    }
+                    bail!("Unknown placeholder `{}{}` in replacement template: {}", c, name, template);
+                }
+                output.push_str(PLACEHOLDER_PREFIX);
+                output.push_str(&name);
+                if matches!(capture, Some(Capture::List(_))) && chars.clone().take(3).eq("...".chars()) {
+                    chars.nth(2);
+                }
+            }
+            _ => output.push(c),
+        }
+    }
+    Ok(output)
+}
+
+/// 자리표시자를 주어진 소스 텍스트로 바꾼 템플릿 텍스트를 만듭니다. (원본 서식을 유지하는 아이템 편집용)
+/// 문자열 리터럴 안은 그대로 두며, 텍스트가 없는 자리표시자가 있으면 None을 반환합니다.
+pub fn fill_source(template: &str, sources: &HashMap<String, String>) -> Option<String> {
+    let mut output = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                output.push(c);
+                let mut escaped = false;
+                for inner in chars.by_ref() {
+                    output.push(inner);
+                    match inner {
+                        '\\' if !escaped => escaped = true,
+                        '"' if !escaped => break,
+                        _ => escaped = false,
+                    }
+                }
+            }
+            '#' | '$' if chars.peek().is_some_and(|next| next.is_alphabetic() || *next == '_') => {
+                let mut name = String::new();
+                while let Some(&next) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    name.push(next);
+                    chars.next();
+                }
+                output.push_str(sources.get(&name)?);
+                if chars.clone().take(3).eq("...".chars()) {
+                    chars.nth(2);
+                }
+            }
+            _ => output.push(c),
+        }
+    }
+    Some(output)
+}
+
+/// 템플릿에 쓰인 자리표시자 이름을 나오는 순서대로 (중복 없이) 반환합니다. (문자열 리터럴 안은 제외, 규칙 검사용)
+pub fn placeholder_names(template: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut chars = template.chars().peekable();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            match c {
+                '\\' if !escaped => escaped = true,
+                '"' if !escaped => in_string = false,
+                _ => escaped = false,
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '#' | '$' if chars.peek().is_some_and(|next| next.is_alphabetic() || *next == '_') => {
+                let mut name = String::new();
+                while let Some(&next) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    name.push(next);
+                    chars.next();
+                }
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+/// `#argN`/`#recv_argN` 형태의 자리표시자이면 같은 호출의 실제 인자 개수를 반환합니다.
+fn arg_count_for(name: &str, captures: &Captures) -> Option<usize> {
+    let (prefix, index) = name.rsplit_once("arg")?;
+    if index.is_empty() || !index.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    match captures.get(&format!("{}args", prefix))? {
+        Capture::List(args) => Some(args.len()),
+        Capture::Expr(_) | Capture::Ident(_) | Capture::Type(_) => None,
+    }
+}
+
+/// AST에 남은 `__modernizer_name` 식별자를 바인딩된 노드로 치환하는 방문자
+struct Substitute<'a> {
+    captures: &'a Captures,
+}
+
+impl Substitute<'_> {
+    /// 식이 자리표시자이면 바인딩 값을 반환합니다.
+    fn lookup(&self, expr: &Expr) -> Option<&Capture> {
+        let Expr::Path(path) = expr else { return None };
+        let name = path.path.get_ident()?.to_string();
+        self.captures.get(name.strip_prefix(PLACEHOLDER_PREFIX)?)
+    }
+
+    /// 인자 목록 안의 `#args`를 여러 인자로 펼칩니다.
+    fn splice_args(&self, args: &mut Punctuated<Expr, Token![,]>) {
+        let mut spliced = Punctuated::new();
+        for arg in std::mem::take(args) {
+            match self.lookup(&arg) {
+                Some(Capture::List(list)) => spliced.extend(list.iter().cloned()),
+                _ => spliced.push(arg),
+            }
+        }
+        *args = spliced;
+    }
+
+    /// 매크로 토큰 안의 자리표시자 식별자를 바인딩된 토큰으로 치환합니다.
+    fn substitute_tokens(&self, tokens: TokenStream) -> TokenStream {
+        tokens
+            .into_iter()
+            .flat_map(|tree| -> Vec<TokenTree> {
+                match tree {
+                    TokenTree::Ident(ref ident) => {
+                        let name = ident.to_string();
+                        let capture = name
+                            .strip_prefix(PLACEHOLDER_PREFIX)
+                            .and_then(|name| self.captures.get(name));
+                        match capture {
+                            Some(Capture::Expr(expr)) => expr.to_token_stream().into_iter().collect(),
+                            Some(Capture::List(list)) => {
+                                let list: Punctuated<Expr, Token![,]> = list.iter().cloned().collect();
+                                list.to_token_stream().into_iter().collect()
+                            }
+                            Some(Capture::Ident(ident)) => vec![TokenTree::Ident(ident.clone())],
+                            Some(Capture::Type(ty)) => ty.to_token_stream().into_iter().collect(),
+                            None => vec![tree],
+                        }
+                    }
+                    TokenTree::Group(group) => {
+                        let mut substituted =
+                            Group::new(group.delimiter(), self.substitute_tokens(group.stream()));
+                        substituted.set_span(group.span());
+                        vec![TokenTree::Group(substituted)]
+                    }
+                    other => vec![other],
+                }
+            })
+            .collect()
+    }
+}
+
+impl VisitMut for Substitute<'_> {
+    fn visit_expr_mut(&mut self, i: &mut Expr) {
+        if let Some(Capture::Expr(expr)) = self.lookup(i) {
+            *i = (**expr).clone();
+            return;
+        }
+        visit_mut::visit_expr_mut(self, i);
+    }
+
+    fn visit_type_mut(&mut self, i: &mut Type) {
+        if let Type::Path(type_path) = i {
+            let name = type_path.path.get_ident().map(Ident::to_string).unwrap_or_default();
+            if let Some(Capture::Type(ty)) = name.strip_prefix(PLACEHOLDER_PREFIX).and_then(|name| self.captures.get(name)) {
+                *i = (**ty).clone();
+                return;
+            }
+        }
+        visit_mut::visit_type_mut(self, i);
+    }
+
+    fn visit_expr_call_mut(&mut self, i: &mut ExprCall) {
+        self.splice_args(&mut i.args);
+        visit_mut::visit_expr_call_mut(self, i);
+    }
+
+    fn visit_expr_method_call_mut(&mut self, i: &mut ExprMethodCall) {
+        self.splice_args(&mut i.args);
+        visit_mut::visit_expr_method_call_mut(self, i);
+    }
+
+    fn visit_ident_mut(&mut self, i: &mut Ident) {
+        let name = i.to_string();
+        if let Some(Capture::Ident(ident)) = name.strip_prefix(PLACEHOLDER_PREFIX).and_then(|name| self.captures.get(name)) {
+            *i = ident.clone();
+        }
+    }
+
+    fn visit_macro_mut(&mut self, i: &mut Macro) {
+        i.tokens = self.substitute_tokens(std::mem::take(&mut i.tokens));
+    }
+}
@@ -0,0 +1,309 @@
+/// ----------------------------------------------------
+/// 규칙 파일 검사 (validate-rules)
+/// ----------------------------------------------------
+/// 로드는 되지만 실제로는 아무 노드에도 적용되지 않는 규칙(ast_type 오타, 전개할 수 없는 템플릿,
+/// 중복 ID, 앞선 규칙에 가려지는 규칙 등)을 찾아 보고합니다.
+use crate::{
+    pattern::Pattern,
+    template::{self, Capture, Captures},
+    head_path, ModernizerRule, RuleAction, Severity, TestBehavior, ReceiverKind, AST_TYPES,
+};
+use std::collections::HashMap;
+use syn::{Expr, Ident, Path, RangeLimits, Type};
+
+/// 규칙 하나에서 발견한 문제
+#[derive(Debug, Clone)]
+pub struct RuleIssue {
+    /// 검사한 규칙 목록에서의 위치
+    pub index: usize,
+    pub rule_id: String,
+    /// Error: 규칙이 전혀 적용되지 않거나 실행 중 오류가 남, Warning: 의도와 다르게 동작할 가능성이 높음
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// 규칙 목록을 검사하여 발견한 문제를 규칙 순서대로 반환합니다.
+pub fn validate_rules(rules: &[ModernizerRule]) -> Vec<RuleIssue> {
+    let mut issues = Vec::new();
+    for (index, rule) in rules.iter().enumerate() {
+        let mut report = |severity: Severity, message: String| {
+            issues.push(RuleIssue { index, rule_id: rule.id.clone(), severity, message });
+        };
+
+        if let Some(first) = rules[..index].iter().position(|earlier| earlier.id == rule.id) {
+            report(Severity::Error, format!("duplicate rule id (first defined as rule #{})", first + 1));
+        }
+
+        if !AST_TYPES.contains(&rule.ast_type.as_str()) {
+            let suggestion = AST_TYPES
+                .iter()
+                .find(|known| known.eq_ignore_ascii_case(&rule.ast_type) || known.strip_prefix("Expr") == Some(rule.ast_type.as_str()))
+                .map(|known| format!(" (did you mean `{}`?)", known))
+                .unwrap_or_default();
+            report(Severity::Error, format!("unknown ast_type `{}`{}; expected one of {}", rule.ast_type, suggestion, AST_TYPES.join(", ")));
+            continue;
+        }
+
+        if let Some(reason) = never_matches(rule) {
+            report(Severity::Error, format!("never matches: {}", reason));
+        }
+        for (field, template) in templates(rule) {
+            for (severity, message) in check_template(rule, template) {
+                report(severity, format!("{}: {}", field, message));
+            }
+        }
+        if let Some(pattern) = pattern_of(rule) {
+            let used: Vec<String> = templates(rule).flat_map(|(_, template)| template::placeholder_names(template)).collect();
+            let unused: Vec<&String> = pattern.names().iter().filter(|name| !used.contains(name)).collect();
+            if rule.handler.is_none() && !unused.is_empty() {
+                let unused: Vec<String> = unused.iter().map(|name| format!("${}", name)).collect();
+                report(Severity::Warning, format!("pattern binds {} but replacement_template never uses it (use `$_` to ignore)", unused.join(", ")));
+            }
+        }
+        if let Some(earlier) = rules[..index].iter().find(|earlier| shadows(earlier, rule)) {
+            report(Severity::Warning, format!("unreachable: every node it matches is rewritten first by earlier rule `{}`", earlier.id));
+        }
+    }
+    issues
+}
+
+/// 매칭 조건만으로 적용될 수 없는 규칙이면 이유를 반환합니다.
+fn never_matches(rule: &ModernizerRule) -> Option<String> {
+    match rule.ast_type.as_str() {
+        "ExprMethodCall" | "ExprCall" | "ExprMacro" | "ExprBinary" | "Attribute" | "ItemDerive" | "Path" | "ExprPath" | "ItemFn"
+        | "ItemTrait"
+            if rule.method_name.is_empty() =>
+        {
+            Some(format!("method_name is empty ({} rules match by name)", rule.ast_type))
+        }
+        "ExprMatch" if !matches!(rule.method_name.rsplit("::").next(), Some("Ok" | "Some")) => {
+            Some(format!("method_name must be `Ok` or `Some` for ExprMatch, found `{}`", rule.method_name))
+        }
+        "Pattern" if rule.pattern.is_none() => Some("ast_type is Pattern but no pattern is given".to_string()),
+        "ImplItemFn" if !rule.method_name.contains("::") => {
+            Some(format!("method_name must be `Trait::method` for ImplItemFn, found `{}`", rule.method_name))
+        }
+        _ => None,
+    }
+}
+
+fn pattern_of(rule: &ModernizerRule) -> Option<Pattern> {
+    rule.pattern.as_deref().filter(|_| rule.ast_type == "Pattern").and_then(|pattern| Pattern::compile(pattern).ok())
+}
+
+/// 전개되는 템플릿 (필드 이름, 템플릿). handler가 있으면 replacement_template은 쓰이지 않음
+fn templates(rule: &ModernizerRule) -> impl Iterator<Item = (&'static str, &str)> {
+    let primary = rule.handler.is_none().then_some(("replacement_template", rule.replacement_template.as_str()));
+    let fallback = rule.fallback_template.as_deref().map(|template| ("fallback_template", template));
+    primary.into_iter().chain(fallback)
+}
+
+/// 템플릿의 자리표시자와 문법을 검사합니다.
+fn check_template(rule: &ModernizerRule, template: &str) -> Vec<(Severity, String)> {
+    let is_primary = std::ptr::eq(template, rule.replacement_template.as_str());
+    if rule.ast_type == "TraitObject" {
+        return check_trait_object_template(rule, template);
+    }
+    match rule.action {
+        // 이름만 바꾸는 동작은 템플릿이 식이 아니라 이름
+        RuleAction::RenameMethod if is_primary => {
+            return match syn::parse_str::<Ident>(template) {
+                Ok(_) => Vec::new(),
+                Err(_) => vec![(Severity::Error, format!("`{}` is not a method name (action: rename_method)", template))],
+            };
+        }
+        RuleAction::RenameMacro if is_primary => {
+            return match syn::parse_str::<Path>(template) {
+                Ok(_) => Vec::new(),
+                Err(_) => vec![(Severity::Error, format!("`{}` is not a macro path (action: rename_macro)", template))],
+            };
+        }
+        RuleAction::ReorderDerive if is_primary => return Vec::new(),
+        RuleAction::InlineFormatArgs if rule.ast_type != "ExprMacro" => {
+            return vec![(Severity::Error, "action inline_format_args is only supported for ExprMacro".to_string())];
+        }
+        RuleAction::InlineFormatArgs if is_primary => return Vec::new(),
+        RuleAction::FuturesToAsync if rule.ast_type != "ItemFn" => {
+            return vec![(Severity::Error, "action futures_to_async is only supported for ItemFn".to_string())];
+        }
+        RuleAction::FuturesToAsync if is_primary => return Vec::new(),
+        _ if rule.ast_type == "ItemFn" => {
+            return vec![(Severity::Error, "only the futures_to_async action is supported for ItemFn".to_string())];
+        }
+        RuleAction::RemoveAsyncTrait if rule.ast_type != "ItemTrait" => {
+            return vec![(Severity::Error, "action remove_async_trait is only supported for ItemTrait".to_string())];
+        }
+        RuleAction::RemoveAsyncTrait if is_primary => return Vec::new(),
+        _ if rule.ast_type == "ItemTrait" => {
+            return vec![(Severity::Error, "only the remove_async_trait action is supported for ItemTrait".to_string())];
+        }
+        RuleAction::ImportMacros if rule.ast_type != "ItemExternCrate" => {
+            return vec![(Severity::Error, "action import_macros is only supported for ItemExternCrate".to_string())];
+        }
+        RuleAction::ThiserrorDerive if rule.ast_type != "ItemDerive" => {
+            return vec![(Severity::Error, "action thiserror_derive is only supported for ItemDerive".to_string())];
+        }
+        RuleAction::ErrorChainToThiserror if rule.ast_type != "ItemMacro" => {
+            return vec![(Severity::Error, "action error_chain_to_thiserror is only supported for ItemMacro".to_string())];
+        }
+        // 템플릿은 `...`을 대신할 범위 연산자
+        _ if rule.ast_type == "PatRange" && is_primary => {
+            if rule.action != RuleAction::Replace {
+                return vec![(Severity::Error, "only the replace action is supported for PatRange".to_string())];
+            }
+            return match syn::parse_str::<RangeLimits>(template.trim()) {
+                Ok(_) => Vec::new(),
+                Err(_) => vec![(Severity::Error, format!("`{}` is not a range operator (PatRange rules)", template))],
+            };
+        }
+        // 템플릿은 새 경로 (Path 규칙의 빈 템플릿은 바꾸지 않고 보고만 함, error_chain_to_thiserror는 derive 경로)
+        _ if matches!(rule.ast_type.as_str(), "ItemDerive" | "Path") || rule.action == RuleAction::ErrorChainToThiserror => {
+            return match template.trim() {
+                "" if rule.ast_type == "Path" && is_primary => Vec::new(),
+                path => match syn::parse_str::<Path>(path) {
+                    Ok(_) => Vec::new(),
+                    Err(_) => vec![(Severity::Error, format!("`{}` is not a path ({} rules)", path, rule.ast_type))],
+                },
+            };
+        }
+        _ if rule.ast_type == "Attribute" => return Vec::new(),
+        // 모듈 경로를 붙이고 `use`로 들여올 맨 앞 경로가 있어야 함
+        _ if rule.ast_type == "ExprPath" && !syn::parse_str::<Expr>(template.trim()).is_ok_and(|mut expr| head_path(&mut expr).is_some()) => {
+            return vec![(Severity::Error, format!("`{}` does not start with a path (ExprPath rules)", template))];
+        }
+        _ => {}
+    }
+
+    let pattern = pattern_of(rule);
+    let mut captures = Captures::new();
+    let mut missing = Vec::new();
+    for name in template::placeholder_names(template) {
+        if !provides_placeholder(rule, pattern.as_ref(), &name) {
+            missing.push(name);
+            continue;
+        }
+        let dummy: Expr = syn::parse_quote!(placeholder);
+        let capture = if (name == "method" && rule.ast_type != "Pattern")
+            || rule.ast_type == "ItemExternCrate"
+            || (is_item_rule(rule) && name == "name")
+        {
+            Capture::Ident(syn::parse_quote!(placeholder))
+        } else if is_item_rule(rule) && matches!(name.as_str(), "ty" | "inner_ty" | "self_ty") {
+            Capture::Type(syn::parse_quote!(Placeholder))
+        } else if name.ends_with("args") || pattern.as_ref().is_some_and(|pattern| pattern.is_spread(&name)) {
+            Capture::List(vec![dummy])
+        } else {
+            Capture::Expr(Box::new(dummy))
+        };
+        captures.insert(name, capture);
+    }
+    if !missing.is_empty() {
+        let missing: Vec<String> = missing.iter().map(|name| format!("#{}", name)).collect();
+        return vec![(Severity::Error, format!(
+            "placeholder {} is not provided by {} rules", missing.join(", "), rule.ast_type
+        ))];
+    }
+
+    if is_item_rule(rule) {
+        // 빈 템플릿은 선언을 지움
+        return match template.trim() {
+            "" => Vec::new(),
+            template => match template::expand_item(template, &captures) {
+                Ok(_) => Vec::new(),
+                Err(err) => vec![(Severity::Error, format!("does not parse as an item: {:#}", err))],
+            },
+        };
+    }
+    captures.insert("original".to_string(), Capture::Expr(Box::new(syn::parse_quote!(placeholder))));
+    match template::expand(&template::fill_original_in_strings(template, "placeholder"), &captures) {
+        Ok(_) => Vec::new(),
+        Err(err) => vec![(Severity::Error, format!("does not parse as an expression: {:#}", err))],
+    }
+}
+
+/// TraitObject 규칙의 템플릿은 `#bounds`(쓰인 그대로의 트레이트 경계)를 넣어 타입으로 전개됩니다.
+fn check_trait_object_template(rule: &ModernizerRule, template: &str) -> Vec<(Severity, String)> {
+    if rule.action != RuleAction::Replace {
+        return vec![(Severity::Error, "only the replace action is supported for TraitObject".to_string())];
+    }
+    let missing: Vec<String> = template::placeholder_names(template).into_iter().filter(|name| name != "bounds").map(|name| format!("#{}", name)).collect();
+    if !missing.is_empty() {
+        return vec![(Severity::Error, format!("placeholder {} is not provided by TraitObject rules", missing.join(", ")))];
+    }
+    let sources = HashMap::from([("bounds".to_string(), "Placeholder".to_string())]);
+    match template::fill_source(template, &sources).map(|ty| syn::parse_str::<Type>(&ty)) {
+        Some(Ok(_)) => Vec::new(),
+        _ => vec![(Severity::Error, format!("`{}` does not parse as a type", template))],
+    }
+}
+
+/// 템플릿이 식이 아니라 아이템으로 전개되는 규칙인지 여부
+fn is_item_rule(rule: &ModernizerRule) -> bool {
+    matches!(rule.ast_type.as_str(), "ItemExternCrate" | "ItemMacro" | "ItemStatic" | "ImplItemFn")
+}
+
+/// 규칙의 ast_type이 템플릿 자리표시자 `name`을 바인딩하는지 여부 (lib.rs의 각 transform_* 참고)
+fn provides_placeholder(rule: &ModernizerRule, pattern: Option<&Pattern>, name: &str) -> bool {
+    // `args`, `arg0`, `arg1`, ... (접두사 포함)
+    let is_args = |name: &str| {
+        name == "args" || name.strip_prefix("arg").is_some_and(|index| !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()))
+    };
+    // 수신자 체인의 호출 인자: `recv_args`, `recv_recv_arg0`, ...
+    let is_receiver_args = |mut name: &str| {
+        let mut depth = 0;
+        while let Some(rest) = name.strip_prefix("recv_") {
+            name = rest;
+            depth += 1;
+        }
+        depth > 0 && is_args(name)
+    };
+
+    match rule.ast_type.as_str() {
+        "ItemExternCrate" if rule.action == RuleAction::ImportMacros => return matches!(name, "crate" | "macro"),
+        "ItemExternCrate" => return matches!(name, "crate" | "rename"),
+        "ItemMacro" => return matches!(name, "name" | "ty" | "init"),
+        "ItemStatic" => return matches!(name, "name" | "ty" | "inner_ty" | "init" | "func") || is_args(name),
+        "ImplItemFn" => return matches!(name, "self_ty" | "body"),
+        _ => {}
+    }
+    name == "original"
+        || match rule.ast_type.as_str() {
+            "ExprMethodCall" => matches!(name, "receiver" | "inner_receiver" | "method") || is_args(name) || is_receiver_args(name),
+            "ExprCall" => name == "func" || is_args(name),
+            "ExprMacro" => is_args(name),
+            "ExprTry" => matches!(name, "inner" | "try" | "func"),
+            "ExprBinary" => matches!(name, "left" | "right" | "receiver" | "method") || is_args(name),
+            "ExprReturn" => matches!(name, "value" | "func") || is_args(name),
+            "ExprMatch" => name == "scrutinee",
+            "Pattern" => pattern.is_some_and(|pattern| pattern.names().iter().any(|bound| bound == name)),
+            _ => false,
+        }
+}
+
+/// `earlier`가 `later`가 매칭하는 모든 노드에 먼저 적용되는지 여부
+/// (같은 대상을 매칭하고, earlier에 노드를 거를 조건이 하나도 없는 경우만 판정)
+fn shadows(earlier: &ModernizerRule, later: &ModernizerRule) -> bool {
+    // derive 정렬 규칙은 가려지지 않고 차례로 모두 적용됨
+    if !earlier.enabled || !later.enabled || earlier.ast_type != later.ast_type || later.ast_type == "Attribute" {
+        return false;
+    }
+    let same_target = match later.ast_type.as_str() {
+        "Pattern" => match (pattern_of(earlier), pattern_of(later)) {
+            (Some(earlier), Some(later)) => earlier == later,
+            _ => false,
+        },
+        _ => earlier.method_name == later.method_name,
+    };
+    let unconditional = earlier.args_count.is_none()
+        && earlier.match_args.is_empty()
+        && earlier.binary_ops.is_empty()
+        && earlier.nested_method.is_none()
+        && earlier.format_string.is_none()
+        && earlier.receiver_kind == ReceiverKind::Any
+        && earlier.test_behavior == TestBehavior::Apply
+        && (earlier.require_fn_returns.is_none() || earlier.fallback_template.is_some())
+        // 처리기는 대체 식을 만들지 않을 수 있고, 전개에 실패한 템플릿은 다음 규칙으로 넘어감
+        && earlier.handler.is_none()
+        && templates(earlier).all(|(_, template)| check_template(earlier, template).is_empty());
+    same_target && unconditional
+}
@@ -0,0 +1,118 @@
+/// ----------------------------------------------------
+/// WebAssembly 규칙 처리기 (`wasm` 기능)
+/// ----------------------------------------------------
+/// 플러그인 디렉터리의 `.wasm`(또는 텍스트 형식 `.wat`) 모듈을 wasmi 인터프리터로 실행합니다.
+/// 모듈은 `memory`와 `handle() -> i32`를 내보내고, 다음 호스트 함수(모듈 이름 `modernizer`)를 가져다 씁니다.
+///
+/// | 함수 | 설명 |
+/// |:--|:--|
+/// | `rule_len() -> i32`, `rule_read(ptr: i32)` | 규칙 ID (UTF-8)의 길이 / `ptr`에 복사 |
+/// | `node_len() -> i32`, `node_read(ptr: i32)` | 매칭된 식의 토큰 문자열 (`quote::ToTokens` 출력)의 길이 / `ptr`에 복사 |
+/// | `replace(ptr: i32, len: i32)` | `memory[ptr..ptr+len]`의 토큰 문자열을 대체 식으로 지정 |
+///
+/// `handle`이 0을 반환하면 `replace`로 지정한 식을 적용하고 (지정하지 않았으면 적용하지 않음), 그 외 값은 오류입니다.
+/// 무한 루프로 변환이 멈추지 않도록 호출마다 실행량(fuel)을 제한합니다.
+use crate::{handler::RuleHandler, ModernizerRule};
+use anyhow::{anyhow, bail, Context, Result};
+use quote::ToTokens;
+use std::{fs, path::Path};
+use syn::Expr;
+use wasmi::{Caller, Config, Engine, Extern, Linker, Module, Store};
+
+/// 처리기 호출 한 번에 허용하는 실행량 (대략 실행할 수 있는 명령어 수)
+const WASM_FUEL: u64 = 10_000_000;
+/// 호스트 함수를 가져오는 모듈 이름
+const HOST_MODULE: &str = "modernizer";
+
+pub(crate) struct WasmHandler {
+    engine: Engine,
+    module: Module,
+}
+
+/// `handle` 호출 하나의 호스트 상태
+struct HostState {
+    rule: String,
+    node: String,
+    replacement: Option<String>,
+}
+
+impl WasmHandler {
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path).with_context(|| format!("Failed to read wasm plugin: {}", path.display()))?;
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, &bytes)
+            .map_err(|err| anyhow!("{}", err))
+            .with_context(|| format!("Failed to compile wasm plugin: {}", path.display()))?;
+        Ok(WasmHandler { engine, module })
+    }
+
+    fn linker(&self) -> Result<Linker<HostState>> {
+        let mut linker = Linker::new(&self.engine);
+        linker
+            .func_wrap(HOST_MODULE, "rule_len", |caller: Caller<'_, HostState>| caller.data().rule.len() as i32)
+            .and_then(|linker| {
+                linker.func_wrap(HOST_MODULE, "rule_read", |mut caller: Caller<'_, HostState>, ptr: i32| {
+                    let rule = caller.data().rule.clone();
+                    write_memory(&mut caller, ptr, rule.as_bytes())
+                })
+            })
+            .and_then(|linker| {
+                linker.func_wrap(HOST_MODULE, "node_len", |caller: Caller<'_, HostState>| caller.data().node.len() as i32)
+            })
+            .and_then(|linker| {
+                linker.func_wrap(HOST_MODULE, "node_read", |mut caller: Caller<'_, HostState>, ptr: i32| {
+                    let node = caller.data().node.clone();
+                    write_memory(&mut caller, ptr, node.as_bytes())
+                })
+            })
+            .and_then(|linker| {
+                linker.func_wrap(HOST_MODULE, "replace", |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+                    let mut buffer = vec![0; usize::try_from(len).map_err(|_| wasmi::Error::new("negative length"))?];
+                    memory(&caller)?
+                        .read(&caller, ptr as u32 as usize, &mut buffer)
+                        .map_err(|err| wasmi::Error::new(err.to_string()))?;
+                    let replacement = String::from_utf8(buffer).map_err(|_| wasmi::Error::new("replacement is not valid UTF-8"))?;
+                    caller.data_mut().replacement = Some(replacement);
+                    Ok(())
+                })
+            })
+            .map_err(|err| anyhow!("{}", err))?;
+        Ok(linker)
+    }
+}
+
+fn memory(caller: &Caller<'_, HostState>) -> Result<wasmi::Memory, wasmi::Error> {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| wasmi::Error::new("plugin does not export `memory`"))
+}
+
+fn write_memory(caller: &mut Caller<'_, HostState>, ptr: i32, bytes: &[u8]) -> Result<(), wasmi::Error> {
+    memory(caller)?
+        .write(caller, ptr as u32 as usize, bytes)
+        .map_err(|err| wasmi::Error::new(err.to_string()))
+}
+
+impl RuleHandler for WasmHandler {
+    fn replace(&self, rule: &ModernizerRule, node: &Expr) -> Result<Option<Expr>> {
+        let state = HostState { rule: rule.id.clone(), node: node.to_token_stream().to_string(), replacement: None };
+        let mut store = Store::new(&self.engine, state);
+        store.set_fuel(WASM_FUEL).map_err(|err| anyhow!("{}", err))?;
+        let instance = self.linker()?.instantiate_and_start(&mut store, &self.module).map_err(|err| anyhow!("{}", err))?;
+        let handle = instance
+            .get_typed_func::<(), i32>(&store, "handle")
+            .map_err(|err| anyhow!("plugin does not export `handle() -> i32`: {}", err))?;
+        let status = handle.call(&mut store, ()).map_err(|err| anyhow!("{}", err))?;
+        if status != 0 {
+            bail!("wasm plugin returned status {}", status);
+        }
+
+        let Some(replacement) = store.into_data().replacement else { return Ok(None) };
+        syn::parse_str(&replacement)
+            .map(Some)
+            .with_context(|| format!("wasm plugin returned an invalid expression: {}", replacement.trim()))
+    }
+}
@@ -0,0 +1,60 @@
+//! 문장/아이템 단위 변환이 남기는 검토 안내 주석(`TransformOptions::annotations`, 규칙의 `emit_comment`)을 확인합니다.
+use modernizer_core::{default_rules, AnnotationStyle, Modernizer, ModernizerRule, TransformOptions};
+
+const SOURCE: &str = "\
+fn f() {
+    let mut x: Foo = unsafe { std::mem::uninitialized() };
+    init(&mut x);
+    use_foo(x);
+}
+";
+
+/// `--scoped-maybeuninit` 변환으로 SOURCE를 바꾼 코드를 반환합니다.
+fn modernize(annotations: Option<AnnotationStyle>, edit: impl Fn(&mut ModernizerRule)) -> String {
+    let mut rules = default_rules().unwrap();
+    rules.iter_mut().for_each(edit);
+    let options = TransformOptions { scoped_maybeuninit: true, annotations, ..TransformOptions::default() };
+    let result = Modernizer::new(rules).with_options(options).modernize_str(SOURCE).unwrap();
+    let code = result.code.expect("rule did not apply");
+    assert!(code.contains("let mut x = std::mem::MaybeUninit::<Foo>::uninit();"), "{}", code);
+    code
+}
+
+#[test]
+fn verbose_style_links_the_rule_docs() {
+    let code = modernize(Some(AnnotationStyle::Verbose), |_| {});
+    assert!(code.contains("/// MODERNIZER REVIEW ("), "{}", code);
+    assert!(code.contains("`x` is now a MaybeUninit."), "{}", code);
+    assert!(code.contains("Ref: https://"), "{}", code);
+}
+
+#[test]
+fn compact_style_is_a_single_short_line() {
+    let code = modernize(Some(AnnotationStyle::Compact), |_| {});
+    let review: Vec<&str> = code.lines().filter(|line| line.contains("/// ")).collect();
+    assert_eq!(review.len(), 1, "{}", code);
+    assert!(review[0].trim_start().starts_with("/// REVIEW("), "{}", code);
+    assert!(review[0].ends_with("verify `x` is fully initialized before `assume_init()`"), "{}", code);
+}
+
+#[test]
+fn no_annotations_leaves_no_comment() {
+    let code = modernize(None, |_| {});
+    assert!(!code.contains("///"), "{}", code);
+}
+
+#[test]
+fn emit_comment_false_leaves_no_comment() {
+    let code = modernize(Some(AnnotationStyle::Verbose), |rule| rule.emit_comment = false);
+    assert!(!code.contains("///"), "{}", code);
+}
+
+#[test]
+fn expression_rules_leave_no_comment() {
+    // 식 단위 규칙은 주석 없이 바꾸고 검토 메모는 보고서에만 남김
+    let source = "fn f() -> Result<u32, String> {\n    let x = try!(g());\n    Ok(x)\n}\n";
+    let result = Modernizer::new(default_rules().unwrap()).modernize_str(source).unwrap();
+    let code = result.code.expect("rule did not apply");
+    assert!(code.contains("let x = g()?;"), "{}", code);
+    assert!(!code.contains("//"), "{}", code);
+}
@@ -0,0 +1,51 @@
+//! `match_args`의 `{ "call": [...] }`(ArgMatcher::Call) 매칭을 or_insert_new_to_or_default 규칙으로 확인합니다.
+use modernizer_core::{default_rules, Modernizer, TransformOptions};
+
+const RULE_ID: &str = "or_insert_new_to_or_default";
+
+/// 규칙 하나만으로 `body`를 함수 본문에 넣어 변환하고, 바뀐 코드(바뀌지 않았으면 None)를 반환합니다.
+fn modernize(body: &str) -> Option<String> {
+    let rules = default_rules().unwrap().into_iter().filter(|rule| rule.id == RULE_ID).collect::<Vec<_>>();
+    assert_eq!(rules.len(), 1, "built-in rule `{}` is missing", RULE_ID);
+    let options = TransformOptions { annotations: None, ..TransformOptions::default() };
+    let source = format!("fn f(map: &mut Map, k: u32) {{\n    {}\n}}\n", body);
+    let result = Modernizer::new(rules).with_options(options).modernize_str(&source).unwrap();
+    result.code
+}
+
+#[test]
+fn entry_or_insert_new_becomes_or_default() {
+    let code = modernize("map.entry(k).or_insert(Vec::new()).push(1);").expect("rule did not apply");
+    assert!(code.contains("map.entry(k).or_default().push(1);"), "{}", code);
+}
+
+#[test]
+fn turbofish_call_matches() {
+    let code = modernize("map.entry(k).or_insert(Vec::<u8>::new());").expect("rule did not apply");
+    assert!(code.contains("map.entry(k).or_default();"), "{}", code);
+}
+
+#[test]
+fn listed_paths_match_by_suffix() {
+    let code = modernize("map.entry(k).or_insert(std::collections::HashMap::new());").expect("rule did not apply");
+    assert!(code.contains("map.entry(k).or_default();"), "{}", code);
+}
+
+#[test]
+fn calls_with_arguments_do_not_match() {
+    assert_eq!(modernize("map.entry(k).or_insert(Vec::with_capacity(4));"), None);
+    assert_eq!(modernize("map.entry(k).or_insert(String::from(\"x\"));"), None);
+}
+
+#[test]
+fn other_calls_and_expressions_do_not_match() {
+    assert_eq!(modernize("map.entry(k).or_insert(make_vec());"), None);
+    assert_eq!(modernize("map.entry(k).or_insert(Vec::default());"), None);
+    assert_eq!(modernize("map.entry(k).or_insert(vec![]);"), None);
+    assert_eq!(modernize("map.entry(k).or_insert(0);"), None);
+}
+
+#[test]
+fn or_insert_without_entry_does_not_match() {
+    assert_eq!(modernize("cache.or_insert(Vec::new());"), None);
+}
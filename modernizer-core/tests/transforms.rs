@@ -0,0 +1,285 @@
+//! 내장 규칙의 변환 전/후를 `Modernizer::modernize_str`로 확인합니다.
+use modernizer_core::{default_rules, Modernizer, ModernizerRule, TransformOptions};
+
+/// 내장 규칙 중 `ids`만으로 `source`를 변환하고, 바뀐 코드(바뀌지 않았으면 None)를 반환합니다.
+fn apply(ids: &[&str], source: &str) -> Option<String> {
+    let rules: Vec<ModernizerRule> = default_rules().unwrap().into_iter().filter(|rule| ids.contains(&rule.id.as_str())).collect();
+    assert_eq!(rules.len(), ids.len(), "built-in rules {:?} are missing", ids);
+    modernize(rules, source)
+}
+
+fn modernize(rules: Vec<ModernizerRule>, source: &str) -> Option<String> {
+    let options = TransformOptions { annotations: None, ..TransformOptions::default() };
+    Modernizer::new(rules).with_options(options).modernize_str(source).unwrap().code
+}
+
+/// JSON 규칙 배열을 파싱합니다. (필수 필드 level_icon, doc_url은 채워 넣음)
+fn custom_rules(json: &str) -> Vec<ModernizerRule> {
+    let mut rules: Vec<serde_json::Value> = serde_json::from_str(json).unwrap();
+    for rule in &mut rules {
+        rule["level_icon"] = "✅".into();
+        rule["doc_url"] = "".into();
+    }
+    serde_json::from_value(rules.into()).unwrap()
+}
+
+/// `source`를 `ids` 규칙으로 변환한 결과에 `expected`가 모두 들어 있는지 확인합니다.
+#[track_caller]
+fn assert_converts(ids: &[&str], source: &str, expected: &[&str]) {
+    let code = apply(ids, source).unwrap_or_else(|| panic!("{:?} did not apply to:\n{}", ids, source));
+    for expected in expected {
+        assert!(code.contains(expected), "expected `{}` in:\n{}", expected, code);
+    }
+}
+
+#[track_caller]
+fn assert_unchanged(ids: &[&str], source: &str) {
+    if let Some(code) = apply(ids, source) {
+        panic!("{:?} unexpectedly changed:\n{}", ids, code);
+    }
+}
+
+#[test]
+fn try_macro_becomes_question_mark() {
+    assert_converts(
+        &["try_macro_to_question_mark"],
+        "fn f() -> Result<u32, E> {\n    let x = try!(g());\n    Ok(x)\n}\n",
+        &["let x = g()?;"],
+    );
+}
+
+#[test]
+fn unwrap_in_result_fn_becomes_question_mark() {
+    assert_converts(&["unwrap_to_try"], "fn f() -> Result<u32, E> {\n    let x = \"1\".parse::<u32>().unwrap();\n    Ok(x)\n}\n", &["let x = \"1\".parse::<u32>()?;"]);
+    // Result를 반환하지 않는 함수는 `?`를 쓸 수 없음
+    assert_unchanged(&["unwrap_to_try"], "fn f() -> u32 {\n    \"1\".parse::<u32>().unwrap()\n}\n");
+    // Option에 `?`를 쓰면 Result를 반환하는 함수에서 컴파일되지 않음
+    assert_unchanged(&["unwrap_to_try"], "fn f(v: &[u32]) -> Result<u32, E> {\n    Ok(*v.first().unwrap())\n}\n");
+}
+
+#[test]
+fn fallback_template_applies_outside_result_fns() {
+    let rules = custom_rules(
+        r##"[{ "id": "unwrap", "ast_type": "ExprMethodCall", "method_name": "unwrap", "args_count": 0, "require_fn_returns": "Result",
+              "replacement_template": "#receiver?", "fallback_template": "#receiver.expect(\"TODO: handle error\")" }]"##,
+    );
+    let code = modernize(rules.clone(), "fn f() -> Result<u32, E> {\n    Ok(g().unwrap())\n}\n").expect("rule did not apply");
+    assert!(code.contains("Ok(g()?)"), "{}", code);
+    let code = modernize(rules, "fn f() -> u32 {\n    g().unwrap()\n}\n").expect("rule did not apply");
+    assert!(code.contains("g().expect(\"TODO: handle error\")"), "{}", code);
+}
+
+#[test]
+fn unwrap_in_test_fn_becomes_expect() {
+    let source = "fn f() -> Result<u32, E> {\n    Ok(1)\n}\n\n#[test]\nfn t() {\n    let x = \"1\".parse::<u32>().unwrap();\n}\n";
+    let code = apply(&["unwrap_to_try"], source).expect("rule did not apply");
+    assert!(code.contains(".expect("), "{}", code);
+    assert!(!code.contains("?;"), "{}", code);
+    assert!(!code.contains(".unwrap()"), "{}", code);
+}
+
+#[test]
+fn unwrap_in_result_closure_becomes_question_mark() {
+    assert_converts(
+        &["unwrap_to_try"],
+        "fn f() {\n    let parse = |s: &str| -> Result<u32, E> { Ok(s.parse::<u32>().unwrap()) };\n}\n",
+        &["Ok(s.parse::<u32>()?)"],
+    );
+}
+
+#[test]
+fn iter_cloned_becomes_copied() {
+    assert_converts(&["iter_cloned_to_copied"], "fn f(v: &[u8]) -> Vec<u8> {\n    v.iter().cloned().collect()\n}\n", &["v.iter().copied().collect()"]);
+}
+
+#[test]
+fn format_of_single_arg_becomes_to_string() {
+    assert_converts(&["format_to_string"], "fn f(x: u32) -> String {\n    format!(\"{}\", x)\n}\n", &["x.to_string()"]);
+    assert_unchanged(&["format_to_string"], "fn f(x: u32) -> String {\n    format!(\"{} items\", x)\n}\n");
+}
+
+#[test]
+fn derive_list_is_reordered() {
+    assert_converts(&["derive_order"], "#[derive(Hash, Debug, Clone)]\nstruct S;\n", &["#[derive(Debug, Clone, Hash)]"]);
+}
+
+#[test]
+fn len_comparisons_become_is_empty() {
+    let ids = ["len_zero_to_is_empty", "len_nonzero_to_not_is_empty"];
+    assert_converts(&ids, "fn f(v: &[u8]) -> bool {\n    v.len() == 0\n}\n", &["v.is_empty()"]);
+    assert_converts(&ids, "fn f(v: &[u8]) -> bool {\n    v.len() > 0\n}\n", &["!v.is_empty()"]);
+    assert_unchanged(&ids, "fn f(v: &[u8]) -> bool {\n    v.len() > 1\n}\n");
+}
+
+#[test]
+fn redundant_ok_try_is_unwrapped() {
+    assert_converts(&["redundant_ok_try"], "fn f() -> Result<u32, E> {\n    Ok(g()?)\n}\n", &["    g()\n"]);
+}
+
+#[test]
+fn tail_return_ok_becomes_expression() {
+    assert_converts(
+        &["tail_return_ok"],
+        "fn f() -> Result<(), E> {\n    g();\n    return Ok(());\n}\n",
+        &["    g();\n    Ok(())\n}"],
+    );
+}
+
+#[test]
+fn match_err_return_becomes_question_mark() {
+    assert_converts(
+        &["match_err_return_to_try"],
+        "fn f() -> Result<u32, E> {\n    let x = match g() {\n        Ok(v) => v,\n        Err(e) => return Err(e),\n    };\n    Ok(x)\n}\n",
+        &["let x = g()?;"],
+    );
+}
+
+#[test]
+fn println_is_renamed_to_tracing() {
+    assert_converts(&["println_to_tracing_info"], "fn f(x: u32) {\n    println!(\"x = {}\", x);\n}\n", &["tracing::info!(\"x = {}\", x);"]);
+}
+
+#[test]
+fn extern_crate_is_removed_or_aliased() {
+    assert_converts(&["extern_crate_removal"], "extern crate serde;\n\nfn f() {}\n", &["fn f() {}"]);
+    assert_converts(&["extern_crate_alias_to_use"], "extern crate serde_json as json;\n\nfn f() {}\n", &["use serde_json as json;"]);
+}
+
+#[test]
+fn macro_use_becomes_explicit_imports() {
+    assert_converts(
+        &["macro_use_to_use"],
+        "#[macro_use]\nextern crate log;\n\nfn f() {\n    info!(\"hi\");\n}\n",
+        &["use log::info;"],
+    );
+}
+
+#[test]
+fn lazy_static_becomes_lazy_lock() {
+    assert_converts(
+        &["lazy_static_to_lazy_lock"],
+        "lazy_static! {\n    static ref NAMES: Vec<String> = load();\n}\n",
+        &["static NAMES: std::sync::LazyLock<Vec<String>> = std::sync::LazyLock::new(|| load());"],
+    );
+}
+
+#[test]
+fn once_cell_becomes_std() {
+    assert_converts(
+        &["once_cell_lazy_to_lazy_lock"],
+        "static NAMES: once_cell::sync::Lazy<Vec<String>> = once_cell::sync::Lazy::new(load);\n",
+        &["std::sync::LazyLock<Vec<String>>"],
+    );
+}
+
+#[test]
+fn failure_paths_become_anyhow() {
+    assert_converts(
+        &["failure_error_to_anyhow", "failure_bail_to_anyhow"],
+        "fn f() -> Result<(), failure::Error> {\n    failure::bail!(\"no\");\n}\n",
+        &["Result<(), anyhow::Error>", "anyhow::bail!(\"no\");"],
+    );
+}
+
+#[test]
+fn fail_derive_becomes_thiserror() {
+    assert_converts(
+        &["fail_derive_to_thiserror"],
+        "#[derive(Debug, Fail)]\nenum E {\n    #[fail(display = \"io: {}\", _0)]\n    Io(#[cause] std::io::Error),\n}\n",
+        &["#[derive(Debug, thiserror::Error)]", "#[error(\"io: {}\", .0)]", "Io(#[source] std::io::Error)"],
+    );
+}
+
+#[test]
+fn error_chain_becomes_thiserror() {
+    assert_converts(
+        &["error_chain_to_thiserror"],
+        "error_chain! {\n    errors {\n        Parse(line: usize) {\n            description(\"parse error\")\n            display(\"parse error at {}\", line)\n        }\n    }\n}\n",
+        &["thiserror::Error"],
+    );
+}
+
+#[test]
+fn error_description_becomes_to_string() {
+    assert_converts(&["error_description_to_string"], "fn f(e: &dyn std::error::Error) -> String {\n    e.description().to_string()\n}\n", &["e.to_string()"]);
+}
+
+#[test]
+fn bare_trait_object_gets_dyn() {
+    assert_converts(&["bare_trait_object_to_dyn"], "fn f(x: Box<Fn(u8) -> u8>) {}\n", &["Box<dyn Fn(u8) -> u8>"]);
+    assert_converts(&["bare_trait_object_to_dyn"], "trait Shape {}\n\nfn f(x: &Shape) {}\n", &["fn f(x: &dyn Shape) {}"]);
+}
+
+#[test]
+fn mem_replace_default_becomes_take() {
+    assert_converts(
+        &["mem_replace_default_to_take"],
+        "fn f(v: &mut Vec<u8>) -> Vec<u8> {\n    std::mem::replace(v, Default::default())\n}\n",
+        &["std::mem::take(v)"],
+    );
+}
+
+#[test]
+fn dotted_range_pattern_becomes_inclusive() {
+    assert_converts(&["range_pattern_dots_to_inclusive"], "fn f(x: u8) -> bool {\n    match x {\n        0...9 => true,\n        _ => false,\n    }\n}\n", &["0..=9 => true"]);
+}
+
+#[test]
+fn std_renames_apply() {
+    let rules: Vec<ModernizerRule> = default_rules().unwrap().into_iter().filter(|rule| rule.id.starts_with("std_rename_")).collect();
+    assert!(!rules.is_empty());
+    let code = modernize(rules, "fn f(s: &str) -> &str {\n    s.trim_left()\n}\n").expect("rule did not apply");
+    assert!(code.contains("s.trim_start()"), "{}", code);
+}
+
+#[test]
+fn format_args_are_inlined() {
+    assert_converts(&["inline_format_args_println"], "fn f(x: u32) {\n    println!(\"x = {}\", x);\n}\n", &["println!(\"x = {x}\");"]);
+    // 식 인자는 인라인할 수 없음
+    assert_unchanged(&["inline_format_args_println"], "fn f(x: u32) {\n    println!(\"x = {}\", x + 1);\n}\n");
+}
+
+#[test]
+fn atomic_initializers_become_new() {
+    assert_converts(
+        &["atomic_bool_init_to_new", "once_init_to_new"],
+        "static FLAG: AtomicBool = std::sync::atomic::ATOMIC_BOOL_INIT;\nstatic ONCE: Once = std::sync::ONCE_INIT;\n",
+        &["AtomicBool::new(false)", "Once::new()"],
+    );
+}
+
+#[test]
+fn futures_combinators_become_async_fn() {
+    assert_converts(
+        &["futures01_to_async_fn"],
+        "fn fetch(c: Client) -> impl Future<Item = u32, Error = E> {\n    c.get().and_then(|body| parse(body))\n}\n",
+        &["async fn fetch(c: Client) -> Result<u32, E>"],
+    );
+}
+
+#[test]
+fn async_trait_attribute_is_removed() {
+    let source = "use async_trait::async_trait;\n\n#[async_trait]\ntrait Store {\n    async fn get(&self) -> u32;\n}\n\n#[async_trait]\nimpl Store for Mem {\n    async fn get(&self) -> u32 {\n        1\n    }\n}\n";
+    let code = apply(&["async_trait_to_native"], source).expect("rule did not apply");
+    assert!(!code.contains("async_trait"), "{}", code);
+    assert!(code.contains("trait Store {\n    async fn get(&self) -> u32;"), "{}", code);
+}
+
+#[test]
+fn pattern_rules_bind_metavariables() {
+    let rules = custom_rules(
+        r##"[{ "id": "ok_unwrap", "ast_type": "Pattern", "method_name": "", "pattern": "$x.ok().unwrap()", "replacement_template": "#x.unwrap()" }]"##,
+    );
+    let code = modernize(rules, "fn f() -> u32 {\n    g().ok().unwrap()\n}\n").expect("rule did not apply");
+    assert!(code.contains("    g().unwrap()\n"), "{}", code);
+}
+
+#[test]
+fn arg_value_selects_the_template() {
+    let rules = custom_rules(
+        r#"[{ "id": "sleep_zero", "ast_type": "ExprCall", "method_name": "thread::sleep_ms", "args_count": 1, "match_args": [{ "literal": 0 }], "replacement_template": "std::thread::yield_now()" }]"#,
+    );
+    let code = modernize(rules.clone(), "fn f() {\n    std::thread::sleep_ms(0);\n}\n").expect("rule did not apply");
+    assert!(code.contains("std::thread::yield_now();"), "{}", code);
+    assert_eq!(modernize(rules, "fn f() {\n    std::thread::sleep_ms(5);\n}\n"), None);
+}
@@ -0,0 +1,39 @@
+/// ----------------------------------------------------
+/// `cargo modernize` 서브커맨드
+/// ----------------------------------------------------
+/// cargo는 `cargo modernize [ARGS]`를 `cargo-modernize modernize [ARGS]`로 실행합니다.
+/// 같은 디렉터리에 설치된 rust_modernizer를 `[ARGS]`로 실행하며, INPUT을 생략하면
+/// 현재 디렉터리가 속한 패키지의 소스(`cargo metadata`의 타깃)를 변환합니다.
+use std::{
+    env,
+    ffi::OsString,
+    path::PathBuf,
+    process::{Command, ExitCode},
+};
+
+/// rust_modernizer에 cargo 서브커맨드로 실행되었음을 알리는 환경 변수
+const CARGO_SUBCOMMAND_ENV: &str = "RUST_MODERNIZER_CARGO_SUBCOMMAND";
+
+fn main() -> ExitCode {
+    let mut args: Vec<OsString> = env::args_os().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "modernize") {
+        args.remove(0);
+    }
+
+    // `cargo install`은 두 바이너리를 같은 디렉터리에 설치하므로 형제 바이너리를 우선 사용하고, 없으면 PATH에서 찾음
+    let binary = format!("rust_modernizer{}", env::consts::EXE_SUFFIX);
+    let program = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(&binary)))
+        .filter(|path| path.is_file())
+        .unwrap_or_else(|| PathBuf::from(&binary));
+
+    match Command::new(&program).args(&args).env(CARGO_SUBCOMMAND_ENV, "1").status() {
+        // 시그널로 종료되어 코드가 없으면 기타 오류(1)
+        Ok(status) => ExitCode::from(status.code().and_then(|code| u8::try_from(code).ok()).unwrap_or(1)),
+        Err(err) => {
+            eprintln!("Error: Failed to run {}: {}", program.display(), err);
+            ExitCode::from(1)
+        }
+    }
+}
@@ -0,0 +1,183 @@
+/// ----------------------------------------------------
+/// 변환 전후 코드의 unified diff (--diff-file, --patch)
+/// ----------------------------------------------------
+/// 줄 단위 Myers 차분입니다. 공통 앞/뒤 줄을 먼저 잘라낸 뒤 차분하며, 편집 거리가
+/// MAX_EDIT_DISTANCE를 넘으면 (파일 전체가 재정렬된 경우 등) 남은 구간을 하나의 교체 hunk로 출력합니다.
+use std::fmt::Write;
+
+/// hunk 앞뒤에 붙이는 변경되지 않은 줄 수 (`diff -u` 기본값)
+const CONTEXT_LINES: usize = 3;
+/// Myers 탐색을 포기하고 구간 전체를 교체로 취급하는 편집 거리 (추적 메모리는 거리의 제곱에 비례)
+const MAX_EDIT_DISTANCE: usize = 2000;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    Keep,
+    Delete,
+    Insert,
+}
+
+/// `old` → `new`의 unified diff hunk(`@@ ... @@`부터)를 만듭니다. 차이가 없으면 빈 문자열을 반환합니다.
+/// 파일 머리말(`---`/`+++`)은 출력 형식에 따라 호출하는 쪽에서 붙입니다.
+pub fn hunks(old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let edits = diff_lines(&old_lines, &new_lines);
+    if edits.iter().all(|edit| *edit == Edit::Keep) {
+        return String::new();
+    }
+
+    // 편집 목록의 각 위치에 대응하는 (원본 줄 번호, 새 줄 번호)
+    let mut positions = Vec::with_capacity(edits.len() + 1);
+    let (mut old_index, mut new_index) = (0, 0);
+    for edit in &edits {
+        positions.push((old_index, new_index));
+        match edit {
+            Edit::Keep => {
+                old_index += 1;
+                new_index += 1;
+            }
+            Edit::Delete => old_index += 1,
+            Edit::Insert => new_index += 1,
+        }
+    }
+
+    let mut output = String::new();
+    let changes: Vec<usize> = (0..edits.len()).filter(|&index| edits[index] != Edit::Keep).collect();
+    let mut next = 0;
+    while next < changes.len() {
+        // 사이의 변경되지 않은 줄이 context 두 배 이하인 변경은 같은 hunk로 묶음
+        let first = changes[next];
+        let mut last = first;
+        next += 1;
+        while next < changes.len() && changes[next] - last <= 2 * CONTEXT_LINES + 1 {
+            last = changes[next];
+            next += 1;
+        }
+        let start = first.saturating_sub(CONTEXT_LINES);
+        let end = (last + CONTEXT_LINES + 1).min(edits.len());
+        write_hunk(&mut output, &edits[start..end], positions[start], &old_lines, &new_lines);
+    }
+    output
+}
+
+/// hunk 하나를 출력합니다. (`start`: hunk 첫 줄의 원본/새 줄 번호, 0부터)
+fn write_hunk(output: &mut String, edits: &[Edit], start: (usize, usize), old_lines: &[&str], new_lines: &[&str]) {
+    let old_count = edits.iter().filter(|edit| **edit != Edit::Insert).count();
+    let new_count = edits.iter().filter(|edit| **edit != Edit::Delete).count();
+    // 줄 수가 0인 범위는 그 앞 줄 번호로 표시 (`diff -u` 관례)
+    let line_number = |index: usize, count: usize| if count == 0 { index } else { index + 1 };
+    let _ = writeln!(
+        output,
+        "@@ -{},{} +{},{} @@",
+        line_number(start.0, old_count), old_count, line_number(start.1, new_count), new_count
+    );
+
+    let (mut old_index, mut new_index) = start;
+    for edit in edits {
+        let (prefix, line) = match edit {
+            Edit::Keep => {
+                old_index += 1;
+                new_index += 1;
+                (' ', old_lines[old_index - 1])
+            }
+            Edit::Delete => {
+                old_index += 1;
+                ('-', old_lines[old_index - 1])
+            }
+            Edit::Insert => {
+                new_index += 1;
+                ('+', new_lines[new_index - 1])
+            }
+        };
+        output.push(prefix);
+        output.push_str(line);
+        if !line.ends_with('\n') {
+            output.push_str("\n\\ No newline at end of file\n");
+        }
+    }
+}
+
+/// 줄 목록의 편집 순서를 구합니다.
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut edits = vec![Edit::Keep; prefix];
+    match myers(old_middle, new_middle) {
+        Some(middle) => edits.extend(middle),
+        None => {
+            edits.extend(std::iter::repeat_n(Edit::Delete, old_middle.len()));
+            edits.extend(std::iter::repeat_n(Edit::Insert, new_middle.len()));
+        }
+    }
+    edits.extend(std::iter::repeat_n(Edit::Keep, suffix));
+    edits
+}
+
+/// Myers 최단 편집 경로. 편집 거리가 MAX_EDIT_DISTANCE를 넘으면 None을 반환합니다.
+fn myers(old: &[&str], new: &[&str]) -> Option<Vec<Edit>> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max = old.len() + new.len();
+    let offset = max as isize + 1;
+    let index = |k: isize| (k + offset) as usize;
+
+    // v[k]: 대각선 k에서 도달한 가장 먼 x. trace[d]는 d번째 단계 직전의 v[-d..=d]
+    let mut v = vec![0isize; 2 * max + 3];
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    for d in 0..=max.min(MAX_EDIT_DISTANCE) as isize {
+        trace.push(v[index(-d)..=index(d)].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+                v[index(k + 1)]
+            } else {
+                v[index(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[index(k)] = x;
+            if x >= n && y >= m {
+                return Some(backtrack(&trace, n, m));
+            }
+        }
+    }
+    None
+}
+
+/// 단계별 v 기록을 거꾸로 따라가며 편집 순서를 복원합니다.
+fn backtrack(trace: &[Vec<isize>], n: isize, m: isize) -> Vec<Edit> {
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        if d == 0 {
+            edits.extend(std::iter::repeat_n(Edit::Keep, x as usize));
+            break;
+        }
+        let get = |k: isize| v[(k + d) as usize];
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && get(k - 1) < get(k + 1)) { k + 1 } else { k - 1 };
+        let prev_x = get(prev_k);
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            edits.push(Edit::Keep);
+            x -= 1;
+            y -= 1;
+        }
+        edits.push(if x == prev_x { Edit::Insert } else { Edit::Delete });
+        x = prev_x;
+        y = prev_y;
+    }
+    edits.reverse();
+    edits
+}
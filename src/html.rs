@@ -0,0 +1,136 @@
+/// ----------------------------------------------------
+/// HTML 보고서 (--report-format html)
+/// ----------------------------------------------------
+/// 외부 리소스 없이 브라우저에서 바로 열 수 있는 단일 HTML 문서를 만듭니다.
+/// 규칙별 빈도는 CSS 막대로, 파일별 변경은 변경 전/후 코드 표로 출력합니다.
+use crate::Report;
+use modernizer_core::AppliedChange;
+use std::fmt::Write;
+
+const STYLE: &str = "\
+body{font-family:system-ui,sans-serif;margin:2rem;color:#222}\
+h1{font-size:1.5rem}h2{font-size:1.15rem;margin-top:2rem}\
+table{border-collapse:collapse;width:100%;margin-top:.5rem}\
+th,td{border:1px solid #ddd;padding:.35rem .5rem;text-align:left;vertical-align:top}\
+th{background:#f5f5f5}\
+code{font-family:ui-monospace,monospace;font-size:.85rem;white-space:pre-wrap;word-break:break-all}\
+.before{background:#fff0f0}.after{background:#f0fff0}\
+.bar{background:#4a7bd0;height:.9rem;display:inline-block;vertical-align:middle;margin-right:.4rem}\
+.risk-high{color:#b00020;font-weight:bold}.risk-medium{color:#b36b00}.note{color:#555;font-size:.85rem}";
+
+/// 보고서 전체를 HTML 문서로 출력합니다.
+pub fn render(report: &Report) -> String {
+    let mut html = String::new();
+    let changed_files = report.files.iter().filter(|file| !file.changes.is_empty()).count();
+    let total: u32 = report.counts.values().sum();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"ko\">\n<head>\n<meta charset=\"utf-8\">\n<title>Rust Modernizer Report</title>\n\
+         <style>{}</style>\n</head>\n<body>\n<h1>Rust Legacy → Modern Migration Report</h1>\n",
+        STYLE
+    );
+    let _ = writeln!(
+        html,
+        "<p>파일 {}개 중 {}개 변경{}, 변환 {}건</p>",
+        report.files.len(),
+        changed_files,
+        if report.dry_run { " 예정 (dry-run)" } else { "" },
+        total
+    );
+
+    if !report.skipped_rules.is_empty() {
+        let ids: Vec<&str> = report.skipped_rules.keys().map(String::as_str).collect();
+        let _ = writeln!(html, "<p>건너뛴 규칙 (--only/--skip): {}</p>", escape(&ids.join(", ")));
+    }
+
+    write_frequency_chart(&mut html, report, total);
+
+    for file in report.files.iter().filter(|file| !file.changes.is_empty()) {
+        let _ = writeln!(html, "<h2>{}</h2>", escape(&file.path.display().to_string()));
+        html.push_str("<table>\n<tr><th>위치</th><th>규칙</th><th>변경 전</th><th>변경 후</th></tr>\n");
+        for change in &file.changes {
+            write_change_row(&mut html, change);
+        }
+        html.push_str("</table>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// 규칙별 적용 건수를 가장 많은 규칙 대비 비율의 막대로 출력합니다.
+fn write_frequency_chart(html: &mut String, report: &Report, total: u32) {
+    if total == 0 {
+        html.push_str("<p>변환할 레거시 패턴이 없습니다.</p>\n");
+        return;
+    }
+    let max = report.counts.values().copied().max().unwrap_or(1).max(1);
+    let mut counts: Vec<(&String, &u32)> = report.counts.iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+    html.push_str("<h2>규칙별 빈도</h2>\n<table>\n<tr><th>규칙</th><th>건수</th></tr>\n");
+    for (id, count) in counts {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td><span class=\"bar\" style=\"width:{}px\"></span>{}</td></tr>",
+            rule_link(id, doc_url_for(report, id)),
+            count * 300 / max,
+            count
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+fn write_change_row(html: &mut String, change: &AppliedChange) {
+    let risk = change.risk.as_str();
+    let _ = write!(
+        html,
+        "<tr><td>{}:{}</td><td>{} <span class=\"risk-{}\">({})</span>",
+        change.location.line,
+        change.location.column,
+        rule_link(&change.rule_id, Some(&change.doc_url)),
+        risk,
+        risk
+    );
+    if let Some(note) = &change.review_note {
+        let _ = write!(html, "<div class=\"note\">{}</div>", escape(note));
+    }
+    let after = change.replacement.as_deref().unwrap_or("(문장 단위 변환)");
+    let _ = writeln!(
+        html,
+        "</td><td class=\"before\"><code>{}</code></td><td class=\"after\"><code>{}</code></td></tr>",
+        escape(&change.original),
+        escape(after)
+    );
+}
+
+/// 규칙 ID를 doc_url 링크로 출력합니다. (doc_url이 없으면 ID만)
+fn rule_link(id: &str, doc_url: Option<&str>) -> String {
+    match doc_url.filter(|url| !url.is_empty()) {
+        Some(url) => format!("<a href=\"{}\">{}</a>", escape(url), escape(id)),
+        None => escape(id),
+    }
+}
+
+fn doc_url_for<'a>(report: &'a Report, id: &str) -> Option<&'a str> {
+    report
+        .files
+        .iter()
+        .flat_map(|file| &file.changes)
+        .find(|change| change.rule_id == id)
+        .map(|change| change.doc_url.as_str())
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
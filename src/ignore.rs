@@ -0,0 +1,117 @@
+/// ----------------------------------------------------
+/// 제외 패턴 (.modernizerignore, --exclude)
+/// ----------------------------------------------------
+/// gitignore 형식의 패턴입니다. `/`가 없는 패턴은 어느 깊이에서나, `/`가 있는 패턴은 기준 디렉터리에서부터 일치하며,
+/// `/`로 끝나면 디렉터리에만, `!`로 시작하면 앞서 제외한 경로를 다시 포함합니다. 마지막으로 일치한 패턴이 이깁니다.
+use anyhow::{Context, Result};
+use globset::{GlobBuilder, GlobMatcher};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// 입력 디렉터리(와 상위 디렉터리)에서 찾는 제외 파일 이름
+pub const IGNORE_FILE: &str = ".modernizerignore";
+
+struct IgnorePattern {
+    /// 경로 자체와 일치
+    matcher: GlobMatcher,
+    /// 일치한 디렉터리 아래의 모든 경로와 일치 (`pattern/**`)
+    contents: GlobMatcher,
+    negate: bool,
+    dir_only: bool,
+}
+
+/// 같은 기준 디렉터리를 쓰는 제외 패턴 목록
+pub struct IgnoreRules {
+    patterns: Vec<IgnorePattern>,
+}
+
+impl IgnoreRules {
+    /// 패턴 목록을 컴파일합니다. 빈 줄과 `#` 주석은 건너뜁니다.
+    pub fn new<'a>(lines: impl IntoIterator<Item = &'a str>) -> Result<Self> {
+        let mut patterns = Vec::new();
+        for line in lines {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            // `\#`, `\!`는 글자 그대로
+            let (negate, pattern) = match line.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, line.strip_prefix('\\').unwrap_or(line)),
+            };
+            let (dir_only, pattern) = match pattern.strip_suffix('/') {
+                Some(rest) => (true, rest),
+                None => (false, pattern),
+            };
+            let glob = match pattern.strip_prefix('/') {
+                Some(anchored) => anchored.to_string(),
+                None if pattern.contains('/') => pattern.to_string(),
+                None => format!("**/{}", pattern),
+            };
+            let compile = |glob: &str| {
+                // `*`는 디렉터리 구분자를 넘지 않음 (`**`만 여러 단계와 일치)
+                GlobBuilder::new(glob)
+                    .literal_separator(true)
+                    .build()
+                    .map(|glob| glob.compile_matcher())
+                    .with_context(|| format!("Invalid ignore pattern: {}", line))
+            };
+            patterns.push(IgnorePattern {
+                matcher: compile(&glob)?,
+                contents: compile(&format!("{}/**", glob))?,
+                negate,
+                dir_only,
+            });
+        }
+        Ok(IgnoreRules { patterns })
+    }
+
+    /// 마지막으로 일치한 패턴의 판정 (Some(true): 제외, Some(false): `!`로 다시 포함, None: 일치 없음)
+    pub fn decide(&self, relative: &Path, is_dir: bool) -> Option<bool> {
+        self.patterns.iter().rev().find_map(|pattern| {
+            let matched = (pattern.matcher.is_match(relative) && (is_dir || !pattern.dir_only))
+                || pattern.contents.is_match(relative);
+            matched.then_some(!pattern.negate)
+        })
+    }
+}
+
+/// `.modernizerignore` 파일 하나 (패턴은 파일이 있는 디렉터리 기준)
+pub struct IgnoreFile {
+    pub path: PathBuf,
+    base: PathBuf,
+    rules: IgnoreRules,
+}
+
+impl IgnoreFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path).with_context(|| format!("Failed to read ignore file: {}", path.display()))?;
+        let rules = IgnoreRules::new(text.lines()).with_context(|| format!("Invalid ignore file: {}", path.display()))?;
+        let base = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(IgnoreFile { path: path.to_path_buf(), base, rules })
+    }
+
+    /// 디렉터리 `dir`와 상위 디렉터리의 제외 파일을 먼 것부터 찾습니다. (저장소 루트(`.git`)에서 멈춤)
+    pub fn discover(dir: &Path) -> Result<Vec<Self>> {
+        let mut files = Vec::new();
+        for ancestor in dir.ancestors() {
+            let path = ancestor.join(IGNORE_FILE);
+            if path.is_file() {
+                files.push(IgnoreFile::load(&path)?);
+            }
+            if ancestor.join(".git").exists() {
+                break;
+            }
+        }
+        files.reverse();
+        Ok(files)
+    }
+
+    /// 이 파일 기준 디렉터리 아래의 경로이면 판정을 반환합니다.
+    pub fn decide(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let relative = path.strip_prefix(&self.base).ok()?;
+        self.rules.decide(relative, is_dir)
+    }
+}
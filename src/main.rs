@@ -1,49 +1,413 @@
-use anyhow::{Context, Result};
-use clap::Parser;
-use std::{collections::HashMap, fs, path::PathBuf};
-use syn::{
-    parse_quote,
-    visit_mut::{self, VisitMut},
-    Expr, ExprCall, ExprMethodCall, Lit,
+mod diff;
+mod html;
+mod ignore;
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::{IgnoreFile, IgnoreRules, IGNORE_FILE};
+use indicatif::{ProgressBar, ProgressStyle};
+use modernizer_core::{
+    default_rules, expand_path, is_markdown, layer_rules, load_rules_by_file, load_rules_with_format, log, manifest_edition,
+    validate_rules, AnnotationStyle, AuditEntry, Baseline, Edition, FileReport, HandlerRegistry, LineEndings, Modernizer, ModernizerRule,
+    Risk, RulesFile, RulesFormat, RustVersion, Severity, TransformOptions, ValidationError,
+};
+use notify::{event::ModifyKind, EventKind, RecursiveMode, Watcher};
+use rayon::prelude::*;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+    fs,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    process::{self, ExitCode},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    time::Duration,
 };
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// 사람이 읽는 진행 로그를 켜는 플래그. JSON 보고서 모드에서는 stdout을 보고서 전용으로 남기기 위해 stderr로 보냅니다.
+static LOG_TO_STDERR: AtomicBool = AtomicBool::new(false);
+/// --quiet: 진행 로그를 출력하지 않음
+static QUIET: AtomicBool = AtomicBool::new(false);
+/// 일괄 처리 중 표시되는 진행 표시줄. 로그는 표시줄을 잠시 지운 뒤 출력하여 화면이 깨지지 않게 합니다.
+static PROGRESS: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// modernizer-core의 진행 로그 출력 함수 (`main`에서 설정)
+fn print_log(message: &str) {
+    if !QUIET.load(Ordering::Relaxed) {
+        print_output(message);
+    }
+}
+
+/// 명령의 실제 결과(dry-run 코드, diff, 텍스트 보고서)를 출력합니다. --quiet여도 숨기지 않습니다.
+fn print_output(message: &str) {
+    let emit = || {
+        if LOG_TO_STDERR.load(Ordering::Relaxed) {
+            eprintln!("{}", message);
+        } else {
+            println!("{}", message);
+        }
+    };
+    let progress = PROGRESS.lock().ok().and_then(|bar| bar.clone());
+    match progress {
+        Some(bar) => bar.suspend(emit),
+        None => emit(),
+    }
+}
 
 /// ----------------------------------------------------
-/// 0. 상수 및 규칙 모델 정의
+/// 0. 상수 정의
 /// ----------------------------------------------------
-const DOC_URL_UNWRAP_TO_TRY: &str = "https://doc.rust-lang.org/book/ch09-02-recoverable-errors-with-result.html";
-const DOC_URL_MEM_UNINITIALIZED: &str = "https://doc.rust-lang.org/std/mem/fn.uninitialized";
-
-/// AST 변환을 위한 단일 규칙을 정의하는 구조체 (JSON에서 로드됨)
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ModernizerRule {
-    /// 규칙 ID (보고서 및 로그용)
-    id: String,
-    /// 매칭할 AST 타입 (ExprMethodCall, ExprCall)
-    ast_type: String, 
-    /// 매칭할 메서드 이름 (.unwrap, uninitialized 등)
-    method_name: String, 
-    /// 매칭할 인자 개수
-    args_count: u8,
-    /// 로그에 사용할 경고/정보 수준 (예: "✅", "⚠️", "❌")
-    level_icon: String,
-    /// 공식 문서 URL (parse_quote 내부에서 직접 참조 불가)
-    doc_url: String,
-    /// 특수 패턴 매칭을 위한 플래그 (예: ok().unwrap() 매칭 시 "ok")
-    nested_method: Option<String>, 
+/// 일괄 처리에서 건너뛸 파일 크기 기본값 (--max-file-size)
+const DEFAULT_MAX_FILE_SIZE: u64 = 1024 * 1024;
+/// --rules-file을 생략했을 때 (있으면) 내장 규칙 위에 덧붙여 읽는 규칙 파일
+const DEFAULT_RULES_FILE: &str = "modernizer_rules.json";
+/// 감시 모드에서 연속 저장 이벤트를 하나로 묶는 디바운스 간격
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// 종료 코드 (스크립트에서 결과를 구분할 수 있도록 고정된 값을 유지합니다)
+/// 변경 사항 없음
+const EXIT_CLEAN: u8 = 0;
+/// 분류되지 않은 오류
+const EXIT_FAILURE: u8 = 1;
+/// 변경 사항을 적용함 (dry-run/--check이면 적용될 변경이 있음)
+const EXIT_CHANGED: u8 = 2;
+/// 입력/규칙 파일 파싱 또는 변환 결과 검증 실패
+const EXIT_INVALID: u8 = 3;
+/// 파일 읽기/쓰기 실패
+const EXIT_IO: u8 = 4;
+/// 잘못된 명령줄 인자
+const EXIT_USAGE: u8 = 64;
+
+/// `cargo-modernize` 바이너리가 `cargo modernize`로 실행했음을 알리는 환경 변수 (src/bin/cargo-modernize.rs와 같은 값)
+const CARGO_SUBCOMMAND_ENV: &str = "RUST_MODERNIZER_CARGO_SUBCOMMAND";
+
+/// 명령줄 인자 오류 중 clap이 검사할 수 없는 것 (EXIT_USAGE)
+#[derive(Debug)]
+struct UsageError(String);
+
+impl std::fmt::Display for UsageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UsageError {}
+
+/// 여러 파일 중 일부가 실패함. 종료 코드는 실패한 파일들의 코드 중 가장 큰 값입니다.
+#[derive(Debug)]
+struct BatchFailure {
+    failed: usize,
+    total: usize,
+    exit_code: u8,
+}
+
+impl std::fmt::Display for BatchFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} of {} file(s) failed to modernize; no files were written", self.failed, self.total)
+    }
+}
+
+impl std::error::Error for BatchFailure {}
+
+/// 보고서 출력 형식
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ReportFormat {
+    /// 사람이 읽는 터미널 출력
+    Text,
+    /// 파일별 변경 위치 목록 (PR 자동화용 JSON, stdout에 출력)
+    Json,
+    /// SARIF 2.1.0 (GitHub code scanning 등 CI 대시보드용, stdout에 출력)
+    Sarif,
+    /// 변경 전/후 코드와 규칙별 빈도 차트가 담긴 단일 HTML 파일 (stdout에 출력, `> report.html`로 저장)
+    Html,
+}
+
+/// 실행 전체의 변환 결과. 방문자가 파일별로 쌓은 결과를 모아 마지막에 --report-format에 따라 한 번만 출력합니다.
+#[derive(Debug, Serialize)]
+struct Report {
+    dry_run: bool,
+    /// 전체 파일의 위험도별 변환 건수
+    risk_counts: BTreeMap<Risk, u32>,
+    /// 전체 파일의 규칙별 변환 건수 (파일별 결과는 병렬로 만들어지므로 모든 파일을 처리한 뒤 합산)
+    counts: BTreeMap<String, u32>,
+    files: Vec<FileReport>,
+    /// --workspace: 크레이트별 요약
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    crates: Vec<CrateSummary>,
+    /// --only/--skip으로 제외된 규칙 ID → 이유
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    skipped_rules: BTreeMap<String, String>,
+}
+
+/// --workspace 실행에서 크레이트 하나의 변환 요약
+#[derive(Debug, Serialize)]
+struct CrateSummary {
+    name: String,
+    files: usize,
+    /// 규칙 ID → 적용(예정) 건수
+    counts: BTreeMap<String, u32>,
+}
+
+impl Report {
+    fn new(files: Vec<FileReport>, dry_run: bool) -> Self {
+        let mut risk_counts = BTreeMap::new();
+        for (risk, count) in files.iter().flat_map(|file| &file.risk_counts) {
+            *risk_counts.entry(*risk).or_insert(0) += count;
+        }
+        let mut counts = BTreeMap::new();
+        for (id, count) in files.iter().flat_map(|file| &file.counts) {
+            *counts.entry(id.clone()).or_insert(0) += count;
+        }
+        Report { dry_run, risk_counts, counts, files, crates: Vec::new(), skipped_rules: BTreeMap::new() }
+    }
+
+    /// 파일별 결과를 가장 가까운 상위 멤버 디렉터리의 크레이트로 묶어 집계합니다.
+    fn summarize_crates(&mut self, members: &[WorkspaceMember]) {
+        self.crates = members
+            .iter()
+            .map(|member| CrateSummary { name: member.name.clone(), files: 0, counts: BTreeMap::new() })
+            .collect();
+        for file in &self.files {
+            let owner = members
+                .iter()
+                .enumerate()
+                .filter(|(_, member)| file.path.starts_with(&member.dir))
+                .max_by_key(|(_, member)| member.dir.components().count());
+            let Some((index, _)) = owner else { continue };
+            let summary = &mut self.crates[index];
+            summary.files += 1;
+            for (id, count) in &file.counts {
+                *summary.counts.entry(id.clone()).or_insert(0) += count;
+            }
+        }
+    }
+
+    /// 변경(dry-run이면 변경 예정)이 하나라도 있는지 확인합니다.
+    fn changed(&self) -> bool {
+        self.files.iter().any(|file| !file.changes.is_empty())
+    }
+
+    /// 심각도가 `threshold` 이상인 변환 건수 (--fail-on)
+    fn findings_at(&self, threshold: Severity) -> usize {
+        self.files.iter().flat_map(|file| &file.changes).filter(|change| change.severity >= threshold).count()
+    }
+
+    /// 활성 규칙 중 모든 파일에서 한 번도 적용되지 않은 규칙 ID 목록
+    fn unused_rules<'r>(&self, rules: &'r [ModernizerRule]) -> Vec<&'r str> {
+        rules
+            .iter()
+            .filter(|rule| rule.disabled_by.is_none())
+            .filter(|rule| self.files.iter().all(|file| file.counts.get(&rule.id).is_none_or(|count| *count == 0)))
+            .map(|rule| rule.id.as_str())
+            .collect()
+    }
+
+    fn render(&self, format: ReportFormat, rules: &[ModernizerRule]) -> Result<String> {
+        match format {
+            ReportFormat::Text => {
+                let mut output = String::new();
+                for file in &self.files {
+                    file.render_text(&mut output);
+                }
+                if self.files.len() > 1 && !self.counts.is_empty() {
+                    let _ = writeln!(output, "\n📊 전체 (파일 {}개):", self.files.len());
+                    for (id, count) in &self.counts {
+                        let _ = writeln!(output, "  - {} 건 ({})", count, id);
+                    }
+                    let risk_summary: Vec<String> =
+                        self.risk_counts.iter().map(|(risk, count)| format!("{} {}", risk.as_str(), count)).collect();
+                    let _ = writeln!(output, "  위험도: {}", risk_summary.join(", "));
+                }
+                if !self.crates.is_empty() {
+                    let _ = writeln!(output, "\n📦 크레이트별 요약:");
+                }
+                for summary in &self.crates {
+                    let total: u32 = summary.counts.values().sum();
+                    if total == 0 {
+                        let _ = writeln!(output, "  ✅ {}: 레거시 패턴 없음 (파일 {}개)", summary.name, summary.files);
+                        continue;
+                    }
+                    let rules: Vec<String> = summary.counts.iter().map(|(id, count)| format!("{} {}", id, count)).collect();
+                    let _ = writeln!(
+                        output,
+                        "  ⚠️ {}: {}건 (파일 {}개) — {}",
+                        summary.name, total, summary.files, rules.join(", ")
+                    );
+                }
+                // 같은 이유로 제외된 규칙은 한 줄로 묶음
+                let mut skipped: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+                for (id, reason) in &self.skipped_rules {
+                    skipped.entry(reason).or_default().push(id);
+                }
+                for (reason, ids) in skipped {
+                    let _ = writeln!(output, "⏭️ 건너뛴 규칙 {}개 ({}): {}", ids.len(), reason, ids.join(", "));
+                }
+                Ok(output)
+            }
+            ReportFormat::Json => serde_json::to_string_pretty(self).with_context(|| "Failed to serialize JSON report."),
+            ReportFormat::Sarif => serde_json::to_string_pretty(&self.sarif(rules))
+                .with_context(|| "Failed to serialize SARIF report."),
+            ReportFormat::Html => Ok(html::render(self)),
+        }
+    }
+
+    /// SARIF 2.1.0 로그: 적용된 규칙이 SARIF 규칙(`helpUri`는 doc_url), 각 변환이 결과(물리적 위치 포함)가 됩니다.
+    fn sarif(&self, rules: &[ModernizerRule]) -> serde_json::Value {
+        let level = |severity: Severity| match severity {
+            Severity::Info => "note",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        let rules: Vec<&ModernizerRule> = rules.iter().filter(|rule| rule.disabled_by.is_none()).collect();
+        let sarif_rules: Vec<serde_json::Value> = rules
+            .iter()
+            .map(|rule| {
+                let mut sarif_rule = serde_json::json!({
+                    "id": rule.id,
+                    "shortDescription": { "text": format!("{} `{}` → `{}`", rule.ast_type, rule.method_name, rule.replacement_template) },
+                    "defaultConfiguration": { "level": level(rule.severity()) },
+                    "properties": { "risk": rule.risk().as_str() },
+                });
+                if !rule.doc_url.is_empty() {
+                    sarif_rule["helpUri"] = rule.doc_url.clone().into();
+                }
+                sarif_rule
+            })
+            .collect();
+
+        let mut results = Vec::new();
+        for file in &self.files {
+            // SARIF URI는 `/` 구분자를 사용
+            let uri = file.path.to_string_lossy().replace('\\', "/");
+            for change in &file.changes {
+                let message = match (&change.replacement, &change.review_note) {
+                    (_, Some(note)) => note.clone(),
+                    (Some(replacement), None) => format!("{}: replace with `{}`", change.rule_id, replacement),
+                    (None, None) => format!("{}: legacy pattern", change.rule_id),
+                };
+                let mut result = serde_json::json!({
+                    "ruleId": change.rule_id,
+                    "level": level(change.severity),
+                    "message": { "text": message },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": uri },
+                            "region": {
+                                "startLine": change.location.line,
+                                "startColumn": change.location.column,
+                                "endLine": change.location.end_line,
+                                "endColumn": change.location.end_column,
+                            },
+                        },
+                    }],
+                });
+                if let Some(index) = rules.iter().position(|rule| rule.id == change.rule_id) {
+                    result["ruleIndex"] = index.into();
+                }
+                results.push(result);
+            }
+        }
+
+        serde_json::json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": sarif_rules,
+                    },
+                },
+                "results": results,
+            }],
+        })
+    }
 }
 
+/// 검증까지 끝나고 쓰기를 기다리는 파일 하나의 변환 결과
+struct PendingOutput {
+    report: FileReport,
+    /// 저장할 경로
+    path: PathBuf,
+    /// 저장할 코드 (변경 없음 또는 dry-run이면 None)
+    code: Option<String>,
+    /// --cache-dir 사용 시 이 파일의 캐시 키 (캐시에서 재사용한 결과면 None)
+    cache_key: Option<String>,
+    /// --audit-file 사용 시 이 파일의 규칙 판정 기록
+    audit: Vec<AuditEntry>,
+    /// --diff-file/--patch 사용 시 원본 대비 변환 결과의 unified diff hunk (머리말 제외, 변경 없으면 빈 문자열)
+    diff: String,
+}
 
 /// ----------------------------------------------------
 /// 1. CLI 구조 정의 (clap)
 /// ----------------------------------------------------
+/// 서브커맨드를 생략하면 `run`과 동일하게 동작합니다. (`rust_modernizer hello.rs --output out.rs`)
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = "Rust Legacy Code Modernizer using AST traversal.")]
-struct Args {
-    /// 변환할 Rust 파일 경로
-    input: PathBuf,
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+#[command(after_help = "종료 코드:\n  0   변경 사항 없음\n  1   기타 오류\n  2   변경 사항 적용 (dry-run: 적용될 변경 있음)\n  \
+                        3   입력/규칙 파일 파싱 또는 변환 결과 검증 실패\n  4   파일 입출력 오류\n  64  잘못된 명령줄 인자")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    // 중첩 flatten(RuleArgs)이 있으면 clap이 Option<Args>의 존재 여부를 판정하지 못하므로 Option으로 감싸지 않습니다.
+    #[command(flatten)]
+    run: RunArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// 규칙을 적용하여 Rust 파일을 변환 (기본 동작)
+    Run(Box<RunArgs>),
+    /// 규칙 파일을 로드하여 각 규칙과 활성화 여부를 표로 출력 (소스 파일은 처리하지 않음)
+    ListRules(ListRulesArgs),
+    /// 내장 기본 규칙으로 채워진 규칙 파일을 생성
+    GenerateRules(GenerateRulesArgs),
+    /// 규칙의 문서 URL(doc_url)을 출력 (--open: 브라우저로 열기)
+    Doc(DocArgs),
+    /// 규칙 파일 형식의 JSON Schema를 출력 (편집기 자동 완성/검증용, 규칙 파일에서 `$schema`로 참조)
+    Schema(SchemaArgs),
+    /// 규칙 파일을 검사하여 적용되지 않을 규칙을 보고 (알 수 없는 ast_type, 전개할 수 없는 템플릿,
+    /// 중복 ID, 없는 자리표시자, 앞선 규칙에 가려지는 규칙. 오류가 있으면 종료 코드 3)
+    ValidateRules(ValidateRulesArgs),
+}
 
-    /// 변환된 코드를 저장할 출력 파일 경로
+#[derive(clap::Args, Debug)]
+struct RunArgs {
+    /// 변환할 Rust 파일 또는 디렉터리 경로 (디렉터리는 하위의 .rs 파일을 재귀적으로 처리,
+    /// 여러 파일을 처리할 때는 --inplace 또는 --dry-run 필요).
+    /// .md 파일은 ```rust 코드 블록만 변환 (디렉터리 안의 .md는 --include 패턴에 일치할 때만).
+    /// --workspace이면 워크스페이스 디렉터리 (생략 시 현재 디렉터리), `cargo modernize`에서 생략하면 현재 패키지
+    #[arg(value_name = "INPUT")]
+    inputs: Vec<PathBuf>,
+
+    /// Cargo 워크스페이스의 모든 멤버 크레이트(`src/`, `tests/`, `examples/`, `benches/`)를 한 번에 변환하고
+    /// 마지막에 크레이트별 요약을 출력 (`cargo metadata`로 멤버 탐색)
+    #[arg(long, default_value_t = false, conflicts_with_all = ["watch", "output"])]
+    workspace: bool,
+
+    /// 디렉터리 입력에서 이 glob과 일치하는 파일만 처리 (디렉터리 기준 상대 경로, 반복 가능. 예: "src/**/*.rs")
+    #[arg(long, value_name = "GLOB")]
+    include: Vec<String>,
+
+    /// 디렉터리 입력에서 이 패턴과 일치하는 파일/디렉터리를 제외 (반복 가능. 예: "tests/", "*_generated.rs", "/vendor").
+    /// `.modernizerignore`와 같은 gitignore 형식이며 입력 디렉터리 기준입니다. (`!`로 다시 포함, .modernizerignore보다 우선)
+    /// 입력 디렉터리와 상위 디렉터리(저장소 루트까지)의 `.modernizerignore`도 읽으며,
+    /// `target/`과 `.`으로 시작하는 경로는 항상 제외됩니다.
+    #[arg(long, value_name = "PATTERN")]
+    exclude: Vec<String>,
+
+    /// 변환된 코드를 저장할 출력 파일 경로.
+    /// 입력이 디렉터리 하나이면 출력 디렉터리로 사용하여 변경된 파일을 같은 상대 경로에 저장합니다.
     #[arg(short, long)]
     output: Option<PathBuf>,
 
@@ -54,248 +418,2071 @@ struct Args {
     /// 실제 파일을 저장하지 않고 변환 결과만 터미널에 출력
     #[arg(long, default_value_t = false)]
     dry_run: bool,
-    
-    /// 규칙 파일을 지정합니다. (기본값: modernizer_rules.json)
-    #[arg(long, default_value = "modernizer_rules.json")]
-    rules_file: PathBuf,
+
+    /// 변환할 코드가 있는지만 검사 (`rustfmt --check`처럼 아무것도 쓰지 않고, 변환 결과 대신 보고서만 출력).
+    /// 적용될 변환이 있으면 종료 코드 2, 없으면 0. --diff와 함께 쓰면 변경 내용을 diff로 보여 줍니다.
+    #[arg(long, default_value_t = false, conflicts_with_all = ["inplace", "output", "watch", "verify", "interactive"])]
+    check: bool,
+
+    #[command(flatten)]
+    rule_args: RuleArgs,
+
+    /// 변환 결과를 다시 파싱하여 실패하면 파일을 저장하지 않음
+    #[arg(long, default_value_t = false)]
+    fail_on_unparseable_output: bool,
+
+    /// 적용된 규칙이 없어도 재출력(prettyplease)한 코드를 저장 (기본값: 재출력이 공백 외의 내용을 바꾸면 경고만 하고 건너뜀)
+    #[arg(long, default_value_t = false, conflicts_with = "only_count")]
+    force_reprint: bool,
+
+    /// 변환된 파일 전체를 prettyplease로 재출력 (기본값: 변환된 식만 바꾸고 주석/빈 줄/서식은 원본 그대로 유지)
+    #[arg(long, default_value_t = false)]
+    reformat: bool,
+
+    /// 저장하기 전에 변환된 파일을 rustfmt로 정리 (입력 파일에서 가장 가까운 rustfmt.toml과 Cargo.toml의 edition을 따름)
+    #[arg(long, default_value_t = false, conflicts_with = "only_count")]
+    format: bool,
+
+    /// 파일을 쓴 뒤 `cargo check`(Cargo 패키지 밖의 파일은 `rustc --emit=metadata`)로 컴파일을 확인하고,
+    /// 변환이 새 컴파일 오류를 만든 파일은 원래 내용으로 되돌립니다. (쓰기 전 검사와 비교하므로 기존 오류는 무시)
+    #[arg(long, default_value_t = false, conflicts_with_all = ["dry_run", "diff", "patch", "only_count", "watch"])]
+    verify: bool,
+
+    /// 저장할 파일의 줄바꿈 형식 (preserve: 원본 유지). BOM과 마지막 줄바꿈 여부는 항상 원본을 따름
+    #[arg(long, value_enum, default_value_t = LineEndings::Preserve)]
+    line_endings: LineEndings,
+
+    /// 파일 처리에 사용할 스레드 수 (기본값: 논리 CPU 수, 1이면 입력 순서대로 순차 처리)
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+    threads: Option<u16>,
+
+    /// 보고서 형식 (json: 파일별/규칙별 건수와 변경 위치, 대체 코드, 문서 URL을 stdout에 출력, 로그는 stderr로 이동)
+    #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+    report_format: ReportFormat,
+
+    /// (실험적) `let mut x = mem::uninitialized(); init(&mut x);`를 문장 단위로
+    /// `MaybeUninit::uninit()` + `x.as_mut_ptr()` + `assume_init()`으로 변환 (수동 검토 주석 포함)
+    #[arg(long, default_value_t = false)]
+    scoped_maybeuninit: bool,
+
+    /// 문장/아이템 단위 변환(--scoped-maybeuninit, `error_chain!`, `#[async_trait]`, futures 0.1 → async fn)이 남기는
+    /// 검토 안내 주석을 남기지 않음 (병합 후 깔끔한 출력용). 식 단위 규칙은 원래 주석 없이 바꾸고 검토 메모는 보고서에만 남깁니다.
+    #[arg(long, default_value_t = false)]
+    no_annotations: bool,
+
+    /// 검토 안내 주석 형식 (문장/아이템 단위 변환에만 해당, --no-annotations 참고)
+    #[arg(long, value_enum, default_value_t = AnnotationStyle::Verbose)]
+    annotation_style: AnnotationStyle,
+
+    /// 지정한 규칙과 부분적으로 일치한 노드마다 위치와 적용/거부 이유를 출력 (규칙 작성 디버깅용, --cache-dir 재사용 안 함)
+    #[arg(long, value_name = "RULE_ID")]
+    explain: Option<String>,
+
+    /// 규칙의 `handler`로 사용할 플러그인 실행 파일 디렉터리 (확장자를 뺀 파일 이름이 처리기 이름).
+    /// 플러그인은 규칙 ID를 인자로, 매칭된 식의 코드를 표준 입력으로 받아 대체 식 코드를 표준 출력에 씁니다. (빈 출력: 적용 안 함)
+    /// `.wasm`/`.wat` 파일은 WebAssembly 모듈로 실행합니다. (호스트 API `modernizer.node_read`/`replace` 등으로 토큰을 주고받음)
+    #[arg(long, value_name = "DIR")]
+    plugins_dir: Option<PathBuf>,
+
+    /// 파일 내용과 규칙의 해시를 키로 이전 결과를 저장하여, 바뀌지 않은 파일은 다시 처리하지 않음
+    /// (규칙이나 변환 옵션이 바뀌면 기존 캐시는 삭제됩니다)
+    #[arg(long, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+
+    /// 문서 주석(`///`, `//!`) 안의 ```rust 코드 블록(doc-test)에도 규칙을 적용
+    /// (숨김 줄 `# `이 있는 블록은 건너뜀)
+    #[arg(long, default_value_t = false)]
+    doc_tests: bool,
+
+    /// 보고서에 감싸는 함수별 규칙 적용 건수를 추가 (JSON: 파일 → 함수 → 규칙 → 건수)
+    #[arg(long, default_value_t = false)]
+    by_function: bool,
+
+    /// 규칙 모양과 일치한 모든 노드의 적용/건너뜀 판정과 이유를 JSONL로 기록 (감사용, --cache-dir 재사용 안 함)
+    #[arg(long, value_name = "PATH", conflicts_with = "watch")]
+    audit_file: Option<PathBuf>,
+
+    /// 원본 대비 변환 결과(dry-run이면 적용될 결과)의 unified diff를 파일로 저장 (여러 파일이면 한 파일에 이어 붙임,
+    /// `patch -p0`로 적용 가능, --cache-dir 재사용 안 함)
+    #[arg(long, value_name = "PATH", conflicts_with = "watch")]
+    diff_file: Option<PathBuf>,
+
+    /// 변환마다 변경 전/후 코드를 보여 주고 적용(y), 거절(n), 직접 수정(e)을 묻습니다. (파일을 순서대로 하나씩 처리)
+    /// 규칙 단위 자동 변환이 위험한 코드(`.unwrap()` → `?` 등)를 사람이 확인하며 적용할 때 사용합니다.
+    #[arg(long, default_value_t = false, conflicts_with_all = ["watch", "only_count"])]
+    interactive: bool,
+
+    /// dry-run 결과를 파일 전체 대신 파일별 unified diff(원본 → 변환 결과)로 출력.
+    /// 파일을 쓰지 않으므로 dry-run처럼 동작합니다.
+    #[arg(long, default_value_t = false, conflicts_with_all = ["inplace", "output", "watch"])]
+    diff: bool,
+
+    /// 파일을 고치는 대신 `git apply`로 적용할 수 있는 패치 파일을 생성 (`a/`, `b/` 머리말, 현재 디렉터리 기준 상대 경로).
+    /// 파일을 쓰지 않으므로 dry-run처럼 동작합니다. (`--emit-patch`로도 사용 가능)
+    #[arg(long, visible_alias = "emit-patch", value_name = "PATH", conflicts_with_all = ["inplace", "output", "watch"])]
+    patch: Option<PathBuf>,
+
+    /// 변환 결과 코드를 만들지 않고(prettyplease 생략) 규칙 적용 건수만 집계 (CI에서 레거시 패턴 존재 여부만 확인할 때).
+    /// 파일을 쓰지 않으므로 dry-run처럼 동작합니다.
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["inplace", "output", "watch", "diff", "diff_file", "patch", "fail_on_unparseable_output"]
+    )]
+    only_count: bool,
+
+    /// 모든 입력을 처리한 뒤 한 번도 적용되지 않은 활성 규칙이 있으면 목록을 출력하고 실패 (종료 코드 3).
+    /// 잘못된 method_name/ast_type처럼 조용히 아무것도 하지 않는 규칙을 찾을 때 사용합니다.
+    #[arg(long, default_value_t = false, conflicts_with = "watch")]
+    error_on_unused_rules: bool,
+
+    /// 이 심각도 이상인 변환(dry-run이면 변환 예정)이 있을 때만 종료 코드 2로 끝내고, 그보다 낮은 변환만 있으면 0.
+    /// CI에서 건전하지 않은 레거시 패턴(error)은 막고 스타일 변환(info)은 보고만 할 때 사용합니다. (예: `--fail-on error`)
+    #[arg(long, value_enum, value_name = "SEVERITY", conflicts_with = "watch")]
+    fail_on: Option<Severity>,
+
+    /// 현재 변환(레거시 패턴) 목록을 이 파일에 기준선으로 기록합니다. (소스 파일은 쓰지 않음)
+    /// --use-baseline과 함께 쓰면 기록하는 대신 이 기준선을 읽습니다.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["watch", "interactive"])]
+    baseline: Option<PathBuf>,
+
+    /// --baseline 파일에 기록된 변환은 적용/보고하지 않고 그 뒤에 새로 생긴 레거시 패턴만 다룹니다.
+    /// 한 번에 다 고칠 수 없는 큰 코드베이스에서 CI로 새 레거시 코드 유입만 막을 때 사용합니다.
+    #[arg(long, default_value_t = false, requires = "baseline")]
+    use_baseline: bool,
+
+    /// --use-baseline으로 읽은 기준선 (run 시작 시 한 번 로드)
+    #[arg(skip)]
+    baseline_findings: Option<Arc<Baseline>>,
+
+    /// 진행 로그와 진행 표시줄을 숨김 (dry-run 결과 코드, diff, 보고서와 오류는 그대로 출력)
+    #[arg(short, long, default_value_t = false)]
+    quiet: bool,
+
+    /// 변경이 없을 때까지 규칙 적용을 최대 N번 반복 (앞선 변환이 만든 코드에 다른 규칙을 연쇄 적용, 예: `try!(x)` → `x?` → `Ok(x?)` 정리)
+    #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    max_iterations: u32,
+
+    /// 여러 파일/디렉터리 처리 시 이 크기(바이트)보다 큰 파일은 건너뜀 (생성된 대용량 코드 보호, 기본값: 1 MiB)
+    #[arg(long, value_name = "BYTES", default_value_t = DEFAULT_MAX_FILE_SIZE)]
+    max_file_size: u64,
+
+    /// --max-file-size보다 큰 파일도 처리
+    #[arg(long, default_value_t = false)]
+    force: bool,
+
+    /// 이 git ref 이후 현재 브랜치에서 바뀐 .rs 파일만 처리 (`git diff --name-only <REF>...HEAD`와 입력 경로의 교집합)
+    #[arg(long, value_name = "GIT_REF", conflicts_with = "watch")]
+    since: Option<String>,
+
+    /// 변환하지 않고 파싱된 syn 구문 트리를 Debug 형식(`{:#?}`)으로 stdout에 출력 (규칙 작성 시 노드 모양 확인용)
+    #[arg(long, default_value_t = false, conflicts_with = "watch")]
+    print_ast: bool,
+
+    /// 입력 경로(파일 또는 디렉터리)와 규칙 파일을 감시하며 저장할 때마다 다시 실행
+    /// (--inplace/--output을 지정하지 않으면 dry-run으로 동작)
+    #[arg(long, default_value_t = false)]
+    watch: bool,
 }
 
-/// ----------------------------------------------------
-/// 2. AST 변환기 정의 (syn::VisitMut)
-/// ----------------------------------------------------
-struct Modernizer {
-    changed: bool, 
-    counters: HashMap<String, u32>, // 규칙 ID별 카운터
-    rules: Vec<ModernizerRule>, 
+/// 규칙 파일과 적용할 규칙을 고르는 옵션 (`run`, `list-rules` 공통)
+#[derive(clap::Args, Debug)]
+struct RuleArgs {
+    /// 규칙 파일을 지정합니다. 규칙은 내장 기본 규칙 위에 덧붙이며, 같은 id의 기본 규칙은 파일의 규칙으로 교체됩니다.
+    /// 생략하면 현재 디렉터리에 modernizer_rules.json이 있을 때만 읽습니다.
+    /// 여러 번 지정하거나 디렉터리를 주면 (디렉터리 안의 `*.json`, `*.toml`, `*.yaml`, `*.yml`을 이름순으로) 모든 규칙을 합칩니다.
+    /// `~`, `$VAR`, `${VAR}`는 읽기 전에 전개됩니다. (예: `$MIGRATION_RULES_DIR/rules.json`)
+    #[arg(long = "rules-file", value_name = "PATH")]
+    rules_files: Vec<PathBuf>,
+
+    /// 내장 기본 규칙을 쓰지 않고 규칙 파일의 규칙만 사용
+    #[arg(long, default_value_t = false)]
+    no_default_rules: bool,
+
+    /// 규칙 파일 형식 (생략하면 확장자로 판별: .json, .toml, .yaml/.yml, 그 외는 JSON)
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    rules_format: Option<RulesFormat>,
+
+    /// 이 심각도 이상인 규칙만 적용 (예: error → 위험한 규칙만 실행)
+    #[arg(long, value_enum, default_value_t = Severity::Info)]
+    min_severity: Severity,
+
+    /// 이 위험도 이하인 규칙만 적용 (예: low → 기계적인 변환만 실행)
+    #[arg(long, value_enum, default_value_t = Risk::High)]
+    max_risk: Risk,
+
+    /// 규칙 파일에서 `"enabled": false`로 꺼 둔 규칙을 켭니다. (여러 번 지정 가능)
+    #[arg(long = "enable-rule", value_name = "ID")]
+    enable_rules: Vec<String>,
+
+    /// 이 규칙들만 적용 (쉼표로 구분, 예: `--only unwrap_to_try,expect_to_try`). 꺼 둔 규칙도 켭니다.
+    #[arg(long, value_name = "ID,...", value_delimiter = ',')]
+    only: Vec<String>,
+
+    /// 이 규칙들은 적용하지 않음 (쉼표로 구분, --only보다 우선)
+    #[arg(long, value_name = "ID,...", value_delimiter = ',')]
+    skip: Vec<String>,
+
+    /// 대상 프로젝트의 최소 지원 Rust 버전 (이보다 높은 min_rust_version의 규칙은 건너뜀).
+    /// 생략하면 (첫 번째) 입력 경로에서 가장 가까운 Cargo.toml의 `rust-version`을 사용합니다.
+    #[arg(long, value_name = "VERSION")]
+    rust_version: Option<RustVersion>,
+
+    /// 대상 크레이트의 에디션 (이보다 높은 min_edition의 규칙은 건너뜀).
+    /// 생략하면 (첫 번째) 입력 경로에서 가장 가까운 Cargo.toml의 `edition`을 사용하고, 없으면 2015로 봅니다.
+    #[arg(long, value_name = "EDITION")]
+    edition: Option<Edition>,
 }
 
-impl Modernizer {
-    fn new(rules: Vec<ModernizerRule>) -> Self {
-        Modernizer {
-            changed: false,
-            counters: HashMap::new(),
-            rules,
+impl RunArgs {
+    /// 파일 단위 변환 옵션 (modernizer-core에 전달)
+    fn transform_options(&self) -> TransformOptions {
+        TransformOptions {
+            max_iterations: self.max_iterations,
+            scoped_maybeuninit: self.scoped_maybeuninit,
+            annotations: (!self.no_annotations).then_some(self.annotation_style),
+            doc_tests: self.doc_tests,
+            explain: self.explain.clone(),
+            audit: self.audit_file.is_some(),
+            interactive: self.interactive,
+            by_function: self.by_function,
+            only_count: self.only_count,
+            reformat: self.reformat,
+            format: self.format,
+            force_reprint: self.force_reprint,
+            line_endings: self.line_endings,
+            fail_on_unparseable_output: self.fail_on_unparseable_output,
         }
     }
-    
-    /// 규칙 템플릿을 기반으로 AST 노드를 생성합니다. (parse_quote! 제약 사항 처리)
-    fn apply_rule_template(&self, method_call: &ExprMethodCall, rule: &ModernizerRule) -> Option<Expr> {
-        let receiver = method_call.receiver.clone();
-        
-        // DOC URL은 parse_quote! 내부에서 직접 참조할 수 없으므로, ID별 상수를 사용합니다.
-        // 이 함수는 런타임에 호출되지만, AST 생성을 위해서는 컴파일 타임 매크로인 parse_quote!에 의존해야 합니다.
-        let doc_url_unwrap = DOC_URL_UNWRAP_TO_TRY; // 상수를 변수에 복사
-
-        match rule.id.as_str() {
-            "unwrap_to_try" => {
-                Some(parse_quote! {
-                    // DOC: Converted `.unwrap()` to `?` for idiomatic error propagation. Ref: #doc_url_unwrap
-                    #receiver? 
-                })
-            }
-            "expect_to_try" => {
-                // Expect 메시지 제거 및 경고 주석 포함
-                Some(parse_quote! {
-                    // DOC: Converted `.expect()` to `?`. Manual review is required. Ref: #doc_url_unwrap
-                    // NOTE: Original expect message was removed during transformation.
-                    #receiver? 
-                })
-            }
-            "ok_unwrap_to_try" => {
-                 if let Expr::MethodCall(inner_call) = &*method_call.receiver {
-                     let inner_receiver = inner_call.receiver.clone();
-                     Some(parse_quote! {
-                        // DOC: Converted `ok().unwrap()` to `?`. Ref: #doc_url_unwrap
-                        #inner_receiver? 
-                     })
-                 } else {
-                     None
-                 }
-            }
-            _ => None
+}
+
+impl RuleArgs {
+    /// --rules-file 경로들의 `~`와 환경 변수를 전개합니다.
+    fn expand_rules_file(&mut self) -> Result<()> {
+        for rules_file in &mut self.rules_files {
+            *rules_file = expand_path(rules_file)
+                .with_context(|| format!("Failed to expand rule file path: {}", rules_file.display()))?;
         }
+        Ok(())
     }
-    
-    /// 로드된 규칙을 순회하며 메서드 호출을 변환합니다.
-    fn transform_method_call(&mut self, method_call: &ExprMethodCall) -> Option<Expr> {
-        let method_name = method_call.method.to_string();
-        
-        for rule in &self.rules {
-            if rule.ast_type != "ExprMethodCall" { continue; }
-
-            if rule.method_name == method_name && rule.args_count as usize == method_call.args.len() {
-                
-                let is_nested_match = match rule.nested_method.as_deref() {
-                    Some(nested) => {
-                        if let Expr::MethodCall(inner_call) = &*method_call.receiver {
-                            inner_call.method.to_string() == nested
-                        } else {
-                            false
-                        }
-                    }
-                    None => true,
-                };
 
-                if is_nested_match {
-                    if let Some(new_expr) = self.apply_rule_template(method_call, rule) {
-                        println!("[MOD] {} {} applied (Span: {:?})", rule.level_icon, rule.id, method_call.method.span());
-                        self.changed = true;
-                        *self.counters.entry(rule.id.clone()).or_insert(0) += 1;
-                        return Some(new_expr);
-                    }
-                }
+    /// 읽을 규칙 파일 (--rules-file, 생략하면 현재 디렉터리에 있는 경우의 modernizer_rules.json)
+    fn user_rules_files(&self) -> Vec<PathBuf> {
+        if !self.rules_files.is_empty() {
+            return self.rules_files.clone();
+        }
+        let default_file = PathBuf::from(DEFAULT_RULES_FILE);
+        if default_file.exists() { vec![default_file] } else { Vec::new() }
+    }
+
+    /// 내장 기본 규칙 위에 규칙 파일의 규칙을 덧붙여 읽습니다. (--rules-format, --no-default-rules 적용)
+    fn load_rules(&self) -> Result<Vec<ModernizerRule>> {
+        let rules_files = self.user_rules_files();
+        let user_rules = load_rules_with_format(&rules_files, self.rules_format)?;
+        if self.no_default_rules {
+            if rules_files.is_empty() {
+                return Err(UsageError(format!("--no-default-rules requires --rules-file (no {} in the current directory)", DEFAULT_RULES_FILE)).into());
             }
+            return Ok(user_rules);
         }
-        None
+
+        let defaults = default_rules()?;
+        let overridden = user_rules.iter().filter(|rule| defaults.iter().any(|default| default.id == rule.id)).count();
+        log!("📖 내장 기본 규칙 {}개 사용 (규칙 파일에서 {}개 재정의)", defaults.len(), overridden);
+        let rules = layer_rules(defaults, user_rules);
+        self.check_selection(&rules)?;
+        Ok(rules)
     }
-    
-    /// 로드된 규칙을 순회하며 함수 호출을 변환합니다. (`mem::uninitialized` 처리)
-    fn transform_expr_call(&mut self, expr_call: &ExprCall) -> Option<Expr> {
-        let doc_url_uninit = DOC_URL_MEM_UNINITIALIZED; // 상수를 변수에 복사
-        
-        for rule in &self.rules {
-            if rule.ast_type != "ExprCall" { continue; }
-            
-            if rule.id == "mem_uninitialized_to_maybeuninit" {
-                if let Expr::Path(expr_path) = &*expr_call.func {
-                    if let Some(segment) = expr_path.path.segments.last() {
-                        if segment.ident.to_string() == rule.method_name && expr_call.args.is_empty() {
-                            println!("[MOD] {} {} applied (Span: {:?})", rule.level_icon, rule.id, segment.ident.span());
-                            self.changed = true;
-                            *self.counters.entry(rule.id.clone()).or_insert(0) += 1;
-                            
-                            // uninitialized 변환은 unsafe 코드가 필요하므로 하드코딩된 parse_quote를 사용
-                            return Some(parse_quote! {
-                                // DOC: `std::mem::uninitialized` is deprecated. Replaced with `MaybeUninit` usage.
-                                // WARNING: This conversion remains `unsafe` and MUST be manually reviewed for initialization correctness.
-                                // Ref: #doc_url_uninit
-                                unsafe { 
-                                    std::mem::MaybeUninit::uninit().assume_init()
-                                }
-                            });
-                        }
-                    }
-                }
+
+    /// --only/--skip의 규칙 ID가 모두 있는지 확인합니다. (오타로 아무 규칙도 적용하지 않는 실행 방지)
+    fn check_selection(&self, rules: &[ModernizerRule]) -> Result<()> {
+        for (flag, ids) in [("--only", &self.only), ("--skip", &self.skip)] {
+            if let Some(unknown) = ids.iter().find(|id| !rules.iter().any(|rule| &&rule.id == id)) {
+                let available: Vec<&str> = rules.iter().map(|rule| rule.id.as_str()).collect();
+                return Err(UsageError(format!("{}: unknown rule `{}` (available: {})", flag, unknown, available.join(", "))).into());
             }
         }
-        None
+        Ok(())
     }
-}
 
-impl VisitMut for Modernizer {
-    fn visit_expr_mut(&mut self, i: &mut Expr) {
-        // 1. 깊이 우선 순회
-        visit_mut::visit_expr_mut(self, i); 
-        
-        let new_expr = match i {
-            // (1) 메서드 호출 변환 (데이터 기반)
-            Expr::MethodCall(method_call) => self.transform_method_call(method_call),
-            
-            // (2) 함수 호출 변환 (데이터 기반)
-            Expr::Call(expr_call) => self.transform_expr_call(expr_call),
+    /// --only/--skip으로 제외된 규칙과 이유 (보고서의 건너뛴 규칙 목록)
+    fn deselected_rules(&self, rules: &[ModernizerRule]) -> BTreeMap<String, String> {
+        rules
+            .iter()
+            .filter_map(|rule| Some((rule.id.clone(), self.deselected_reason(rule)?)))
+            .collect()
+    }
 
-            // (3) 기타 리터럴 패턴 확인 (유지)
-            Expr::Lit(expr_lit) => {
-                if let Lit::Str(lit_str) = &expr_lit.lit {
-                    if lit_str.value().contains("mem::uninitialized") {
-                        println!("[MOD] ℹ️ Found deprecated string pattern in literal.");
-                        self.changed = true;
-                    }
-                }
-                None
+    fn deselected_reason(&self, rule: &ModernizerRule) -> Option<String> {
+        if self.skip.contains(&rule.id) {
+            Some("listed in --skip".to_string())
+        } else if !self.only.is_empty() && !self.only.contains(&rule.id) {
+            Some("not listed in --only".to_string())
+        } else {
+            None
+        }
+    }
+
+    /// 규칙이 켜져 있는지 여부 (`"enabled": false`여도 --enable-rule 또는 --only로 지정하면 켜짐)
+    fn is_opted_in(&self, rule: &ModernizerRule) -> bool {
+        rule.enabled || self.enable_rules.contains(&rule.id) || self.only.contains(&rule.id)
+    }
+
+    /// 오류 메시지용 규칙 출처 (예: "built-in rules, a.json, rules/")
+    fn rules_files_display(&self) -> String {
+        let builtin = (!self.no_default_rules).then(|| "built-in rules".to_string());
+        let files = self.user_rules_files().into_iter().map(|path| path.display().to_string());
+        builtin.into_iter().chain(files).collect::<Vec<_>>().join(", ")
+    }
+
+    /// 로그용 필터 설명 (예: "--min-severity info --max-risk high --rust-version 1.70")
+    fn describe(&self) -> String {
+        let mut description = format!("--min-severity {} --max-risk {}", self.min_severity.as_str(), self.max_risk.as_str());
+        if let Some(rust_version) = self.rust_version {
+            description.push_str(&format!(" --rust-version {}", rust_version));
+        }
+        if let Some(edition) = self.edition {
+            description.push_str(&format!(" --edition {}", edition));
+        }
+        if !self.only.is_empty() {
+            description.push_str(&format!(" --only {}", self.only.join(",")));
+        }
+        if !self.skip.is_empty() {
+            description.push_str(&format!(" --skip {}", self.skip.join(",")));
+        }
+        description
+    }
+
+    /// 규칙이 현재 옵션에서 활성화되는지 판정합니다.
+    fn is_enabled(&self, rule: &ModernizerRule) -> bool {
+        self.disabled_reason(rule).is_none()
+    }
+
+    /// 규칙이 요구하는 Rust 버전이 프로젝트의 rust-version보다 높으면 (요구 버전, 프로젝트 버전)을 반환합니다.
+    fn exceeds_rust_version(&self, rule: &ModernizerRule) -> Option<(RustVersion, RustVersion)> {
+        rule.min_rust_version.zip(self.rust_version).filter(|(required, project)| required > project)
+    }
+
+    /// 규칙이 요구하는 에디션이 대상 크레이트의 edition보다 높으면 (요구 에디션, 크레이트 에디션)을 반환합니다.
+    /// edition을 알 수 없으면 rustc 기본값인 2015로 봅니다.
+    fn exceeds_edition(&self, rule: &ModernizerRule) -> Option<(Edition, Edition)> {
+        let project = self.edition.unwrap_or(Edition::E2015);
+        rule.min_edition.map(|required| (required, project)).filter(|(required, project)| required > project)
+    }
+
+    /// --rust-version/--edition이 없으면 `start`에서 가장 가까운 Cargo.toml의 rust-version/edition을 사용합니다.
+    fn resolve_manifest(&mut self, start: &Path) {
+        if self.rust_version.is_none() {
+            if let Some((version, manifest)) = discover_rust_version(start) {
+                log!("🦀 rust-version {} ({})", version, manifest.display());
+                self.rust_version = Some(version);
             }
-            
-            _ => None
-        };
+        }
+        if self.edition.is_none() {
+            if let Some((edition, manifest)) = discover_edition(start) {
+                log!("📅 edition {} ({})", edition, manifest.display());
+                self.edition = Some(edition);
+            }
+        }
+    }
 
-        if let Some(expr) = new_expr {
-            *i = expr;
+    /// 규칙이 비활성화된 이유를 반환합니다. (활성화되면 None)
+    fn disabled_reason(&self, rule: &ModernizerRule) -> Option<String> {
+        if let Some(reason) = self.deselected_reason(rule) {
+            Some(reason)
+        } else if !self.is_opted_in(rule) {
+            Some("\"enabled\": false without --enable-rule".to_string())
+        } else if let Some((required, project)) = self.exceeds_rust_version(rule) {
+            Some(format!("min_rust_version {} is above the project's rust-version {}", required, project))
+        } else if let Some((required, project)) = self.exceeds_edition(rule) {
+            Some(format!("min_edition {} is above the crate's edition {}", required, project))
+        } else if rule.severity() < self.min_severity {
+            Some(format!("severity {} is below --min-severity {}", rule.severity().as_str(), self.min_severity.as_str()))
+        } else if rule.risk() > self.max_risk {
+            Some(format!("risk {} is above --max-risk {}", rule.risk().as_str(), self.max_risk.as_str()))
+        } else {
+            None
         }
     }
 }
 
-/// ----------------------------------------------------
-/// 3. 메인 함수 및 파일 I/O
-/// ----------------------------------------------------
+#[derive(clap::Args, Debug)]
+struct ListRulesArgs {
+    #[command(flatten)]
+    rule_args: RuleArgs,
+}
 
-fn load_rules(file_path: &PathBuf) -> Result<Vec<ModernizerRule>> {
-    println!("📖 규칙 파일 로드 중: {}", file_path.display());
-    
-    let rule_json = fs::read_to_string(file_path)
-        .with_context(|| format!("Failed to read rule file: {}", file_path.display()))?;
-    
-    let rules: Vec<ModernizerRule> = serde_json::from_str(&rule_json)
-        .with_context(|| "Failed to parse modernizer_rules.json. Check JSON format.")?;
-        
-    Ok(rules)
+#[derive(clap::Args, Debug)]
+struct GenerateRulesArgs {
+    /// 생성할 규칙 파일 경로
+    #[arg(short, long, default_value = "modernizer_rules.json")]
+    output: PathBuf,
+
+    /// 이미 존재하는 파일을 덮어쓰기
+    #[arg(long, default_value_t = false)]
+    force: bool,
 }
 
-fn main() -> Result<()> {
-    // 1. CLI 인자 파싱
-    let args = Args::parse();
-    
-    // 2. 규칙 로드
-    let rules = load_rules(&args.rules_file)?; // 
+#[derive(clap::Args, Debug)]
+struct DocArgs {
+    /// 문서를 확인할 규칙 ID (예: expect_to_try)
+    #[arg(value_name = "RULE_ID")]
+    rule_id: String,
 
-    // 3. 출력 경로 결정
-    let output_path = match &args.output {
-        Some(path) => path.clone(),
-        None if args.inplace => args.input.clone(),
-        None => PathBuf::from("modernized_output.rs"),
+    /// URL을 기본 브라우저로 엶
+    #[arg(long, default_value_t = false)]
+    open: bool,
+
+    #[command(flatten)]
+    rule_args: RuleArgs,
+}
+
+#[derive(clap::Args, Debug)]
+struct ValidateRulesArgs {
+    #[command(flatten)]
+    rule_args: RuleArgs,
+}
+
+#[derive(clap::Args, Debug)]
+struct SchemaArgs {
+    /// 스키마를 저장할 파일 경로 (생략 시 stdout에 출력)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+/// CLI 옵션(--min-severity, --max-risk)에 따라 적용할 규칙만 남깁니다.
+fn filter_rules(mut rules: Vec<ModernizerRule>, args: &RunArgs) -> Vec<ModernizerRule> {
+    let total = rules.len();
+    if let Some(rule_id) = &args.explain {
+        match rules.iter().find(|rule| &rule.id == rule_id) {
+            None => log!("⚠️ --explain: 규칙 '{}'이(가) 규칙 파일에 없습니다.", rule_id),
+            Some(rule) if !args.rule_args.is_enabled(rule) => {
+                log!("⚠️ --explain: 규칙 '{}'은(는) {}로 제외되었습니다.", rule_id, args.rule_args.describe())
+            }
+            Some(_) => {}
+        }
+    }
+    // 꺼 둔 규칙은 어차피 적용되지 않으므로 켜진 규칙만 버전 때문에 건너뛴다고 알림
+    for rule in rules.iter().filter(|rule| args.rule_args.is_opted_in(rule) && args.rule_args.deselected_reason(rule).is_none()) {
+        if let Some((required, project)) = args.rule_args.exceeds_rust_version(rule) {
+            log!("[SKIP] {} {}: Rust {} 이상 필요 (프로젝트 rust-version {})", rule.severity().icon(), rule.id, required, project);
+        }
+        if let Some((required, project)) = args.rule_args.exceeds_edition(rule) {
+            log!("[SKIP] {} {}: edition {} 이상 필요 (크레이트 edition {})", rule.severity().icon(), rule.id, required, project);
+        }
+    }
+    if args.audit_file.is_some() {
+        // 감사 기록에 "규칙이 꺼져 있어 건너뜀"도 남기도록 제외된 규칙도 매칭은 하되 적용하지 않음
+        for rule in &mut rules {
+            rule.disabled_by = args.rule_args.disabled_reason(rule);
+        }
+    } else {
+        rules.retain(|rule| args.rule_args.is_enabled(rule));
+    }
+    let enabled = rules.iter().filter(|rule| rule.disabled_by.is_none()).count();
+    if enabled < total {
+        log!("🔎 {}: 규칙 {}개 중 {}개 적용", args.rule_args.describe(), total, enabled);
+    }
+    rules
+}
+
+/// `list-rules`: 로드된 규칙과 필터 적용 후 활성화 여부를 표로 출력합니다.
+fn list_rules(mut args: ListRulesArgs) -> Result<()> {
+    args.rule_args.expand_rules_file()?;
+    args.rule_args.resolve_manifest(Path::new("."));
+    let rules = args.rule_args.load_rules()?;
+
+    let header = ["id", "ast_type", "method_name", "severity", "risk", "enabled", "doc_url"].map(String::from);
+    let rows: Vec<[String; 7]> = rules
+        .iter()
+        .map(|rule| {
+            [
+                rule.id.clone(),
+                rule.ast_type.clone(),
+                rule.method_name.clone(),
+                rule.severity().as_str().to_string(),
+                rule.risk().as_str().to_string(),
+                if args.rule_args.is_enabled(rule) { "yes" } else { "no" }.to_string(),
+                rule.doc_url.clone(),
+            ]
+        })
+        .collect();
+
+    // 열 너비는 가장 긴 값 기준 (doc_url은 마지막 열이라 패딩하지 않음)
+    let mut widths = header.each_ref().map(String::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let format_row = |cells: &[String; 7]| {
+        let mut line = String::new();
+        for (cell, width) in cells.iter().zip(widths).take(6) {
+            line.push_str(&format!("{:<width$}  ", cell, width = width));
+        }
+        line.push_str(&cells[6]);
+        line
     };
-    
-    // ... (CLI 출력 유지)
-    if args.dry_run {
-        println!("\n🚨 DRY-RUN MODE: 파일 쓰기 작업을 건너뜁니다.");
+
+    log!();
+    log!("{}", format_row(&header));
+    log!("{}", format_row(&widths.map(|width| "-".repeat(width))));
+    for row in &rows {
+        log!("{}", format_row(row));
     }
-    println!("============================================");
-    println!("    Rust Legacy → Modern Migration Tool");
-    println!("============================================\n");
-    println!("📄 입력 파일: {}", args.input.display());
-    if !args.dry_run {
-        println!("📁 출력 파일: {}", output_path.display());
+
+    let enabled = rules.iter().filter(|rule| args.rule_args.is_enabled(rule)).count();
+    log!();
+    log!("📋 규칙 {}개 중 {}개 활성화 ({})", rules.len(), enabled, args.rule_args.describe());
+    Ok(())
+}
+
+/// `validate-rules`: 규칙 파일의 문제를 규칙별로 출력하고, 오류가 하나라도 있으면 ValidationError를 반환합니다.
+fn validate_rules_file(mut args: ValidateRulesArgs) -> Result<()> {
+    args.rule_args.expand_rules_file()?;
+    // 규칙 파일이 없으면 내장 기본 규칙을 검사
+    let rules_files = args.rule_args.user_rules_files();
+    let (files, rules): (Vec<String>, Vec<ModernizerRule>) = match rules_files.is_empty() {
+        true => default_rules()?.into_iter().map(|rule| ("(built-in)".to_string(), rule)).unzip(),
+        false => load_rules_by_file(&rules_files, args.rule_args.rules_format)?
+            .into_iter()
+            .map(|(path, rule)| (path.display().to_string(), rule))
+            .unzip(),
+    };
+
+    let issues = validate_rules(&rules);
+    log!();
+    for issue in &issues {
+        log!("{} {}: `{}`: {}", issue.severity.icon(), files[issue.index], issue.rule_id, issue.message);
     }
 
-    // 4. 파일 읽기 및 AST 생성
-    let source_code = fs::read_to_string(&args.input)
-        .with_context(|| format!("Failed to read input file: {}", args.input.display()))?;
-    
-    let mut ast = syn::parse_file(&source_code)
-        .with_context(|| format!("Failed to parse Rust code as AST: {}", args.input.display()))?;
-    
-    // 5. AST 변환 적용
-    println!("\n⚙️ Modernizing code using AST traversal...");
-    let mut modernizer = Modernizer::new(rules);
-    modernizer.visit_file_mut(&mut ast); // AST의 루트 노드(File)부터 변환기 적용
-
-    // 6. 변경 사항 확인 및 보고서 출력
-    if !modernizer.changed {
-        println!("\nℹ️ 코드 변경 사항이 감지되지 않았습니다.");
-        return Ok(());
+    let errors = issues.iter().filter(|issue| issue.severity == Severity::Error).count();
+    let warnings = issues.len() - errors;
+    if !issues.is_empty() {
+        log!();
     }
-    
-    println!("\n📊 변환 보고서:");
-    for (id, count) in modernizer.counters {
-        // 규칙 ID를 기반으로 출력
-        println!("  - {} 건 ({})", count, id);
+    log!("📋 규칙 {}개 검사: 오류 {}개, 경고 {}개", rules.len(), errors, warnings);
+    if errors > 0 {
+        return Err(ValidationError(format!("{} error(s) in {}", errors, args.rule_args.rules_files_display())).into());
     }
+    Ok(())
+}
+
+/// `doc`: 규칙의 doc_url을 stdout에 출력하고, --open이면 브라우저로 엽니다.
+fn doc(mut args: DocArgs) -> Result<()> {
+    args.rule_args.expand_rules_file()?;
+    let rules = args.rule_args.load_rules()?;
+    let Some(rule) = rules.iter().find(|rule| rule.id == args.rule_id) else {
+        let ids: Vec<&str> = rules.iter().map(|rule| rule.id.as_str()).collect();
+        bail!("Rule `{}` not found in {} (available: {})", args.rule_id, args.rule_args.rules_files_display(), ids.join(", "));
+    };
 
+    log!("{} {} ({}, risk {})", rule.severity().icon(), rule.id, rule.severity().as_str(), rule.risk().as_str());
+    if let Some(note) = &rule.review_note {
+        log!("  {}", note);
+    }
+    println!("{}", rule.doc_url);
 
-    // 7. AST를 코드 문자열로 재구성 및 8. 파일 I/O
-    let modernized_code = prettyplease::unparse(&ast); 
+    if args.open {
+        open_in_browser(&rule.doc_url)?;
+    }
+    Ok(())
+}
 
-    if args.dry_run {
-        println!("\n📄 Dry Run 결과 코드 (파일 저장 안 함):");
-        println!("--------------------------------------------");
-        println!("{}", modernized_code);
-        println!("--------------------------------------------");
+/// 운영체제 기본 브라우저로 URL을 엽니다.
+fn open_in_browser(url: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else if cfg!(target_os = "macos") {
+        process::Command::new("open")
     } else {
-        fs::write(&output_path, modernized_code)
-            .with_context(|| format!("Failed to write output file: {}", output_path.display()))?;
+        process::Command::new("xdg-open")
+    };
+    let status = command
+        .arg(url)
+        .status()
+        .with_context(|| format!("Failed to launch a browser for {}", url))?;
+    if !status.success() {
+        bail!("Browser launcher exited with {} for {}", status, url);
+    }
+    Ok(())
+}
+
+/// `schema`: ModernizerRule에서 생성한 규칙 파일 JSON Schema를 출력합니다.
+fn schema(args: SchemaArgs) -> Result<()> {
+    let schema = schemars::schema_for!(RulesFile);
+    let schema_json = serde_json::to_string_pretty(&schema)
+        .with_context(|| "Failed to serialize JSON schema.")?;
 
-        println!("\n✅ 변환 완료! 파일 저장됨.");
-        println!("→ {}", output_path.display());
+    match &args.output {
+        Some(path) => {
+            fs::write(path, schema_json + "\n")
+                .with_context(|| format!("Failed to write schema file: {}", path.display()))?;
+            log!("✅ 규칙 파일 JSON Schema를 저장했습니다.");
+            log!("→ {}", path.display());
+        }
+        None => println!("{}", schema_json),
     }
-    
     Ok(())
 }
+
+/// `generate-rules`: 기본 규칙을 모든 필드가 채워진 JSON으로 저장합니다.
+fn generate_rules(args: GenerateRulesArgs) -> Result<()> {
+    if args.output.exists() && !args.force {
+        bail!("Rule file already exists: {} (use --force to overwrite)", args.output.display());
+    }
+
+    let rules = default_rules()?;
+    let rule_json = serde_json::to_string_pretty(&rules)
+        .with_context(|| "Failed to serialize default rules.")?;
+
+    fs::write(&args.output, rule_json + "\n")
+        .with_context(|| format!("Failed to write rule file: {}", args.output.display()))?;
+
+    log!("✅ 기본 규칙 {}개를 저장했습니다.", rules.len());
+    log!("→ {}", args.output.display());
+    Ok(())
+}
+
+/// ----------------------------------------------------
+/// 2. 메인 함수 및 파일 I/O
+/// ----------------------------------------------------
+fn main() -> ExitCode {
+    modernizer_core::set_log_sink(Some(print_log));
+
+    // 1. CLI 인자 파싱 (서브커맨드가 없으면 run으로 처리)
+    //    clap의 사용법 오류 코드(2)는 EXIT_CHANGED와 겹치므로 EXIT_USAGE로 바꿉니다.
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(err) => {
+            let _ = err.print();
+            return ExitCode::from(if err.use_stderr() { EXIT_USAGE } else { EXIT_CLEAN });
+        }
+    };
+
+    let result = match cli.command {
+        Some(Command::Run(args)) => run(*args),
+        Some(Command::ListRules(args)) => list_rules(args).map(|()| false),
+        Some(Command::GenerateRules(args)) => generate_rules(args).map(|()| false),
+        Some(Command::Doc(args)) => doc(args).map(|()| false),
+        Some(Command::Schema(args)) => schema(args).map(|()| false),
+        Some(Command::ValidateRules(args)) => validate_rules_file(args).map(|()| false),
+        // 서브커맨드가 없으면 run으로 처리 (INPUT 누락은 run에서 사용법 오류로 보고)
+        None => run(cli.run),
+    };
+
+    match result {
+        Ok(false) => ExitCode::from(EXIT_CLEAN),
+        Ok(true) => ExitCode::from(EXIT_CHANGED),
+        Err(err) => {
+            eprintln!("Error: {:?}", err);
+            ExitCode::from(exit_code_for(&err))
+        }
+    }
+}
+
+/// 오류 원인 체인을 따라 종료 코드를 결정합니다.
+fn exit_code_for(err: &anyhow::Error) -> u8 {
+    for cause in err.chain() {
+        if let Some(batch) = cause.downcast_ref::<BatchFailure>() {
+            return batch.exit_code;
+        }
+        if cause.is::<std::io::Error>() {
+            return EXIT_IO;
+        }
+        if cause.is::<UsageError>() {
+            return EXIT_USAGE;
+        }
+        if cause.is::<syn::Error>() || cause.is::<serde_json::Error>() || cause.is::<ValidationError>() {
+            return EXIT_INVALID;
+        }
+    }
+    EXIT_FAILURE
+}
+
+/// `run`: 규칙을 로드하여 입력 파일을 변환합니다. 변경(dry-run이면 변경 예정)이 있으면 true를 반환합니다.
+fn run(mut args: RunArgs) -> Result<bool> {
+    // JSON 보고서는 stdout을 독점하므로 진행 로그를 stderr로 보냅니다.
+    LOG_TO_STDERR.store(args.report_format != ReportFormat::Text, Ordering::Relaxed);
+    QUIET.store(args.quiet, Ordering::Relaxed);
+    args.rule_args.expand_rules_file()?;
+    let members = match args.workspace {
+        true => {
+            let root = match args.inputs.as_slice() {
+                [] => PathBuf::from("."),
+                [root] => root.clone(),
+                _ => bail!("--workspace takes a single workspace directory"),
+            };
+            let members = workspace_members(&root)?;
+            args.inputs = members.iter().flat_map(WorkspaceMember::target_dirs).collect();
+            args.rule_args.resolve_manifest(&root);
+            if args.inputs.is_empty() {
+                bail!("No src/, tests/, examples/ or benches/ directories found in workspace members");
+            }
+            members
+        }
+        // `cargo modernize`에서 INPUT을 생략하면 현재 패키지의 소스를 변환
+        false if args.inputs.is_empty() && std::env::var_os(CARGO_SUBCOMMAND_ENV).is_some() => {
+            let package = current_package()?;
+            args.inputs = package.target_dirs();
+            args.rule_args.resolve_manifest(&package.dir);
+            vec![package]
+        }
+        false if args.inputs.is_empty() => {
+            return Err(UsageError("the following required arguments were not provided: <INPUT>...".to_string()).into());
+        }
+        false => {
+            args.rule_args.resolve_manifest(&args.inputs[0]);
+            Vec::new()
+        }
+    };
+
+    if args.watch {
+        return watch(args).map(|()| false);
+    }
+    // --patch는 원본 파일을 쓰지 않고 패치 파일만 만들고, --only-count/--check는 아무 파일도 쓰지 않음
+    if args.patch.is_some() || args.only_count || args.diff || args.check {
+        args.dry_run = true;
+    }
+    if args.interactive {
+        if !std::io::stdin().is_terminal() {
+            return Err(UsageError("--interactive requires a terminal on standard input".to_string()).into());
+        }
+        // 질문이 파일 순서대로 나오도록 순차 처리하고, 이전 결과를 재사용하지 않음
+        args.threads = Some(1);
+        args.cache_dir = None;
+    }
+    // --baseline: 기준선을 기록할 때는 아무 파일도 바꾸지 않음
+    if let Some(baseline) = &args.baseline {
+        match args.use_baseline {
+            true => args.baseline_findings = Some(Arc::new(Baseline::load(baseline)?)),
+            false => args.dry_run = true,
+        }
+    }
+
+    if args.print_ast {
+        let files = collect_inputs(&args.inputs, &FileFilter::new(&args)?)?;
+        return print_ast(&files).map(|()| false);
+    }
+
+    // 2. 규칙 로드 (내장 기본 규칙 + 규칙 파일)
+    let rules = args.rule_args.load_rules()?;
+    let deselected_rules = args.rule_args.deselected_rules(&rules);
+    let rules = filter_rules(rules, &args);
+    check_handlers(&rules, &args)?;
+
+    let filter = FileFilter::new(&args)?;
+    let mut files = collect_inputs(&args.inputs, &filter)?;
+    if files.is_empty() {
+        bail!("No .rs files found under the given input(s)");
+    }
+    if let Some(git_ref) = &args.since {
+        let changed = changed_since(git_ref, &args.inputs)?;
+        let total = files.len();
+        files.retain(|file| file.canonicalize().is_ok_and(|file| changed.contains(&file)));
+        log!("🔀 --since {}: .rs 파일 {}개 중 {}개가 변경됨", git_ref, total, files.len());
+        if files.is_empty() {
+            log!("\nℹ️ {} 이후 변경된 .rs 파일이 없습니다.", git_ref);
+            return Ok(false);
+        }
+    }
+
+    let batch = files.len() > 1 || args.inputs.iter().any(|input| input.is_dir());
+    if batch && !args.force {
+        files.retain(|file| match fs::metadata(file) {
+            Ok(metadata) if metadata.len() > args.max_file_size => {
+                log!(
+                    "[SKIP] {}: {} bytes > --max-file-size {} (--force로 처리 가능)",
+                    file.display(), metadata.len(), args.max_file_size
+                );
+                false
+            }
+            _ => true,
+        });
+        if files.is_empty() {
+            log!("\nℹ️ 처리할 파일이 없습니다. (모두 --max-file-size 초과)");
+            return Ok(false);
+        }
+    }
+
+    if batch {
+        if args.output.is_some() && output_root(&args).is_none() {
+            bail!("--output with multiple inputs requires a single directory input (use --inplace or --dry-run)");
+        }
+        if !args.inplace && !args.dry_run && args.output.is_none() {
+            bail!("Multiple inputs require --inplace, --output DIR, --dry-run, --patch or --only-count");
+        }
+    }
+
+    let mut report = Report::new(process_files(&files, &rules, &args)?, args.dry_run);
+    report.summarize_crates(&members);
+    report.skipped_rules = deselected_rules;
+    emit_report(&report, &rules, &args)?;
+    if let (Some(path), false) = (&args.baseline, args.use_baseline) {
+        let baseline = Baseline::from_reports(&report.files);
+        baseline.save(path)?;
+        log!("\n📌 기준선 저장: 변환 {}건 → {} (이후 --use-baseline으로 새 변환만 보고)", baseline.len(), path.display());
+        return Ok(false);
+    }
+    if args.use_baseline {
+        let baselined: u32 = report.files.iter().map(|file| file.baselined).sum();
+        let new: usize = report.files.iter().map(|file| file.changes.len()).sum();
+        log!("\n📌 --use-baseline: 기준선에 있는 변환 {}건 제외, 새 변환 {}건", baselined, new);
+    }
+    if args.error_on_unused_rules {
+        check_unused_rules(&rules, &report)?;
+    }
+    if args.check {
+        match report.files.iter().filter(|file| !file.changes.is_empty()).count() {
+            0 => log!("✅ --check: 변환할 코드가 없습니다."),
+            changed => log!("❌ --check: 파일 {}개에 변환할 코드가 있습니다. (--inplace로 적용)", changed),
+        }
+    }
+    match args.fail_on {
+        Some(threshold) => {
+            let findings = report.findings_at(threshold);
+            if findings > 0 {
+                log!("🚫 --fail-on {}: 심각도 {} 이상 변환 {}건", threshold.as_str(), threshold.as_str(), findings);
+            }
+            Ok(findings > 0)
+        }
+        None => Ok(report.changed()),
+    }
+}
+
+/// 활성 규칙의 `handler`가 모두 --plugins-dir에 있는지 확인합니다. (파일을 처리하기 전에 설정 오류를 보고)
+fn check_handlers(rules: &[ModernizerRule], args: &RunArgs) -> Result<()> {
+    let handlers = plugin_handlers(args)?;
+    for rule in rules.iter().filter(|rule| rule.disabled_by.is_none()) {
+        if let Some(name) = rule.handler.as_deref().filter(|name| !handlers.contains(name)) {
+            return Err(UsageError(format!(
+                "rule `{}` uses handler `{}`, which is not in --plugins-dir{}",
+                rule.id,
+                name,
+                args.plugins_dir.as_ref().map(|dir| format!(" ({})", dir.display())).unwrap_or_default()
+            ))
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// --plugins-dir의 실행 파일 처리기 목록 (지정하지 않으면 빈 목록)
+fn plugin_handlers(args: &RunArgs) -> Result<HandlerRegistry> {
+    let mut handlers = HandlerRegistry::default();
+    if let Some(dir) = &args.plugins_dir {
+        handlers.load_dir(dir)?;
+    }
+    Ok(handlers)
+}
+
+/// --error-on-unused-rules: 활성 규칙 중 모든 파일에서 적용 건수가 0인 규칙이 있으면 실패합니다.
+fn check_unused_rules(rules: &[ModernizerRule], report: &Report) -> Result<()> {
+    let unused = report.unused_rules(rules);
+    if unused.is_empty() {
+        return Ok(());
+    }
+    log!("\n⚠️ 한 번도 적용되지 않은 규칙 {}개:", unused.len());
+    for id in &unused {
+        log!("  - {}", id);
+    }
+    Err(ValidationError(format!("{} enabled rule(s) matched nothing: {}", unused.len(), unused.join(", "))).into())
+}
+
+/// `--print-ast`: 각 파일의 구문 트리를 출력합니다. (여러 파일이면 파일 경로 머리말을 붙임)
+fn print_ast(files: &[PathBuf]) -> Result<()> {
+    if files.is_empty() {
+        bail!("No .rs files found under the given input(s)");
+    }
+    for file in files.iter().filter(|file| !is_markdown(file)) {
+        let source_code = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read input file: {}", file.display()))?;
+        let ast = syn::parse_file(&source_code)
+            .with_context(|| format!("Failed to parse Rust code as AST: {}", file.display()))?;
+        if files.len() > 1 {
+            println!("// ===== {} =====", file.display());
+        }
+        println!("{:#?}", ast);
+    }
+    Ok(())
+}
+
+/// `start`(파일 또는 디렉터리)에서 상위로 올라가며 가장 가까운 Cargo.toml의 `[package] rust-version`을 찾습니다.
+/// `rust-version.workspace = true`이면 상위 워크스페이스의 `[workspace.package] rust-version`을 사용합니다.
+fn discover_rust_version(start: &Path) -> Option<(RustVersion, PathBuf)> {
+    // 가장 가까운 패키지에 rust-version이 없으면 제한 없음
+    let (version, manifest) = discover_package_value(start, "rust-version")?;
+    Some((version?.parse().ok()?, manifest))
+}
+
+/// 가장 가까운 Cargo.toml 패키지의 `edition`을 찾습니다. 패키지에 edition이 없으면 Cargo 기본값인 2015입니다.
+fn discover_edition(start: &Path) -> Option<(Edition, PathBuf)> {
+    let (edition, manifest) = discover_package_value(start, "edition")?;
+    let edition = match edition {
+        Some(edition) => edition.parse().ok()?,
+        None => Edition::E2015,
+    };
+    Some((edition, manifest))
+}
+
+/// `start`에서 가장 가까운 패키지 Cargo.toml의 `[package] key` 값을 찾습니다.
+/// `key.workspace = true`이면 상위 워크스페이스의 `[workspace.package] key`를 사용합니다.
+/// 패키지를 찾지 못하면 None, 패키지에 값이 없으면 (None, 패키지 Cargo.toml)을 반환합니다.
+fn discover_package_value(start: &Path, key: &str) -> Option<(Option<String>, PathBuf)> {
+    let start = fs::canonicalize(start).ok()?;
+    let mut inherit_from_workspace = false;
+    for dir in start.ancestors().filter(|dir| dir.is_dir()) {
+        let manifest = dir.join("Cargo.toml");
+        let Ok(contents) = fs::read_to_string(&manifest) else { continue };
+        if inherit_from_workspace {
+            if let Some(value) = manifest_value(&contents, "workspace.package", key) {
+                return Some((Some(value), manifest));
+            }
+            continue;
+        }
+        match manifest_value(&contents, "package", key) {
+            // `key = { workspace = true }`
+            Some(value) if value.contains("workspace") => inherit_from_workspace = true,
+            Some(value) => return Some((Some(value), manifest)),
+            None if manifest_value(&contents, "package", &format!("{}.workspace", key)).as_deref() == Some("true") => {
+                inherit_from_workspace = true;
+            }
+            None if contents.lines().any(|line| line.trim() == "[package]") => return Some((None, manifest)),
+            None => {}
+        }
+    }
+    None
+}
+
+/// Cargo.toml에서 `[section]`의 `key = value` 값을 읽습니다. (따옴표 제거, 한 줄 값만 지원하는 단순 파서)
+fn manifest_value(contents: &str, section: &str, key: &str) -> Option<String> {
+    let mut current = "";
+    for line in contents.lines() {
+        let line = line.split(" #").next().unwrap_or_default().trim();
+        if let Some(header) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            current = header.trim();
+            continue;
+        }
+        let Some((name, value)) = line.split_once('=') else { continue };
+        if current == section && name.trim() == key {
+            return Some(value.trim().trim_matches('"').to_string());
+        }
+    }
+    None
+}
+
+/// `--since`: 입력 경로가 속한 git 저장소에서 `<git_ref>...HEAD` 사이에 바뀐 .rs 파일의 절대 경로를 구합니다.
+/// (삭제된 파일 제외)
+fn changed_since(git_ref: &str, inputs: &[PathBuf]) -> Result<BTreeSet<PathBuf>> {
+    let mut repo_roots = BTreeSet::new();
+    for input in inputs {
+        let dir = if input.is_dir() { input.as_path() } else { input.parent().unwrap_or(Path::new("")) };
+        let dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+        let root = git(dir, &["rev-parse", "--show-toplevel"])
+            .with_context(|| format!("Input is not inside a git repository: {}", input.display()))?;
+        repo_roots.insert(PathBuf::from(root.trim_end()));
+    }
+
+    let mut changed = BTreeSet::new();
+    for root in &repo_roots {
+        git(root, &["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", git_ref)])
+            .with_context(|| format!("Git ref `{}` does not resolve in {}", git_ref, root.display()))?;
+        let names = git(root, &["diff", "--name-only", "--diff-filter=d", &format!("{}...HEAD", git_ref)])?;
+        for name in names.lines().filter(|name| name.ends_with(".rs")) {
+            if let Ok(path) = root.join(name).canonicalize() {
+                changed.insert(path);
+            }
+        }
+    }
+    Ok(changed)
+}
+
+/// Cargo 워크스페이스 멤버 크레이트
+struct WorkspaceMember {
+    name: String,
+    edition: String,
+    /// 멤버 Cargo.toml이 있는 디렉터리
+    dir: PathBuf,
+    /// 멤버 Cargo.toml (정규화된 절대 경로)
+    manifest: PathBuf,
+    /// `cargo metadata`의 타깃 루트 파일 (lib.rs, main.rs, build.rs, 사용자 지정 `path` 등)
+    targets: Vec<PathBuf>,
+}
+
+impl WorkspaceMember {
+    /// 변환 대상 경로: 표준 디렉터리 중 존재하는 것과, 그 밖에 있는 타깃 루트 파일 (build.rs 등)
+    fn target_dirs(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = ["src", "tests", "examples", "benches"]
+            .iter()
+            .map(|name| self.dir.join(name))
+            .filter(|dir| dir.is_dir())
+            .collect();
+        let extra: Vec<PathBuf> = self
+            .targets
+            .iter()
+            .filter(|target| !paths.iter().any(|dir| target.starts_with(dir)))
+            .cloned()
+            .collect();
+        paths.extend(extra);
+        paths
+    }
+}
+
+/// `--workspace`: `cargo metadata`로 `root`의 워크스페이스 멤버를 찾습니다.
+fn workspace_members(root: &Path) -> Result<Vec<WorkspaceMember>> {
+    let members = cargo_metadata(root)?;
+    log!("📦 --workspace {}: 멤버 크레이트 {}개", root.display(), members.len());
+    Ok(members)
+}
+
+/// `cargo modernize`에서 INPUT을 생략한 경우: 현재 디렉터리에서 상위로 올라가며 가장 가까운 패키지를 찾습니다.
+fn current_package() -> Result<WorkspaceMember> {
+    let cwd = std::env::current_dir().with_context(|| "Failed to read current directory")?;
+    let root = cwd
+        .ancestors()
+        .find(|dir| fs::read_to_string(dir.join("Cargo.toml")).is_ok_and(|contents| contents.lines().any(|line| line.trim() == "[package]")))
+        .with_context(|| format!("No Cargo package found in {} or its parents", cwd.display()))?;
+    // 현재 디렉터리가 패키지 루트이면 보고서 경로가 짧도록 `.` 기준으로 조회
+    let root = if root == cwd { PathBuf::from(".") } else { root.to_path_buf() };
+    let manifest = fs::canonicalize(root.join("Cargo.toml"))?;
+    let package = cargo_metadata(&root)?
+        .into_iter()
+        .find(|member| member.manifest == manifest)
+        .with_context(|| format!("`cargo metadata` did not list the package at {}", manifest.display()))?;
+    log!("📦 cargo modernize: 패키지 {} (edition {})", package.name, package.edition);
+    Ok(package)
+}
+
+/// `cargo metadata --no-deps`로 `root` 워크스페이스의 패키지를 이름 순으로 구합니다.
+/// (경로는 `root` 기준 상대 경로로 바꿔 로그와 보고서 경로를 짧게 유지)
+fn cargo_metadata(root: &Path) -> Result<Vec<WorkspaceMember>> {
+    #[derive(Deserialize)]
+    struct Metadata {
+        packages: Vec<Package>,
+    }
+    #[derive(Deserialize)]
+    struct Package {
+        name: String,
+        edition: String,
+        manifest_path: PathBuf,
+        targets: Vec<Target>,
+    }
+    #[derive(Deserialize)]
+    struct Target {
+        src_path: PathBuf,
+    }
+
+    let output = process::Command::new("cargo")
+        .args(["metadata", "--no-deps", "--format-version", "1", "--manifest-path"])
+        .arg(root.join("Cargo.toml"))
+        .output()
+        .with_context(|| "Failed to run `cargo metadata` (is cargo installed and on PATH?)")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("`cargo metadata` failed for {}: {}", root.display(), stderr.trim());
+    }
+    let metadata: Metadata =
+        serde_json::from_slice(&output.stdout).with_context(|| "Failed to parse `cargo metadata` output.")?;
+
+    let canonical_root = fs::canonicalize(root).with_context(|| format!("Failed to resolve {}", root.display()))?;
+    let relative = |path: &Path| match path.strip_prefix(&canonical_root) {
+        Ok(relative) => root.join(relative),
+        Err(_) => path.to_path_buf(),
+    };
+    let mut members: Vec<WorkspaceMember> = metadata
+        .packages
+        .into_iter()
+        .map(|package| WorkspaceMember {
+            dir: relative(package.manifest_path.parent().unwrap_or(Path::new(""))),
+            targets: package.targets.iter().map(|target| relative(&target.src_path)).collect(),
+            name: package.name,
+            edition: package.edition,
+            manifest: package.manifest_path,
+        })
+        .collect();
+    members.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(members)
+}
+
+/// `dir`에서 git 명령을 실행하고 stdout을 반환합니다. (실패하면 stderr를 오류 메시지로 사용)
+fn git(dir: &Path, git_args: &[&str]) -> Result<String> {
+    let output = process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(git_args)
+        .output()
+        .with_context(|| "Failed to run `git` (is it installed and on PATH?)")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = if stderr.trim().is_empty() { output.status.to_string() } else { stderr.trim().to_string() };
+        bail!("`git {}` failed: {}", git_args.join(" "), reason);
+    }
+    String::from_utf8(output.stdout).with_context(|| "`git` output is not valid UTF-8")
+}
+
+/// 디렉터리 입력에 적용할 --include/--exclude/.modernizerignore 필터
+struct FileFilter {
+    include: Option<GlobSet>,
+    exclude: IgnoreRules,
+    /// 입력 디렉터리와 상위 디렉터리의 .modernizerignore (먼 것부터)
+    ignore_files: Vec<IgnoreFile>,
+}
+
+impl FileFilter {
+    fn new(args: &RunArgs) -> Result<Self> {
+        let build = |patterns: &[String]| -> Result<GlobSet> {
+            let mut builder = GlobSetBuilder::new();
+            for pattern in patterns {
+                // `*`는 디렉터리 구분자를 넘지 않음 (`**`만 여러 단계와 일치)
+                let glob = GlobBuilder::new(pattern)
+                    .literal_separator(true)
+                    .build()
+                    .with_context(|| format!("Invalid glob pattern: {}", pattern))?;
+                builder.add(glob);
+            }
+            builder.build().with_context(|| "Failed to build glob set.")
+        };
+
+        // 같은 파일은 입력에 가장 가까운 경로로 한 번만 사용 (`.`과 빈 경로처럼 같은 디렉터리를 가리키는 경우)
+        let mut ignore_files: Vec<IgnoreFile> = Vec::new();
+        for input in args.inputs.iter().filter(|input| input.is_dir()) {
+            for file in IgnoreFile::discover(input)? {
+                ignore_files.retain(|known| !is_same_path(&known.path, &file.path));
+                ignore_files.push(file);
+            }
+        }
+        for file in &ignore_files {
+            log!("🙈 {} 적용: {}", IGNORE_FILE, file.path.display());
+        }
+
+        Ok(FileFilter {
+            include: if args.include.is_empty() { None } else { Some(build(&args.include)?) },
+            exclude: IgnoreRules::new(args.exclude.iter().map(String::as_str))?,
+            ignore_files,
+        })
+    }
+
+    /// --exclude와 .modernizerignore로 제외된 경로인지 판정합니다. (`path`: 전체 경로, `relative`: 입력 디렉터리 기준)
+    fn is_ignored(&self, path: &Path, relative: &Path, is_dir: bool) -> bool {
+        self.exclude
+            .decide(relative, is_dir)
+            .or_else(|| self.ignore_files.iter().rev().find_map(|file| file.decide(path, is_dir)))
+            .unwrap_or(false)
+    }
+
+    /// 디렉터리(`relative`: 입력 디렉터리 기준 상대 경로)를 탐색할지 판정합니다.
+    fn accepts_dir(&self, path: &Path, relative: &Path) -> bool {
+        !is_default_excluded(relative) && !self.is_ignored(path, relative, true)
+    }
+
+    /// 파일을 처리할지 판정합니다.
+    /// Markdown 파일은 --include 패턴에 명시적으로 일치할 때만 처리합니다.
+    fn accepts_file(&self, path: &Path, relative: &Path) -> bool {
+        let markdown = is_markdown(relative)
+            && self.include.as_ref().is_some_and(|include| include.is_match(relative));
+        (relative.extension().is_some_and(|ext| ext == "rs") || markdown)
+            && !is_default_excluded(relative)
+            && !self.is_ignored(path, relative, false)
+            && self.include.as_ref().is_none_or(|include| include.is_match(relative))
+    }
+}
+
+/// 기본 제외 경로: `target` 디렉터리와 `.`으로 시작하는 경로 요소 (.git 등)
+fn is_default_excluded(relative: &Path) -> bool {
+    relative.components().any(|component| {
+        let name = component.as_os_str().to_string_lossy();
+        name == "target" || (name.starts_with('.') && name != "." && name != "..")
+    })
+}
+
+/// 입력 경로 목록을 처리할 파일 목록으로 펼칩니다.
+/// 파일은 그대로 사용하고, 디렉터리는 필터를 통과한 .rs 파일을 경로 순으로 재귀 수집합니다.
+fn collect_inputs(inputs: &[PathBuf], filter: &FileFilter) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for input in inputs {
+        if input.is_dir() {
+            let start = files.len();
+            collect_dir(input, input, filter, &mut files)?;
+            files[start..].sort();
+        } else {
+            files.push(input.clone());
+        }
+    }
+    Ok(files)
+}
+
+fn collect_dir(root: &Path, dir: &Path, filter: &FileFilter, files: &mut Vec<PathBuf>) -> Result<()> {
+    let entries = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory: {}", dir.display()))?;
+    for entry in entries {
+        let path = entry
+            .with_context(|| format!("Failed to read directory: {}", dir.display()))?
+            .path();
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        if path.is_dir() {
+            if filter.accepts_dir(&path, relative) {
+                collect_dir(root, &path, filter, files)?;
+            }
+        } else if filter.accepts_file(&path, relative) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// 여러 파일을 --threads 개의 스레드로 병렬 처리합니다. (1이면 입력 순서대로 순차 처리)
+/// 보고서는 스레드 수와 관계없이 입력 순서대로 반환됩니다.
+/// 모든 파일의 변환과 검증이 끝난 뒤에만 파일을 쓰므로, 한 파일이라도 실패하면 아무 파일도 바뀌지 않습니다.
+fn process_files(files: &[PathBuf], rules: &[ModernizerRule], args: &RunArgs) -> Result<Vec<FileReport>> {
+    let cache = args.cache_dir.as_deref().map(|dir| Cache::open(dir, rules, args)).transpose()?;
+    let progress = start_progress(files.len(), args);
+    let process = |path: &PathBuf| {
+        if let Some(bar) = &progress {
+            bar.set_message(path.display().to_string());
+        }
+        // 감사 기록, diff/패치, --explain 출력은 파일마다 새로 만들어야 하므로 캐시를 거치지 않음
+        let bypass = args.audit_file.is_some() || args.diff_file.is_some() || args.patch.is_some() || args.diff || args.explain.is_some();
+        let result = match &cache {
+            Some(cache) if !bypass => cache.transform_file(path, rules, args),
+            _ => transform_file(path, rules.to_vec(), args, args.dry_run),
+        };
+        if let Some(bar) = &progress {
+            bar.inc(1);
+        }
+        result
+    };
+
+    let results: Vec<Result<PendingOutput>> = match args.threads {
+        Some(1) => files.iter().map(process).collect(),
+        threads => {
+            // num_threads(0)은 rayon 기본값(논리 CPU 수)
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads.map_or(0, usize::from))
+                .build()
+                .with_context(|| "Failed to build thread pool.")?;
+            pool.install(|| files.par_iter().map(process).collect())
+        }
+    };
+    finish_progress();
+
+    // 1단계: 변환/검증 결과 확인. 여러 파일이면 실패한 파일을 모두 보고한 뒤 중단
+    let outputs: Vec<PendingOutput> = if files.len() == 1 {
+        results.into_iter().collect::<Result<_>>()?
+    } else {
+        let mut outputs = Vec::with_capacity(results.len());
+        let mut failure = BatchFailure { failed: 0, total: files.len(), exit_code: EXIT_FAILURE };
+        for result in results {
+            match result {
+                Ok(output) => outputs.push(output),
+                Err(err) => {
+                    eprintln!("\n❌ {:#}", err);
+                    failure.failed += 1;
+                    failure.exit_code = failure.exit_code.max(exit_code_for(&err));
+                }
+            }
+        }
+        if failure.failed > 0 {
+            return Err(failure.into());
+        }
+        outputs
+    };
+
+    // 2단계: 모든 파일이 통과했을 때만 쓰기 (--verify: 쓰기 전후의 컴파일 오류를 비교해 새 오류가 생긴 파일은 되돌림)
+    let mut outputs = outputs;
+    let checks = if args.verify { verify_baseline(&outputs)? } else { Vec::new() };
+    write_outputs(&outputs)?;
+    if args.verify {
+        verify_outputs(checks, &mut outputs)?;
+    }
+    if let Some(audit_file) = &args.audit_file {
+        write_audit(audit_file, &outputs)?;
+    }
+    if let Some(diff_file) = &args.diff_file {
+        write_diff(diff_file, &outputs)?;
+    }
+    if let Some(patch_file) = &args.patch {
+        write_patch(patch_file, &outputs)?;
+    }
+    if let Some(cache) = &cache {
+        for output in &outputs {
+            cache.store(output);
+        }
+    }
+    Ok(outputs.into_iter().map(|output| output.report).collect())
+}
+
+/// --audit-file: 입력 순서대로 파일별 판정을 JSONL로 저장합니다.
+fn write_audit(audit_file: &Path, outputs: &[PendingOutput]) -> Result<()> {
+    let mut lines = String::new();
+    for entry in outputs.iter().flat_map(|output| &output.audit) {
+        lines.push_str(&serde_json::to_string(entry).with_context(|| "Failed to serialize audit entry.")?);
+        lines.push('\n');
+    }
+    fs::write(audit_file, lines)
+        .with_context(|| format!("Failed to write audit file: {}", audit_file.display()))?;
+    let count = outputs.iter().map(|output| output.audit.len()).sum::<usize>();
+    log!("🧾 감사 기록 {}건 저장: {}", count, audit_file.display());
+    Ok(())
+}
+
+/// --diff-file: 입력 순서대로 파일별 diff를 이어 붙여 저장합니다. (변경이 없으면 빈 파일)
+/// 파일 구분이 쉽도록 머리말의 탭 뒤에 (original)/(modernized)를 붙입니다.
+fn write_diff(diff_file: &Path, outputs: &[PendingOutput]) -> Result<()> {
+    let mut diff = String::new();
+    for output in outputs.iter().filter(|output| !output.diff.is_empty()) {
+        diff.push_str(&diff_header(&output.report.path));
+        diff.push_str(&output.diff);
+    }
+    fs::write(diff_file, diff)
+        .with_context(|| format!("Failed to write diff file: {}", diff_file.display()))?;
+    let count = outputs.iter().filter(|output| !output.diff.is_empty()).count();
+    log!("📝 파일 {}개의 diff 저장: {}", count, diff_file.display());
+    Ok(())
+}
+
+/// `diff -u` 형식의 파일 머리말 (--diff, --diff-file)
+fn diff_header(path: &Path) -> String {
+    format!("--- {}\t(original)\n+++ {}\t(modernized)\n", path.display(), path.display())
+}
+
+/// --patch: 입력 순서대로 파일별 diff를 `git apply`용 머리말과 함께 저장합니다.
+/// 경로는 현재 디렉터리 기준 상대 경로이므로 저장소 루트에서 실행해야 그대로 적용됩니다.
+fn write_patch(patch_file: &Path, outputs: &[PendingOutput]) -> Result<()> {
+    let current_dir = std::env::current_dir().with_context(|| "Failed to read current directory.")?;
+    let mut patch = String::new();
+    for output in outputs.iter().filter(|output| !output.diff.is_empty()) {
+        let path = &output.report.path;
+        let relative = if path.is_absolute() { path.strip_prefix(&current_dir).ok() } else { Some(path.as_path()) };
+        let relative = relative
+            .map(|relative| relative.strip_prefix(".").unwrap_or(relative))
+            .filter(|relative| !relative.components().any(|component| component == std::path::Component::ParentDir))
+            .with_context(|| format!("--patch requires inputs under the current directory: {}", path.display()))?;
+        // git 패치 경로는 항상 `/` 구분자
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        patch.push_str(&format!("diff --git a/{0} b/{0}\n--- a/{0}\n+++ b/{0}\n", relative));
+        patch.push_str(&output.diff);
+    }
+    fs::write(patch_file, patch)
+        .with_context(|| format!("Failed to write patch file: {}", patch_file.display()))?;
+    let count = outputs.iter().filter(|output| !output.diff.is_empty()).count();
+    log!("🩹 파일 {}개의 패치 저장: {} (적용: git apply {})", count, patch_file.display(), patch_file.display());
+    Ok(())
+}
+
+/// --cache-dir: (규칙 해시)/(파일 경로 + 내용 해시).json 에 파일별 보고서를 저장하는 캐시
+struct Cache {
+    /// 현재 규칙/옵션 조합 전용 디렉터리 (`<cache-dir>/<규칙 해시>`)
+    dir: PathBuf,
+}
+
+impl Cache {
+    /// 규칙과 변환 옵션의 해시로 캐시 디렉터리를 열고, 다른 규칙으로 만든 이전 캐시는 삭제합니다.
+    fn open(cache_dir: &Path, rules: &[ModernizerRule], args: &RunArgs) -> Result<Self> {
+        let rules_json = serde_json::to_string(rules).with_context(|| "Failed to serialize rules for cache key.")?;
+        let options = format!(
+            "{}|{}|{}|{}|{}|{:?}|{}|{}|{}|{}|{}|{:?}|{:?}",
+            env!("CARGO_PKG_VERSION"),
+            args.max_iterations,
+            args.force_reprint,
+            args.scoped_maybeuninit,
+            args.no_annotations,
+            args.annotation_style,
+            args.fail_on_unparseable_output,
+            args.doc_tests,
+            args.by_function,
+            args.reformat,
+            args.format,
+            args.line_endings,
+            args.explain
+        );
+        // 플러그인이 바뀌면 결과도 바뀌므로 플러그인 파일 내용도 키에 포함
+        let mut plugins = Vec::new();
+        if let Some(dir) = &args.plugins_dir {
+            let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+                .with_context(|| format!("Failed to read plugins directory: {}", dir.display()))?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.is_file())
+                .collect();
+            paths.sort();
+            for path in paths {
+                plugins.extend_from_slice(path.to_string_lossy().as_bytes());
+                plugins.extend(fs::read(&path).with_context(|| format!("Failed to read plugin: {}", path.display()))?);
+            }
+        }
+        // --use-baseline: 기준선이 바뀌면 가려지는 변환도 바뀜
+        let baseline = match &args.baseline_findings {
+            Some(baseline) => serde_json::to_string(&**baseline).with_context(|| "Failed to serialize baseline for cache key.")?,
+            None => String::new(),
+        };
+        let rules_hash = sha256_hex(&[options.as_bytes(), rules_json.as_bytes(), &plugins, baseline.as_bytes()]);
+
+        if let Ok(entries) = fs::read_dir(cache_dir) {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let is_stale = name != rules_hash && name.len() == 64 && name.chars().all(|c| c.is_ascii_hexdigit());
+                if is_stale && entry.path().is_dir() {
+                    log!("🗑️ 규칙이 바뀌어 이전 캐시를 삭제합니다: {}", entry.path().display());
+                    if let Err(err) = fs::remove_dir_all(entry.path()) {
+                        log!("⚠️ 캐시 삭제 실패: {} ({})", entry.path().display(), err);
+                    }
+                }
+            }
+        }
+
+        let dir = cache_dir.join(rules_hash);
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create cache directory: {}", dir.display()))?;
+        Ok(Cache { dir })
+    }
+
+    /// 캐시된 결과가 있으면 재사용하고, 없으면 변환합니다.
+    /// 변경 사항이 없었던 파일의 결과만 재사용합니다. (변경이 있는 파일은 dry-run이면 결과 코드를 출력해야 하고,
+    /// 아니면 파일을 써야 하므로 다시 변환)
+    fn transform_file(&self, path: &Path, rules: &[ModernizerRule], args: &RunArgs) -> Result<PendingOutput> {
+        let contents = fs::read(path).with_context(|| format!("Failed to read input file: {}", path.display()))?;
+        let key = sha256_hex(&[path.to_string_lossy().as_bytes(), b"\0", &contents]);
+
+        if let Some(report) = self.load(&key) {
+            if report.changes.is_empty() {
+                log!("[CACHE] {}: 이전 결과 재사용 ({}건)", path.display(), report.changes.len());
+                return Ok(PendingOutput {
+                    report,
+                    path: path.to_path_buf(),
+                    code: None,
+                    cache_key: None,
+                    audit: Vec::new(),
+                    diff: String::new(),
+                });
+            }
+        }
+
+        let mut output = transform_file(path, rules.to_vec(), args, args.dry_run)?;
+        output.cache_key = Some(key);
+        Ok(output)
+    }
+
+    fn load(&self, key: &str) -> Option<FileReport> {
+        let json = fs::read_to_string(self.dir.join(format!("{}.json", key))).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    /// 재사용할 수 있는 결과(변경 없음)만 저장합니다. 캐시 쓰기 실패는 경고만 합니다.
+    fn store(&self, output: &PendingOutput) {
+        let Some(key) = &output.cache_key else { return };
+        if !output.report.changes.is_empty() {
+            return;
+        }
+        let path = self.dir.join(format!("{}.json", key));
+        let result = serde_json::to_string(&output.report)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| fs::write(&path, json).map_err(anyhow::Error::from));
+        if let Err(err) = result {
+            log!("⚠️ 캐시 저장 실패: {} ({})", path.display(), err);
+        }
+    }
+}
+
+/// 여러 바이트 조각을 이어 붙인 SHA-256 해시를 16진수 문자열로 반환합니다.
+fn sha256_hex(parts: &[&[u8]]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// 여러 파일을 처리할 때 진행 표시줄을 띄웁니다.
+/// stderr가 터미널이 아니거나 --quiet, --report-format json이면 표시하지 않습니다.
+fn start_progress(total: usize, args: &RunArgs) -> Option<ProgressBar> {
+    if total < 2 || args.quiet || args.interactive || args.report_format != ReportFormat::Text || !std::io::stderr().is_terminal() {
+        return None;
+    }
+
+    let bar = ProgressBar::new(total as u64);
+    bar.set_style(
+        ProgressStyle::with_template("{bar:40.cyan/blue} {pos}/{len} [{elapsed_precise}] {wide_msg}")
+            .unwrap_or_else(|_| ProgressStyle::default_bar()),
+    );
+    if let Ok(mut progress) = PROGRESS.lock() {
+        *progress = Some(bar.clone());
+    }
+    Some(bar)
+}
+
+/// 진행 표시줄을 지우고 로그 출력을 원래대로 되돌립니다.
+fn finish_progress() {
+    if let Some(bar) = PROGRESS.lock().ok().and_then(|mut progress| progress.take()) {
+        bar.finish_and_clear();
+    }
+}
+
+/// 쓰기 대기 중인 변환 결과를 순서대로 저장합니다.
+/// 중간에 쓰기가 실패하면 이미 쓴 파일을 원래 내용으로 되돌립니다. (새로 만든 파일은 삭제)
+fn write_outputs(outputs: &[PendingOutput]) -> Result<()> {
+    let mut backups: Vec<(&Path, Option<Vec<u8>>)> = Vec::new();
+
+    for output in outputs {
+        let Some(code) = &output.code else { continue };
+        let path = output.path.as_path();
+        let original = fs::read(path).ok();
+
+        // --output 디렉터리에 입력 디렉터리 구조를 그대로 만듦
+        let written = match path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            Some(parent) => fs::create_dir_all(parent).and_then(|()| fs::write(path, code)),
+            None => fs::write(path, code),
+        };
+        if let Err(err) = written {
+            let restored = backups.len();
+            for (written, original) in backups.into_iter().rev() {
+                let restore = match original {
+                    Some(bytes) => fs::write(written, bytes),
+                    None => fs::remove_file(written),
+                };
+                if let Err(restore_err) = restore {
+                    log!("❌ 복구 실패: {} ({})", written.display(), restore_err);
+                }
+            }
+            return Err(err).with_context(|| {
+                format!("Failed to write output file: {} (rolled back {} earlier write(s))", path.display(), restored)
+            });
+        }
+        backups.push((path, original));
+
+        log!("\n✅ 변환 완료! 파일 저장됨.");
+        log!("→ {}", path.display());
+    }
+    Ok(())
+}
+
+/// --verify: 컴파일 검사 단위
+#[derive(Clone, PartialEq, Eq)]
+enum CheckTarget {
+    /// 제자리 변환한 파일이 속한 가장 가까운 Cargo.toml (`cargo check --all-targets`)
+    Cargo(PathBuf),
+    /// Cargo 패키지 밖의 파일이거나 다른 경로에 쓴 파일 (쓰기 전에는 입력, 쓴 뒤에는 출력을 `rustc --emit=metadata`)
+    Rustc { input: PathBuf, output: PathBuf },
+}
+
+/// 검사 단위별 쓰기 전 상태
+struct VerifyCheck {
+    target: CheckTarget,
+    /// 이 단위에 속한 출력의 인덱스와 쓰기 전 내용 (새로 만드는 파일이면 None)
+    outputs: Vec<(usize, Option<Vec<u8>>)>,
+    baseline: Vec<CompileError>,
+}
+
+/// 컴파일 오류 하나 (`file`: primary span의 파일, 정규화된 경로)
+struct CompileError {
+    file: Option<PathBuf>,
+    rendered: String,
+}
+
+/// --verify: 쓸 파일을 검사 단위로 묶고, 쓰기 전의 컴파일 오류를 구합니다. (Markdown은 컴파일 대상이 아니므로 제외)
+fn verify_baseline(outputs: &[PendingOutput]) -> Result<Vec<VerifyCheck>> {
+    let mut checks: Vec<VerifyCheck> = Vec::new();
+    for (index, output) in outputs.iter().enumerate() {
+        if output.code.is_none() || is_markdown(&output.path) {
+            continue;
+        }
+        let input = &output.report.path;
+        let manifest = (input == &output.path)
+            .then(|| fs::canonicalize(input).ok())
+            .flatten()
+            .and_then(|path| path.ancestors().skip(1).map(|dir| dir.join("Cargo.toml")).find(|manifest| manifest.is_file()));
+        let target = match manifest {
+            Some(manifest) => CheckTarget::Cargo(manifest),
+            None => CheckTarget::Rustc { input: input.clone(), output: output.path.clone() },
+        };
+        let original = fs::read(&output.path).ok();
+        match checks.iter_mut().find(|check| check.target == target) {
+            Some(check) => check.outputs.push((index, original)),
+            None => checks.push(VerifyCheck { target, outputs: vec![(index, original)], baseline: Vec::new() }),
+        }
+    }
+    for check in &mut checks {
+        check.baseline = compile_errors(&check.target, false)?;
+    }
+    Ok(checks)
+}
+
+/// --verify: 쓴 파일을 다시 컴파일해 새 오류가 생긴 파일을 되돌리고 요약을 출력합니다.
+/// Cargo 패키지에서는 오류가 늘어난 파일만 되돌리며, 늘어난 오류가 변환한 파일 밖에 있으면 그 패키지의 파일을 모두 되돌립니다.
+fn verify_outputs(checks: Vec<VerifyCheck>, outputs: &mut [PendingOutput]) -> Result<()> {
+    let file_count: usize = checks.iter().map(|check| check.outputs.len()).sum();
+    let mut rolled_back: Vec<(PathBuf, Vec<String>)> = Vec::new();
+    for check in checks {
+        let errors = compile_errors(&check.target, true)?;
+        let is_new = |error: &&CompileError| !check.baseline.iter().any(|old| old.rendered == error.rendered);
+        let count = |errors: &[CompileError], file: Option<&Path>| {
+            errors.iter().filter(|error| file.is_none_or(|file| error.file.as_deref() == Some(file))).count()
+        };
+
+        let mut failed: Vec<(usize, Vec<String>)> = Vec::new();
+        for (index, _) in &check.outputs {
+            let file = match &check.target {
+                CheckTarget::Cargo(_) => fs::canonicalize(&outputs[*index].path).ok(),
+                CheckTarget::Rustc { .. } => None,
+            };
+            if count(&errors, file.as_deref()) > count(&check.baseline, file.as_deref()) {
+                let diagnostics = errors
+                    .iter()
+                    .filter(|error| file.is_none() || error.file == file)
+                    .filter(is_new)
+                    .map(|error| error.rendered.clone())
+                    .collect();
+                failed.push((*index, diagnostics));
+            }
+        }
+        if failed.is_empty() && errors.len() > check.baseline.len() {
+            let diagnostics: Vec<String> = errors.iter().filter(is_new).map(|error| error.rendered.clone()).collect();
+            failed = check.outputs.iter().map(|(index, _)| (*index, diagnostics.clone())).collect();
+        }
+
+        for (index, diagnostics) in failed {
+            let original = check.outputs.iter().find(|(output, _)| *output == index).and_then(|(_, original)| original.as_ref());
+            let output = &mut outputs[index];
+            let restored = match original {
+                Some(bytes) => fs::write(&output.path, bytes),
+                None => fs::remove_file(&output.path),
+            };
+            restored.with_context(|| format!("Failed to roll back {}", output.path.display()))?;
+            // 되돌린 파일은 변경되지 않은 것으로 보고 (캐시에도 저장하지 않음)
+            let report = &mut output.report;
+            report.changes.clear();
+            report.counts.clear();
+            report.risk_counts.clear();
+            report.functions.clear();
+            output.code = None;
+            output.cache_key = None;
+            output.diff.clear();
+            rolled_back.push((output.path.clone(), diagnostics));
+        }
+    }
+
+    if rolled_back.is_empty() {
+        log!("\n🔍 --verify: 변환한 파일 {}개 모두 새 컴파일 오류가 없습니다.", file_count);
+        return Ok(());
+    }
+    log!("\n🔍 --verify: 변환한 파일 {}개 중 {}개가 새 컴파일 오류를 만들어 원래 내용으로 되돌렸습니다.", file_count, rolled_back.len());
+    for (path, diagnostics) in rolled_back {
+        log!("  ↩️ {} (새 오류 {}개)", path.display(), diagnostics.len());
+        for diagnostic in diagnostics {
+            for line in diagnostic.lines() {
+                log!("      {}", line);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 검사 단위를 컴파일해 오류 목록을 구합니다. (`written`: 파일을 쓴 뒤의 검사인지, Rustc 단위의 검사 파일 선택)
+fn compile_errors(target: &CheckTarget, written: bool) -> Result<Vec<CompileError>> {
+    match target {
+        CheckTarget::Cargo(manifest) => {
+            let output = process::Command::new("cargo")
+                .args(["check", "--all-targets", "--quiet", "--message-format=json", "--manifest-path"])
+                .arg(manifest)
+                .output()
+                .with_context(|| "Failed to run `cargo check` (is cargo installed and on PATH?)")?;
+            // 진단의 파일 이름은 워크스페이스 루트 기준이므로 매니페스트 디렉터리부터 상위로 올라가며 찾음
+            let dir = manifest.parent().unwrap_or(Path::new("."));
+            let resolve = |name: &str| dir.ancestors().map(|dir| dir.join(name)).find(|path| path.is_file()).and_then(|path| fs::canonicalize(path).ok());
+            Ok(String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+                .filter(|message| message["reason"] == "compiler-message")
+                .filter_map(|message| compile_error(&message["message"], &resolve))
+                .collect())
+        }
+        CheckTarget::Rustc { input, output } => {
+            let file = if written { output } else { input };
+            let metadata = std::env::temp_dir().join(format!("rust_modernizer-verify-{}.rmeta", process::id()));
+            let edition = manifest_edition(file).unwrap_or_else(|| "2021".to_string());
+            let result = process::Command::new("rustc")
+                .args(["--crate-type", "lib", "--emit=metadata", "--error-format=json", "--edition", &edition, "-o"])
+                .arg(&metadata)
+                .arg(file)
+                .output()
+                .with_context(|| "Failed to run `rustc` (is it installed and on PATH?)")?;
+            let _ = fs::remove_file(&metadata);
+            let resolve = |name: &str| fs::canonicalize(name).ok();
+            Ok(String::from_utf8_lossy(&result.stderr)
+                .lines()
+                .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+                .filter_map(|message| compile_error(&message, &resolve))
+                .collect())
+        }
+    }
+}
+
+/// rustc JSON 진단에서 위치가 있는 오류만 골라냅니다. (`aborting due to ...` 같은 요약 메시지는 제외)
+fn compile_error(message: &serde_json::Value, resolve: &dyn Fn(&str) -> Option<PathBuf>) -> Option<CompileError> {
+    if message["level"] != "error" {
+        return None;
+    }
+    let span = message["spans"].as_array()?.iter().find(|span| span["is_primary"] == true)?;
+    Some(CompileError {
+        file: span["file_name"].as_str().and_then(resolve),
+        rendered: message["rendered"].as_str()?.trim_end().to_string(),
+    })
+}
+
+/// 실행 결과 보고서를 --report-format에 따라 출력합니다. (text: 진행 로그와 같은 곳, 그 외: stdout)
+fn emit_report(report: &Report, rules: &[ModernizerRule], args: &RunArgs) -> Result<()> {
+    let rendered = report.render(args.report_format, rules)?;
+    match args.report_format {
+        // 보고서는 --quiet여도 출력 (기계 판독용/HTML 보고서는 stdout)
+        ReportFormat::Json | ReportFormat::Sarif | ReportFormat::Html => println!("{}", rendered),
+        ReportFormat::Text => print_output(rendered.trim_end()),
+    }
+    Ok(())
+}
+
+/// 단일 파일에 규칙을 적용하고 결과를 출력/저장합니다.
+fn modernize_file(input: &Path, rules: Vec<ModernizerRule>, args: &RunArgs, dry_run: bool) -> Result<FileReport> {
+    let output = transform_file(input, rules, args, dry_run)?;
+    write_outputs(std::slice::from_ref(&output))?;
+    Ok(output.report)
+}
+
+/// 입력이 디렉터리 하나이면 그 경로를 반환합니다. (--output을 출력 디렉터리로 사용하며, 파일은 이 디렉터리 기준 상대 경로로 저장)
+fn output_root(args: &RunArgs) -> Option<&Path> {
+    match args.inputs.as_slice() {
+        [input] if input.is_dir() => Some(input),
+        _ => None,
+    }
+}
+
+/// 단일 파일에 규칙을 적용하고 검증까지 마친 결과를 반환합니다. (파일은 쓰지 않음, dry-run이면 결과 출력)
+fn transform_file(input: &Path, rules: Vec<ModernizerRule>, args: &RunArgs, dry_run: bool) -> Result<PendingOutput> {
+    // 3. 출력 경로 결정
+    let output_path = match (&args.output, output_root(args)) {
+        (Some(dir), Some(root)) => dir.join(input.strip_prefix(root).unwrap_or(input)),
+        (Some(path), None) => path.clone(),
+        (None, _) if args.inplace => input.to_path_buf(),
+        (None, _) => PathBuf::from("modernized_output.rs"),
+    };
+    
+    // ... (CLI 출력 유지)
+    if dry_run {
+        log!("\n🚨 DRY-RUN MODE: 파일 쓰기 작업을 건너뜁니다.");
+    }
+    log!("============================================");
+    log!("    Rust Legacy → Modern Migration Tool");
+    log!("============================================\n");
+    log!("📄 입력 파일: {}", input.display());
+    if !dry_run {
+        log!("📁 출력 파일: {}", output_path.display());
+    }
+
+    // 4~7. 파일 읽기, AST 변환, 코드 재구성 (modernizer-core)
+    let mut modernizer = Modernizer::new(rules)
+        .with_options(args.transform_options())
+        .with_handlers(plugin_handlers(args)?);
+    if let Some(baseline) = &args.baseline_findings {
+        modernizer = modernizer.with_baseline(baseline, input);
+    }
+    let result = modernizer.modernize_file(input)?;
+    let (report, audit) = (result.report, result.audit);
+
+    // 7-1. 재파싱 검증: 템플릿 조합이 잘못된 코드를 만들었는지 쓰기 전에 확인
+    if let Some(err) = result.reparse_error.as_ref().filter(|_| !dry_run) {
+        return Err(ValidationError(format!(
+            "Modernized output failed to re-parse; refusing to write {}: {}", output_path.display(), err
+        ))
+        .into());
+    }
+
+    let Some(modernized_code) = result.code else {
+        return Ok(PendingOutput { report, path: output_path, code: None, cache_key: None, audit, diff: String::new() });
+    };
+    let diff = if args.diff_file.is_some() || args.patch.is_some() || args.diff {
+        diff::hunks(&result.original, &modernized_code)
+    } else {
+        String::new()
+    };
+
+    // 6. 변경 사항 확인 (--force-reprint로 재출력한 코드는 변환 결과 대신 diff만 출력)
+    if !result.changed {
+        if dry_run && args.diff {
+            print_output(&format!("\n{}{}", diff_header(input), diff.trim_end()));
+        }
+        let code = (!dry_run).then_some(modernized_code);
+        return Ok(PendingOutput { report, path: output_path, code, cache_key: None, audit, diff });
+    }
+
+    if dry_run && args.patch.is_some() {
+        log!("\n🩹 변경 내용은 --patch 파일에 기록됩니다.");
+        return Ok(PendingOutput { report, path: output_path, code: None, cache_key: None, audit, diff });
+    }
+    if dry_run && args.diff {
+        print_output(&format!("\n{}{}", diff_header(input), diff.trim_end()));
+        return Ok(PendingOutput { report, path: output_path, code: None, cache_key: None, audit, diff });
+    }
+    if dry_run && args.check {
+        return Ok(PendingOutput { report, path: output_path, code: None, cache_key: None, audit, diff });
+    }
+    if dry_run {
+        print_output("\n📄 Dry Run 결과 코드 (파일 저장 안 함):");
+        print_output("--------------------------------------------");
+        print_output(&modernized_code);
+        print_output("--------------------------------------------");
+        return Ok(PendingOutput { report, path: output_path, code: None, cache_key: None, audit, diff });
+    }
+
+    Ok(PendingOutput { report, path: output_path, code: Some(modernized_code), cache_key: None, audit, diff })
+}
+
+/// `--watch`: 입력 경로와 규칙 파일의 변경을 감지하여 변환을 반복 실행합니다.
+fn watch(args: RunArgs) -> Result<()> {
+    // 저장할 때마다 소스가 덮어써지는 일을 막기 위해 명시적으로 요청한 경우에만 파일을 씁니다.
+    let dry_run = args.dry_run || (!args.inplace && args.output.is_none());
+    let input = match args.inputs.as_slice() {
+        [input] => input.clone(),
+        _ => bail!("--watch supports a single input path (file or directory)"),
+    };
+    let watch_dir = input.is_dir();
+    let filter = FileFilter::new(&args)?;
+    if watch_dir && args.output.is_some() {
+        bail!("--output cannot be used when watching a directory (use --inplace or dry-run)");
+    }
+
+    let mut rules = filter_rules(args.rule_args.load_rules()?, &args);
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)
+        .with_context(|| "Failed to start file watcher.")?;
+
+    // 에디터의 "임시 파일 저장 후 rename"에도 감시가 끊기지 않도록 파일은 상위 디렉터리를 감시합니다.
+    let rules_files = args.rule_args.user_rules_files();
+    for path in std::iter::once(&input).chain(&rules_files) {
+        let (target, mode) = if path.is_dir() {
+            (path.as_path(), RecursiveMode::Recursive)
+        } else {
+            (watch_parent(path), RecursiveMode::NonRecursive)
+        };
+        watcher
+            .watch(target, mode)
+            .with_context(|| format!("Failed to watch path: {}", target.display()))?;
+    }
+
+    log!("\n👀 감시 모드: {} (종료: Ctrl+C)", input.display());
+    if dry_run {
+        log!("   --inplace 또는 --output을 지정하지 않았으므로 파일을 저장하지 않습니다.");
+    }
+    if !watch_dir {
+        report_watch_error(
+            modernize_file(&input, rules.clone(), &args, dry_run)
+                .and_then(|report| emit_report(&Report::new(vec![report], dry_run), &rules, &args)),
+        );
+    }
+
+    // 첫 이벤트를 기다린 뒤, WATCH_DEBOUNCE 동안 이어지는 이벤트를 한 번에 모아 처리합니다.
+    while let Ok(first) = rx.recv() {
+        let mut changed_paths: Vec<PathBuf> = Vec::new();
+        let mut pending = Some(first);
+        while let Some(result) = pending {
+            match result {
+                // 읽기(Access)나 메타데이터 변경은 무시: 변환기가 파일을 읽는 것만으로 재실행되는 것을 방지
+                Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Remove(_))
+                    || matches!(event.kind, EventKind::Modify(kind) if !matches!(kind, ModifyKind::Metadata(_))) =>
+                {
+                    changed_paths.extend(event.paths);
+                }
+                Ok(_) => {}
+                Err(err) => log!("⚠️ 감시 오류: {}", err),
+            }
+            pending = rx.recv_timeout(WATCH_DEBOUNCE).ok();
+        }
+        changed_paths.sort();
+        changed_paths.dedup();
+
+        // 규칙 디렉터리를 지정했다면 그 안의 파일이 바뀐 경우도 포함
+        let rules_changed = changed_paths.iter().any(|path| {
+            rules_files.iter().any(|rules_file| {
+                is_same_path(path, rules_file)
+                    || (rules_file.is_dir() && is_same_path(watch_parent(path), rules_file))
+            })
+        });
+        if rules_changed {
+            log!("\n🔄 규칙 파일 변경 감지");
+            match args.rule_args.load_rules() {
+                Ok(new_rules) => rules = filter_rules(new_rules, &args),
+                Err(err) => log!("❌ 규칙을 다시 읽지 못했습니다. 이전 규칙을 유지합니다: {:#}", err),
+            }
+        }
+
+        if watch_dir {
+            // 디렉터리 감시: 필터를 통과한 변경된 .rs 파일만 다시 변환
+            let changed_files = changed_paths.iter().filter(|path| {
+                let relative = path.strip_prefix(&input).unwrap_or(path);
+                filter.accepts_file(path, relative) && path.is_file()
+            });
+            for path in changed_files {
+                log!("\n🔄 변경 감지: {}", path.display());
+                report_watch_error(
+                    modernize_file(path, rules.clone(), &args, dry_run)
+                        .and_then(|report| emit_report(&Report::new(vec![report], dry_run), &rules, &args)),
+                );
+            }
+        } else if rules_changed || changed_paths.iter().any(|path| is_same_path(path, &input)) {
+            log!("\n🔄 변경 감지: {}", input.display());
+            report_watch_error(
+                modernize_file(&input, rules.clone(), &args, dry_run)
+                    .and_then(|report| emit_report(&Report::new(vec![report], dry_run), &rules, &args)),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// 파일 경로의 상위 디렉터리 (상대 경로 "foo.rs"는 현재 디렉터리)
+fn watch_parent(path: &Path) -> &Path {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+/// 감시 이벤트 경로(절대 경로)와 CLI 인자 경로(상대 경로일 수 있음)를 비교합니다.
+fn is_same_path(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// 감시 모드에서는 오류가 나도 종료하지 않고 다음 저장을 기다립니다.
+fn report_watch_error(result: Result<()>) {
+    if let Err(err) = result {
+        log!("\n❌ {:#}", err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(ids: &[&str]) -> Vec<ModernizerRule> {
+        default_rules().unwrap().into_iter().filter(|rule| ids.contains(&rule.id.as_str())).collect()
+    }
+
+    fn file_report(path: &str, rules: &[ModernizerRule], source: &str) -> FileReport {
+        let mut report = Modernizer::new(rules.to_vec()).modernize_str(source).unwrap().report;
+        report.path = PathBuf::from(path);
+        report
+    }
+
+    fn member(name: &str, dir: &str) -> WorkspaceMember {
+        WorkspaceMember {
+            name: name.to_string(),
+            edition: "2021".to_string(),
+            dir: PathBuf::from(dir),
+            manifest: Path::new(dir).join("Cargo.toml"),
+            targets: Vec::new(),
+        }
+    }
+
+    /// `try!` 두 건(info, low)인 파일, `mem::uninitialized` 한 건(error, high)인 파일, 변경 없는 파일의 dry-run 보고서
+    fn sample() -> (Report, Vec<ModernizerRule>) {
+        let rules = rules(&["try_macro_to_question_mark", "mem_uninitialized_to_maybeuninit", "iter_cloned_to_copied"]);
+        let files = vec![
+            file_report("crates/a/src/lib.rs", &rules, "fn f() -> Result<u32, E> {\n    let x = try!(g());\n    let y = try!(h());\n    Ok(x + y)\n}\n"),
+            file_report("crates/b/src/lib.rs", &rules, "fn f() -> Foo {\n    unsafe { std::mem::uninitialized() }\n}\n"),
+            file_report("crates/b/src/empty.rs", &rules, "fn f() {}\n"),
+        ];
+        (Report::new(files, true), rules)
+    }
+
+    #[test]
+    fn new_sums_counts_across_files() {
+        let (mut report, _) = sample();
+        assert!(report.changed());
+        assert_eq!(report.counts.get("try_macro_to_question_mark"), Some(&2));
+        assert_eq!(report.counts.get("mem_uninitialized_to_maybeuninit"), Some(&1));
+        assert_eq!(report.risk_counts.get(&Risk::Low), Some(&2));
+        assert_eq!(report.risk_counts.get(&Risk::High), Some(&1));
+        report.files.retain(|file| file.changes.is_empty());
+        assert!(!report.changed());
+    }
+
+    #[test]
+    fn findings_at_counts_changes_at_or_above_threshold() {
+        let (report, _) = sample();
+        assert_eq!(report.findings_at(Severity::Info), 3);
+        assert_eq!(report.findings_at(Severity::Warning), 1);
+        assert_eq!(report.findings_at(Severity::Error), 1);
+    }
+
+    #[test]
+    fn unused_rules_skips_disabled_rules() {
+        let (report, mut rules) = sample();
+        assert_eq!(report.unused_rules(&rules), ["iter_cloned_to_copied"]);
+        for rule in &mut rules {
+            rule.disabled_by = Some("--skip".to_string());
+        }
+        assert!(report.unused_rules(&rules).is_empty());
+    }
+
+    #[test]
+    fn summarize_crates_assigns_files_to_the_nearest_member() {
+        let (mut report, _) = sample();
+        report.summarize_crates(&[member("root", "crates"), member("a", "crates/a"), member("b", "crates/b"), member("c", "crates/c")]);
+        let summary: Vec<(&str, usize, u32)> =
+            report.crates.iter().map(|summary| (summary.name.as_str(), summary.files, summary.counts.values().sum())).collect();
+        assert_eq!(summary, [("root", 0, 0), ("a", 1, 2), ("b", 2, 1), ("c", 0, 0)]);
+    }
+
+    #[test]
+    fn render_text_lists_changes_and_totals() {
+        let (mut report, rules) = sample();
+        report.summarize_crates(&[member("a", "crates/a"), member("c", "crates/c")]);
+        report.skipped_rules.insert("format_to_string".to_string(), "--only".to_string());
+        report.skipped_rules.insert("derive_order".to_string(), "--only".to_string());
+        let text = report.render(ReportFormat::Text, &rules).unwrap();
+        assert!(text.contains("[MOD] ✅ try_macro_to_question_mark applied (crates/a/src/lib.rs:2:13)"), "{}", text);
+        assert!(text.contains("🚨 HIGH RISK: mem_uninitialized_to_maybeuninit (crates/b/src/lib.rs:2:14)"), "{}", text);
+        assert!(text.contains("📊 전체 (파일 3개):\n  - 1 건 (mem_uninitialized_to_maybeuninit)\n  - 2 건 (try_macro_to_question_mark)\n  위험도: low 2, high 1\n"), "{}", text);
+        assert!(text.contains("  ⚠️ a: 2건 (파일 1개) — try_macro_to_question_mark 2\n  ✅ c: 레거시 패턴 없음 (파일 0개)\n"), "{}", text);
+        assert!(text.contains("⏭️ 건너뛴 규칙 2개 (--only): derive_order, format_to_string"), "{}", text);
+    }
+
+    #[test]
+    fn render_json_serializes_the_report() {
+        let (report, rules) = sample();
+        let json: serde_json::Value = serde_json::from_str(&report.render(ReportFormat::Json, &rules).unwrap()).unwrap();
+        assert_eq!(json["dry_run"], true);
+        assert_eq!(json["counts"]["try_macro_to_question_mark"], 2);
+        assert_eq!(json["risk_counts"]["high"], 1);
+        assert_eq!(json["files"].as_array().unwrap().len(), 3);
+        let change = &json["files"][1]["changes"][0];
+        assert_eq!(change["rule_id"], "mem_uninitialized_to_maybeuninit");
+        assert_eq!(change["location"]["line"], 2);
+        // 비어 있는 선택 항목은 생략
+        assert!(json.get("crates").is_none() && json.get("skipped_rules").is_none(), "{}", json);
+    }
+
+    #[test]
+    fn render_sarif_maps_changes_to_results() {
+        let (report, mut rules) = sample();
+        rules.iter_mut().find(|rule| rule.id == "iter_cloned_to_copied").unwrap().disabled_by = Some("--skip".to_string());
+        let sarif: serde_json::Value = serde_json::from_str(&report.render(ReportFormat::Sarif, &rules).unwrap()).unwrap();
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        let driver_rules: Vec<&str> = run["tool"]["driver"]["rules"].as_array().unwrap().iter().map(|rule| rule["id"].as_str().unwrap()).collect();
+        assert!(driver_rules.len() == 2 && !driver_rules.contains(&"iter_cloned_to_copied"), "{:?}", driver_rules);
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        let uninit = results.iter().find(|result| result["ruleId"] == "mem_uninitialized_to_maybeuninit").unwrap();
+        assert_eq!(uninit["level"], "error");
+        assert_eq!(driver_rules[uninit["ruleIndex"].as_u64().unwrap() as usize], "mem_uninitialized_to_maybeuninit");
+        let location = &uninit["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "crates/b/src/lib.rs");
+        assert_eq!(location["region"]["startLine"], 2);
+        let try_result = results.iter().find(|result| result["ruleId"] == "try_macro_to_question_mark").unwrap();
+        assert_eq!(try_result["level"], "note");
+    }
+}
@@ -0,0 +1,41 @@
+//! `rust_modernizer` 바이너리를 직접 실행하여 명령줄 동작을 확인합니다.
+use std::{fs, path::PathBuf, process::Command};
+
+/// 테스트마다 따로 쓰는 임시 디렉터리에 `source`를 `a.rs`로 저장하고 그 경로를 반환합니다.
+fn write_input(name: &str, source: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rust_modernizer-cli-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("a.rs");
+    fs::write(&path, source).unwrap();
+    path
+}
+
+fn modernizer() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_rust_modernizer"))
+}
+
+const LEGACY: &str = "fn f() -> Result<u32, String> {\n    let x = try!(g());\n    Ok(x)\n}\n";
+
+#[test]
+fn quiet_dry_run_still_prints_code() {
+    let input = write_input("quiet-dry-run", LEGACY);
+    let output = modernizer().arg(&input).args(["--dry-run", "-q"]).output().unwrap();
+    let _ = fs::remove_dir_all(input.parent().unwrap());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(2), "{}", stdout);
+    assert!(stdout.contains("let x = g()?;"), "{}", stdout);
+    assert!(stdout.contains("[MOD]"), "{}", stdout);
+    // 진행 로그는 숨김
+    assert!(!stdout.contains("입력 파일"), "{}", stdout);
+}
+
+#[test]
+fn quiet_diff_still_prints_diff() {
+    let input = write_input("quiet-diff", LEGACY);
+    let output = modernizer().arg(&input).args(["--dry-run", "--diff", "-q"]).output().unwrap();
+    let _ = fs::remove_dir_all(input.parent().unwrap());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(2), "{}", stdout);
+    assert!(stdout.contains("-    let x = try!(g());"), "{}", stdout);
+    assert!(stdout.contains("+    let x = g()?;"), "{}", stdout);
+}